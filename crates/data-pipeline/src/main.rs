@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use clap::Parser;
 use serde::ser::{SerializeSeq, Serializer as _};
 use serde::{Deserialize, Serialize};
@@ -7,16 +7,46 @@ use serde_json::{
     de::Deserializer,
     ser::{PrettyFormatter, Serializer as JsonSerializer},
 };
-use std::fs::{self, File};
-use std::io::BufReader;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
 use std::path::{Path, PathBuf};
 use tokio::runtime::Runtime;
 use walkdir::WalkDir;
 
 mod postgres;
 
+/// Version stamped on every curated record; bump when the record shape changes and
+/// register the new column set in `builtin_schema_registry`.
+const SCHEMA_VERSION: i32 = 2;
+const SCHEMA_REGISTRY_FILE: &str = "schema_registry.json";
+const PARTITION_MANIFEST_FILE: &str = ".partitions.json";
+/// Bucket for records whose `timestamp` is not RFC 3339.
+const UNDATED_PARTITION: &str = "undated";
+
+/// Columns present in version 1 snapshots, written before records carried a version.
+const V1_COLUMNS: &[&str] = &[
+    "session_id",
+    "timestamp",
+    "query",
+    "verdict",
+    "requires_manual_review",
+    "math_status",
+    "math_alert_required",
+    "math_outputs",
+    "math_stdout",
+    "math_stderr",
+    "trace_path",
+    "sandbox_failure_streak",
+    "domain_label",
+    "confidence_bucket",
+    "consent_provided",
+];
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 struct SessionRecord {
+    #[serde(default = "legacy_schema_version")]
+    schema_version: i32,
     session_id: String,
     timestamp: String,
     query: String,
@@ -38,6 +68,10 @@ struct SessionRecord {
     consent_provided: Option<bool>,
 }
 
+fn legacy_schema_version() -> i32 {
+    1
+}
+
 #[derive(Parser, Debug)]
 struct Args {
     #[arg(long, default_value = "data/pipeline/raw")]
@@ -55,6 +89,248 @@ struct Args {
     /// Number of records to accumulate before flushing inserts to Postgres.
     #[arg(long, default_value_t = 1000)]
     batch_size: usize,
+    /// Skip raw files whose contents were already consolidated by a previous run.
+    #[arg(long)]
+    incremental: bool,
+    /// Manifest of processed raw files (defaults to `<output_dir>/.processed.json`).
+    #[arg(long)]
+    processed_manifest: Option<PathBuf>,
+    /// Rewrite an existing snapshot at the current schema version and exit.
+    #[arg(long)]
+    upgrade_snapshot: Option<PathBuf>,
+    /// Write one `YYYY/MM/DD/part-NNN.json` file per record date instead of a single snapshot.
+    #[arg(long)]
+    partition_by_date: bool,
+}
+
+/// Schema version -> column names present in snapshots of that version.
+type SchemaRegistry = BTreeMap<i32, Vec<String>>;
+
+fn builtin_schema_registry() -> SchemaRegistry {
+    let v1: Vec<String> = V1_COLUMNS.iter().map(|column| column.to_string()).collect();
+    let mut v2 = vec!["schema_version".to_string()];
+    v2.extend(v1.iter().cloned());
+    SchemaRegistry::from([(1, v1), (2, v2)])
+}
+
+/// Load `schema_registry.json` from `curated_dir`, falling back to the built-in versions
+/// for anything the file does not list.
+fn load_schema_registry(curated_dir: &Path) -> Result<SchemaRegistry> {
+    let mut registry = builtin_schema_registry();
+    let path = curated_dir.join(SCHEMA_REGISTRY_FILE);
+    if path.exists() {
+        let contents = fs::read(&path).with_context(|| format!("read {}", path.display()))?;
+        let stored: SchemaRegistry = serde_json::from_slice(&contents)
+            .with_context(|| format!("parse {}", path.display()))?;
+        registry.extend(stored);
+    }
+    Ok(registry)
+}
+
+fn write_schema_registry(curated_dir: &Path) -> Result<()> {
+    let mut registry = load_schema_registry(curated_dir)?;
+    registry.extend(builtin_schema_registry());
+    write_json_atomic(&curated_dir.join(SCHEMA_REGISTRY_FILE), &registry)
+}
+
+/// Read a curated snapshot of any registered schema version. Columns introduced after
+/// a record's version are filled with `null` before decoding.
+fn read_snapshot(path: &Path, registry: &SchemaRegistry) -> Result<Vec<SessionRecord>> {
+    let current = registry
+        .get(&SCHEMA_VERSION)
+        .with_context(|| format!("schema version {SCHEMA_VERSION} is not registered"))?;
+    let contents = fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    let rows: Vec<serde_json::Map<String, serde_json::Value>> =
+        serde_json::from_slice(&contents).with_context(|| format!("parse {}", path.display()))?;
+
+    rows.into_iter()
+        .map(|mut row| {
+            let version = row
+                .get("schema_version")
+                .and_then(|value| value.as_i64())
+                .unwrap_or(1) as i32;
+            if !registry.contains_key(&version) {
+                anyhow::bail!(
+                    "{} contains unknown schema version {version}",
+                    path.display()
+                );
+            }
+            row.insert("schema_version".to_string(), version.into());
+            for column in current {
+                row.entry(column.clone()).or_insert(serde_json::Value::Null);
+            }
+            serde_json::from_value(serde_json::Value::Object(row))
+                .with_context(|| format!("decode record in {}", path.display()))
+        })
+        .collect()
+}
+
+fn upgrade_snapshot(path: &Path) -> Result<()> {
+    let curated_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let registry = load_schema_registry(curated_dir)?;
+    let mut records = read_snapshot(path, &registry)?;
+    for record in &mut records {
+        record.schema_version = SCHEMA_VERSION;
+    }
+    write_json_atomic(path, &records)?;
+    write_schema_registry(curated_dir)?;
+    println!(
+        "Upgraded {} records in {} to schema version {}",
+        records.len(),
+        path.display(),
+        SCHEMA_VERSION
+    );
+    Ok(())
+}
+
+/// Raw file path (relative to `raw_dir`) -> how much of it earlier runs consumed.
+type ProcessedManifest = BTreeMap<String, ProcessedFile>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+struct ProcessedFile {
+    /// Bytes already consolidated; always ends on a line boundary.
+    offset: u64,
+    /// SHA-256 of the first `offset` bytes, used to detect files rewritten in place.
+    prefix_sha256: String,
+}
+
+fn manifest_key(raw_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(raw_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Where to resume reading `contents`: the recorded offset while the file still starts
+/// with the bytes consumed before, otherwise 0 (the file was truncated or rewritten).
+fn resume_offset(contents: &[u8], entry: Option<&ProcessedFile>) -> usize {
+    match entry {
+        Some(entry) => {
+            let offset = entry.offset as usize;
+            let unchanged =
+                offset <= contents.len() && sha256_hex(&contents[..offset]) == entry.prefix_sha256;
+            if unchanged { offset } else { 0 }
+        }
+        None => 0,
+    }
+}
+
+fn load_manifest(path: &Path) -> Result<ProcessedManifest> {
+    if !path.exists() {
+        return Ok(ProcessedManifest::new());
+    }
+    let contents = fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    serde_json::from_slice(&contents).with_context(|| format!("parse {}", path.display()))
+}
+
+fn write_manifest(path: &Path, manifest: &ProcessedManifest) -> Result<()> {
+    write_json_atomic(path, manifest)
+}
+
+fn write_json_atomic<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(value)?)
+        .with_context(|| format!("write {}", tmp_path.display()))?;
+    fs::rename(&tmp_path, path).with_context(|| format!("replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Partition file (relative to the output dir) -> completion record, written once the
+/// partition file is fully on disk.
+type PartitionManifest = BTreeMap<String, PartitionEntry>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+struct PartitionEntry {
+    records: usize,
+    completed_at: String,
+}
+
+/// `YYYY/MM/DD` directory for a record's UTC date.
+fn partition_dir(record: &SessionRecord) -> String {
+    record_time(record)
+        .map(|ts| ts.date_naive().format("%Y/%m/%d").to_string())
+        .unwrap_or_else(|| UNDATED_PARTITION.to_string())
+}
+
+/// Write records grouped by date to `<output_dir>/YYYY/MM/DD/part-NNN.json`. Incremental
+/// runs append the next free part number; full runs replace each date's parts with a
+/// single `part-000.json` so rerunning over the same input does not duplicate records.
+fn write_date_partitions(
+    output_dir: &Path,
+    records: &[SessionRecord],
+    incremental: bool,
+) -> Result<Vec<PathBuf>> {
+    let mut buckets: BTreeMap<String, Vec<&SessionRecord>> = BTreeMap::new();
+    for record in records {
+        buckets
+            .entry(partition_dir(record))
+            .or_default()
+            .push(record);
+    }
+
+    let manifest_path = output_dir.join(PARTITION_MANIFEST_FILE);
+    let mut manifest: PartitionManifest = if manifest_path.exists() {
+        let contents = fs::read(&manifest_path)
+            .with_context(|| format!("read {}", manifest_path.display()))?;
+        serde_json::from_slice(&contents)
+            .with_context(|| format!("parse {}", manifest_path.display()))?
+    } else {
+        PartitionManifest::new()
+    };
+
+    let mut written = Vec::with_capacity(buckets.len());
+    for (partition, bucket) in buckets {
+        let dir = output_dir.join(&partition);
+        let part = if incremental {
+            (0..)
+                .map(|index| format!("part-{index:03}.json"))
+                .find(|name| !dir.join(name).exists())
+                .expect("part numbers are unbounded")
+        } else {
+            "part-000.json".to_string()
+        };
+        let path = dir.join(&part);
+        write_json_atomic(&path, &bucket)?;
+        if !incremental {
+            remove_stale_parts(&dir, &part)?;
+            let prefix = format!("{partition}/");
+            manifest.retain(|key, _| !key.starts_with(&prefix));
+        }
+        manifest.insert(
+            format!("{partition}/{part}"),
+            PartitionEntry {
+                records: bucket.len(),
+                completed_at: Utc::now().to_rfc3339(),
+            },
+        );
+        write_json_atomic(&manifest_path, &manifest)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Delete every `part-NNN.json` in `dir` except `keep`.
+fn remove_stale_parts(dir: &Path, keep: &str) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("read {}", dir.display()))? {
+        let path = entry?.path();
+        let stale = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                name != keep && name.starts_with("part-") && name.ends_with(".json")
+            });
+        if stale {
+            fs::remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+        }
+    }
+    Ok(())
 }
 
 struct PostgresSink {
@@ -122,6 +398,28 @@ fn collect_jsonl_files(raw_dir: &Path) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
+/// Create a new `sessions_<timestamp>.json` snapshot, adding a `_N` suffix when a run in
+/// the same millisecond already claimed the name so earlier snapshots are never overwritten.
+fn create_snapshot_file(output_dir: &Path) -> Result<(PathBuf, File)> {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%S%3f").to_string();
+    let mut attempt = 0u32;
+    loop {
+        let name = match attempt {
+            0 => format!("sessions_{stamp}.json"),
+            n => format!("sessions_{stamp}_{n}.json"),
+        };
+        let path = output_dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("failed to create snapshot {}", path.display()));
+            }
+        }
+    }
+}
+
 fn copy_alias(snapshot_path: &Path, alias_dir: &Path, alias_name: &str) -> Result<()> {
     let alias_path = alias_dir.join(alias_name);
     if alias_path.exists() {
@@ -132,6 +430,21 @@ fn copy_alias(snapshot_path: &Path, alias_dir: &Path, alias_name: &str) -> Resul
     Ok(())
 }
 
+/// Fold an incremental snapshot into the alias. Incremental snapshots only hold records
+/// from newly appended raw lines, so copying one over the alias would drop earlier records.
+fn merge_alias(snapshot_path: &Path, alias_dir: &Path, alias_name: &str) -> Result<()> {
+    let alias_path = alias_dir.join(alias_name);
+    let registry = load_schema_registry(alias_dir)?;
+    let mut merged = if alias_path.exists() {
+        read_snapshot(&alias_path, &registry)?
+    } else {
+        Vec::new()
+    };
+    merged.extend(read_snapshot(snapshot_path, &registry)?);
+    let (merged, _) = deduplicate_records(merged);
+    write_json_atomic(&alias_path, &merged)
+}
+
 fn assign_taxonomy(record: &mut SessionRecord) {
     let query_lower = record.query.to_lowercase();
     if record.domain_label.is_none() {
@@ -191,7 +504,63 @@ fn prune_raw(raw_dir: &Path, retain_days: i64) -> Result<()> {
     Ok(())
 }
 
-fn run(args: Args) -> Result<()> {
+/// Outcome of collapsing records that share a `session_id`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct DedupeReport {
+    original_count: usize,
+    deduplicated_count: usize,
+    /// One entry per dropped record, so a session seen three times appears twice.
+    removed_ids: Vec<String>,
+}
+
+fn record_time(record: &SessionRecord) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&record.timestamp)
+        .ok()
+        .map(|ts| ts.with_timezone(&Utc))
+}
+
+/// Keep only the latest record per `session_id` (ties go to the record read last),
+/// preserving the position where each session first appeared.
+fn deduplicate_records(records: Vec<SessionRecord>) -> (Vec<SessionRecord>, DedupeReport) {
+    let original_count = records.len();
+    let mut kept: Vec<SessionRecord> = Vec::with_capacity(records.len());
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut removed_ids = Vec::new();
+
+    for record in records {
+        match index.get(&record.session_id) {
+            Some(&slot) => {
+                let existing = &kept[slot];
+                let newer = match (record_time(&record), record_time(existing)) {
+                    (Some(candidate), Some(current)) => candidate >= current,
+                    _ => record.timestamp >= existing.timestamp,
+                };
+                removed_ids.push(record.session_id.clone());
+                if newer {
+                    kept[slot] = record;
+                }
+            }
+            None => {
+                index.insert(record.session_id.clone(), kept.len());
+                kept.push(record);
+            }
+        }
+    }
+
+    let report = DedupeReport {
+        original_count,
+        deduplicated_count: kept.len(),
+        removed_ids,
+    };
+    (kept, report)
+}
+
+fn run(args: Args) -> Result<DedupeReport> {
+    if let Some(path) = args.upgrade_snapshot.as_deref() {
+        upgrade_snapshot(path)?;
+        return Ok(DedupeReport::default());
+    }
+
     prune_raw(&args.raw_dir, args.retain_days)?;
 
     let files = collect_jsonl_files(&args.raw_dir)?;
@@ -200,44 +569,132 @@ fn run(args: Args) -> Result<()> {
             "No raw records found in {}; skipping",
             args.raw_dir.display()
         );
-        return Ok(());
+        return Ok(DedupeReport::default());
     }
 
-    fs::create_dir_all(&args.output_dir)?;
-    let snapshot_path = args.output_dir.join(format!(
-        "sessions_{}.json",
-        Utc::now().format("%Y%m%dT%H%M%S")
-    ));
-    let file = File::create(&snapshot_path)?;
-    let formatter = PrettyFormatter::with_indent(b"  ");
-    let mut serializer = JsonSerializer::with_formatter(file, formatter);
+    let manifest_path = args
+        .processed_manifest
+        .clone()
+        .unwrap_or_else(|| args.output_dir.join(".processed.json"));
+    let mut manifest = if args.incremental {
+        load_manifest(&manifest_path)?
+    } else {
+        ProcessedManifest::new()
+    };
+    let mut pending_offsets = Vec::new();
+    let mut records = Vec::new();
+    for path in files {
+        let contents = fs::read(&path).with_context(|| format!("read {}", path.display()))?;
+        let (start, end) = if args.incremental {
+            let key = manifest_key(&args.raw_dir, &path);
+            let start = resume_offset(&contents, manifest.get(&key));
+            // Stop at the last complete line; a partially appended record waits for the next run.
+            let end = contents[start..]
+                .iter()
+                .rposition(|&byte| byte == b'\n')
+                .map_or(start, |newline| start + newline + 1);
+            if end == start {
+                continue;
+            }
+            pending_offsets.push((
+                key,
+                ProcessedFile {
+                    offset: end as u64,
+                    prefix_sha256: sha256_hex(&contents[..end]),
+                },
+            ));
+            (start, end)
+        } else {
+            (0, contents.len())
+        };
+
+        let stream = Deserializer::from_slice(&contents[start..end]).into_iter::<SessionRecord>();
+        for record in stream {
+            let mut record =
+                record.with_context(|| format!("parse JSONL in {}", path.display()))?;
+            // Raw records may omit consent (implicitly granted); curated output always
+            // carries the resolved flag so auditors can cross-check it.
+            let consent = record.consent_provided.unwrap_or(true);
+            if !consent {
+                continue;
+            }
+            record.consent_provided = Some(consent);
+            record.schema_version = SCHEMA_VERSION;
+            assign_taxonomy(&mut record);
+            records.push(record);
+        }
+    }
+
+    if args.incremental && pending_offsets.is_empty() {
+        println!(
+            "All raw files in {} already processed; skipping",
+            args.raw_dir.display()
+        );
+        return Ok(DedupeReport::default());
+    }
+
+    let (records, report) = deduplicate_records(records);
+    if !report.removed_ids.is_empty() {
+        println!(
+            "Removed {} duplicate record(s) ({} -> {}): {}",
+            report.removed_ids.len(),
+            report.original_count,
+            report.deduplicated_count,
+            report.removed_ids.join(", ")
+        );
+    }
+    let consented_count = records.len();
 
-    let mut consented_count = 0usize;
     let mut sink = match args.postgres_url.as_deref() {
         Some(url) => Some(PostgresSink::new(url, args.batch_size)?),
         None => None,
     };
 
-    {
-        let mut seq = serializer.serialize_seq(None)?;
-        for path in files {
-            let file = File::open(&path).with_context(|| format!("open {}", path.display()))?;
-            let reader = BufReader::new(file);
-            let stream = Deserializer::from_reader(reader).into_iter::<SessionRecord>();
-
-            for record in stream {
-                let mut record =
-                    record.with_context(|| format!("parse JSONL in {}", path.display()))?;
-                if !record.consent_provided.unwrap_or(true) {
-                    continue;
-                }
-                assign_taxonomy(&mut record);
-                seq.serialize_element(&record)?;
-                consented_count += 1;
+    fs::create_dir_all(&args.output_dir)?;
+    if args.partition_by_date {
+        if consented_count == 0 {
+            println!("No consented records found; skipping output");
+        } else {
+            let partitions = write_date_partitions(&args.output_dir, &records, args.incremental)?;
+            write_schema_registry(&args.output_dir)?;
+            println!(
+                "Wrote {} records to {} date partition(s) under {}",
+                consented_count,
+                partitions.len(),
+                args.output_dir.display()
+            );
+        }
+        if let Some(mut writer) = sink {
+            for record in records {
+                writer.push(record)?;
+            }
+            let (inserted, batches) = writer.finish()?;
+            println!(
+                "Inserted {} records into Postgres across {} batch(es)",
+                inserted, batches
+            );
+        }
+        if args.incremental {
+            update_manifest(
+                &args.raw_dir,
+                &manifest_path,
+                &mut manifest,
+                pending_offsets,
+            )?;
+        }
+        return Ok(report);
+    }
 
-                if let Some(writer) = sink.as_mut() {
-                    writer.push(record.clone())?;
-                }
+    let (snapshot_path, file) = create_snapshot_file(&args.output_dir)?;
+    let formatter = PrettyFormatter::with_indent(b"  ");
+    let mut serializer = JsonSerializer::with_formatter(file, formatter);
+
+    {
+        let mut seq = serializer.serialize_seq(Some(consented_count))?;
+        for record in records {
+            seq.serialize_element(&record)?;
+            if let Some(writer) = sink.as_mut() {
+                writer.push(record)?;
             }
         }
         seq.end()?;
@@ -249,10 +706,23 @@ fn run(args: Args) -> Result<()> {
         if let Some(writer) = sink {
             writer.finish()?;
         }
-        return Ok(());
+        if args.incremental {
+            update_manifest(
+                &args.raw_dir,
+                &manifest_path,
+                &mut manifest,
+                pending_offsets,
+            )?;
+        }
+        return Ok(report);
     }
 
-    copy_alias(&snapshot_path, &args.output_dir, &args.snapshot_alias)?;
+    if args.incremental {
+        merge_alias(&snapshot_path, &args.output_dir, &args.snapshot_alias)?;
+    } else {
+        copy_alias(&snapshot_path, &args.output_dir, &args.snapshot_alias)?;
+    }
+    write_schema_registry(&args.output_dir)?;
     println!(
         "Wrote {} records to {}",
         consented_count,
@@ -267,10 +737,341 @@ fn run(args: Args) -> Result<()> {
         );
     }
 
-    Ok(())
+    if args.incremental {
+        update_manifest(
+            &args.raw_dir,
+            &manifest_path,
+            &mut manifest,
+            pending_offsets,
+        )?;
+    }
+
+    Ok(report)
+}
+
+/// Record newly processed files and drop entries for raw files that were pruned.
+fn update_manifest(
+    raw_dir: &Path,
+    manifest_path: &Path,
+    manifest: &mut ProcessedManifest,
+    processed: Vec<(String, ProcessedFile)>,
+) -> Result<()> {
+    manifest.extend(processed);
+    manifest.retain(|key, _| raw_dir.join(key).exists());
+    write_manifest(manifest_path, manifest)
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
-    run(args)
+    run(args)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw_dir: &Path, output_dir: &Path) -> Args {
+        Args {
+            raw_dir: raw_dir.to_path_buf(),
+            output_dir: output_dir.to_path_buf(),
+            postgres_url: None,
+            retain_days: 0,
+            snapshot_alias: "sessions_latest.json".to_string(),
+            batch_size: 1000,
+            incremental: true,
+            processed_manifest: None,
+            upgrade_snapshot: None,
+            partition_by_date: false,
+        }
+    }
+
+    fn snapshots(output_dir: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(output_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("sessions_2"))
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn incremental_run_skips_processed_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let raw_dir = temp.path().join("raw");
+        let output_dir = temp.path().join("curated");
+        fs::create_dir_all(&raw_dir).unwrap();
+        let record = serde_json::json!({
+            "session_id": "s1",
+            "timestamp": "2025-01-01T00:00:00Z",
+            "query": "finance outlook",
+            "verdict": "ok",
+            "requires_manual_review": false,
+            "math_status": "success",
+            "math_alert_required": false,
+            "math_outputs": [],
+            "math_stdout": "",
+            "math_stderr": "",
+            "trace_path": null
+        });
+        fs::write(raw_dir.join("2025-01-01.jsonl"), format!("{record}\n")).unwrap();
+
+        run(args(&raw_dir, &output_dir)).expect("first run");
+        let first = snapshots(&output_dir);
+        assert_eq!(first.len(), 1);
+        let manifest = load_manifest(&output_dir.join(".processed.json")).unwrap();
+        assert!(manifest.contains_key("2025-01-01.jsonl"));
+        let registry = load_schema_registry(&output_dir).unwrap();
+        let records = read_snapshot(&first[0], &registry).unwrap();
+        assert_eq!(records[0].schema_version, SCHEMA_VERSION);
+        assert_eq!(records[0].consent_provided, Some(true));
+        assert!(output_dir.join(SCHEMA_REGISTRY_FILE).exists());
+
+        run(args(&raw_dir, &output_dir)).expect("second run");
+        assert_eq!(snapshots(&output_dir), first);
+
+        let mut appended = record.clone();
+        appended["session_id"] = "s2".into();
+        fs::write(
+            raw_dir.join("2025-01-01.jsonl"),
+            format!("{record}\n{appended}\n"),
+        )
+        .unwrap();
+        run(args(&raw_dir, &output_dir)).expect("appended run");
+        let latest = snapshots(&output_dir);
+        assert_eq!(latest.len(), 2);
+        let ids = |path: &Path| -> Vec<String> {
+            read_snapshot(path, &registry)
+                .unwrap()
+                .into_iter()
+                .map(|record| record.session_id)
+                .collect()
+        };
+        assert_eq!(ids(&latest[1]), vec!["s2".to_string()]);
+        assert_eq!(
+            ids(&output_dir.join("sessions_latest.json")),
+            vec!["s1".to_string(), "s2".to_string()]
+        );
+    }
+
+    #[test]
+    fn duplicate_sessions_keep_latest_record() {
+        let temp = tempfile::tempdir().unwrap();
+        let raw_dir = temp.path().join("raw");
+        let output_dir = temp.path().join("curated");
+        fs::create_dir_all(raw_dir.join("backup")).unwrap();
+        let record = |session_id: &str, timestamp: &str, verdict: &str| {
+            serde_json::json!({
+                "session_id": session_id,
+                "timestamp": timestamp,
+                "query": "finance outlook",
+                "verdict": verdict,
+                "requires_manual_review": false,
+                "math_status": "success",
+                "math_alert_required": false,
+                "math_outputs": [],
+                "math_stdout": "",
+                "math_stderr": "",
+                "trace_path": null
+            })
+        };
+        fs::write(
+            raw_dir.join("2025-01-01.jsonl"),
+            format!(
+                "{}\n{}\n",
+                record("s1", "2025-01-01T00:00:00Z", "stale"),
+                record("s2", "2025-01-01T01:00:00Z", "only")
+            ),
+        )
+        .unwrap();
+        fs::write(
+            raw_dir.join("backup").join("2025-01-01.jsonl"),
+            format!("{}\n", record("s1", "2025-01-01T02:00:00+00:00", "latest")),
+        )
+        .unwrap();
+
+        let report = run(args(&raw_dir, &output_dir)).expect("run");
+        assert_eq!(report.original_count, 3);
+        assert_eq!(report.deduplicated_count, 2);
+        assert_eq!(report.removed_ids, vec!["s1".to_string()]);
+
+        let registry = load_schema_registry(&output_dir).unwrap();
+        let records = read_snapshot(&snapshots(&output_dir)[0], &registry).unwrap();
+        let verdicts: Vec<(&str, &str)> = records
+            .iter()
+            .map(|record| (record.session_id.as_str(), record.verdict.as_str()))
+            .collect();
+        assert_eq!(verdicts, vec![("s1", "latest"), ("s2", "only")]);
+    }
+
+    #[test]
+    fn v1_snapshot_is_readable_with_current_schema() {
+        let temp = tempfile::tempdir().unwrap();
+        let curated = temp.path();
+        let v1_snapshot = curated.join("sessions_20250101T000000.json");
+        let v1_record = serde_json::json!({
+            "session_id": "legacy",
+            "timestamp": "2025-01-01T00:00:00Z",
+            "query": "security review",
+            "verdict": "ok",
+            "requires_manual_review": false,
+            "math_status": "skipped",
+            "math_alert_required": false,
+            "math_outputs": [],
+            "math_stdout": "",
+            "math_stderr": "",
+            "trace_path": null,
+            "domain_label": "security"
+        });
+        fs::write(&v1_snapshot, serde_json::to_vec(&vec![v1_record]).unwrap()).unwrap();
+        write_schema_registry(curated).unwrap();
+
+        let registry = load_schema_registry(curated).unwrap();
+        assert!(registry[&SCHEMA_VERSION].contains(&"schema_version".to_string()));
+        let records = read_snapshot(&v1_snapshot, &registry).expect("read v1 snapshot");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].schema_version, 1);
+        assert_eq!(records[0].domain_label.as_deref(), Some("security"));
+        assert!(records[0].sandbox_failure_streak.is_none());
+        assert!(records[0].consent_provided.is_none());
+
+        upgrade_snapshot(&v1_snapshot).expect("upgrade");
+        let upgraded = read_snapshot(&v1_snapshot, &registry).unwrap();
+        assert_eq!(upgraded[0].schema_version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn partition_by_date_writes_one_file_per_day() {
+        let temp = tempfile::tempdir().unwrap();
+        let raw_dir = temp.path().join("raw");
+        let output_dir = temp.path().join("curated");
+        fs::create_dir_all(&raw_dir).unwrap();
+        let record = |session_id: &str, timestamp: &str| {
+            serde_json::json!({
+                "session_id": session_id,
+                "timestamp": timestamp,
+                "query": "finance outlook",
+                "verdict": "ok",
+                "requires_manual_review": false,
+                "math_status": "success",
+                "math_alert_required": false,
+                "math_outputs": [],
+                "math_stdout": "",
+                "math_stderr": "",
+                "trace_path": null
+            })
+        };
+        fs::write(
+            raw_dir.join("2025-01-01.jsonl"),
+            format!(
+                "{}\n{}\n{}\n",
+                record("s1", "2025-01-01T09:00:00Z"),
+                record("s2", "2025-01-01T23:30:00Z"),
+                record("s3", "2025-01-02T00:15:00+00:00")
+            ),
+        )
+        .unwrap();
+
+        let mut partitioned = args(&raw_dir, &output_dir);
+        partitioned.partition_by_date = true;
+        run(partitioned).expect("partitioned run");
+
+        let registry = load_schema_registry(&output_dir).unwrap();
+        let day_one = output_dir.join("2025/01/01/part-000.json");
+        let day_two = output_dir.join("2025/01/02/part-000.json");
+        let ids = |path: &Path| -> Vec<String> {
+            read_snapshot(path, &registry)
+                .unwrap()
+                .into_iter()
+                .map(|record| record.session_id)
+                .collect()
+        };
+        assert_eq!(ids(&day_one), vec!["s1".to_string(), "s2".to_string()]);
+        assert_eq!(ids(&day_two), vec!["s3".to_string()]);
+        assert!(snapshots(&output_dir).is_empty());
+
+        let contents = fs::read(output_dir.join(PARTITION_MANIFEST_FILE)).unwrap();
+        let manifest: PartitionManifest = serde_json::from_slice(&contents).unwrap();
+        assert_eq!(manifest["2025/01/01/part-000.json"].records, 2);
+        assert_eq!(manifest["2025/01/02/part-000.json"].records, 1);
+
+        fs::write(
+            raw_dir.join("2025-01-02.jsonl"),
+            format!("{}\n", record("s4", "2025-01-02T08:00:00Z")),
+        )
+        .unwrap();
+        let mut partitioned = args(&raw_dir, &output_dir);
+        partitioned.partition_by_date = true;
+        run(partitioned).expect("incremental partitioned run");
+        assert_eq!(
+            ids(&output_dir.join("2025/01/02/part-001.json")),
+            vec!["s4".to_string()]
+        );
+        assert_eq!(ids(&day_two), vec!["s3".to_string()]);
+    }
+
+    #[test]
+    fn full_partitioned_rerun_does_not_duplicate_records() {
+        let temp = tempfile::tempdir().unwrap();
+        let raw_dir = temp.path().join("raw");
+        let output_dir = temp.path().join("curated");
+        fs::create_dir_all(&raw_dir).unwrap();
+        let record = |session_id: &str, timestamp: &str| {
+            serde_json::json!({
+                "session_id": session_id,
+                "timestamp": timestamp,
+                "query": "finance outlook",
+                "verdict": "ok",
+                "requires_manual_review": false,
+                "math_status": "success",
+                "math_alert_required": false,
+                "math_outputs": [],
+                "math_stdout": "",
+                "math_stderr": "",
+                "trace_path": null
+            })
+        };
+        fs::write(
+            raw_dir.join("2025-01-01.jsonl"),
+            format!(
+                "{}\n{}\n{}\n",
+                record("s1", "2025-01-01T09:00:00Z"),
+                record("s2", "2025-01-01T23:30:00Z"),
+                record("s3", "2025-01-02T00:15:00+00:00")
+            ),
+        )
+        .unwrap();
+
+        for _ in 0..2 {
+            let mut full = args(&raw_dir, &output_dir);
+            full.partition_by_date = true;
+            full.incremental = false;
+            run(full).expect("full partitioned run");
+        }
+
+        let registry = load_schema_registry(&output_dir).unwrap();
+        let count = |day: &str| -> usize {
+            fs::read_dir(output_dir.join(day))
+                .unwrap()
+                .map(|entry| {
+                    read_snapshot(&entry.unwrap().path(), &registry)
+                        .unwrap()
+                        .len()
+                })
+                .sum()
+        };
+        assert_eq!(count("2025/01/01"), 2);
+        assert_eq!(count("2025/01/02"), 1);
+
+        let contents = fs::read(output_dir.join(PARTITION_MANIFEST_FILE)).unwrap();
+        let manifest: PartitionManifest = serde_json::from_slice(&contents).unwrap();
+        assert_eq!(manifest.len(), 2);
+        assert_eq!(manifest["2025/01/01/part-000.json"].records, 2);
+        assert_eq!(manifest["2025/01/02/part-000.json"].records, 1);
+    }
 }
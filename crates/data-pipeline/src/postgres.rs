@@ -103,6 +103,9 @@ pub async fn insert_records(pool: &Pool<Postgres>, records: &[SessionRecord]) ->
 #[cfg(test)]
 mod tests {
     use super::*;
+    use deepresearch_core::{
+        DeleteOptions, SessionOptions, delete_session, run_research_session_with_options,
+    };
 
     #[tokio::test]
     async fn creates_table_and_inserts() -> Result<()> {
@@ -118,4 +121,62 @@ mod tests {
         insert_records(&pool, &[]).await?;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn delete_session_cascades_pipeline_records() -> Result<()> {
+        let Some(url) = std::env::var("PIPELINE_TEST_DATABASE_URL").ok() else {
+            return Ok(());
+        };
+        let pool = init_pool(&url).await?;
+        let session_id = format!("pipeline-delete-{}", chrono::Utc::now().timestamp_millis());
+
+        run_research_session_with_options(
+            SessionOptions::new("Delete me after the pipeline run")
+                .with_session_id(session_id.clone())
+                .with_postgres_storage(url.clone()),
+        )
+        .await?;
+        insert_records(
+            &pool,
+            &[SessionRecord {
+                schema_version: crate::SCHEMA_VERSION,
+                session_id: session_id.clone(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                query: "Delete me after the pipeline run".to_string(),
+                verdict: "auto".to_string(),
+                requires_manual_review: false,
+                math_status: "skipped".to_string(),
+                math_alert_required: false,
+                math_outputs: serde_json::Value::Array(Vec::new()),
+                math_stdout: String::new(),
+                math_stderr: String::new(),
+                trace_path: None,
+                sandbox_failure_streak: None,
+                domain_label: None,
+                confidence_bucket: None,
+                consent_provided: None,
+            }],
+        )
+        .await?;
+
+        delete_session(
+            DeleteOptions::new(session_id.clone())
+                .with_postgres_storage(url.clone())
+                .with_cascade_delete_pipeline_records(true),
+        )
+        .await?;
+
+        let sessions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions WHERE id = $1")
+            .bind(&session_id)
+            .fetch_one(&pool)
+            .await?;
+        let records: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM session_records WHERE session_id = $1")
+                .bind(&session_id)
+                .fetch_one(&pool)
+                .await?;
+        assert_eq!(sessions, 0);
+        assert_eq!(records, 0);
+        Ok(())
+    }
 }
@@ -1,29 +1,45 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use axum::{
-    Json, Router,
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Response},
+    Extension, Json, Router,
+    extract::{Path, Query, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header},
+    middleware::{self, Next},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
     routing::{get, post},
 };
+use dashmap::{DashMap, mapref::entry::Entry};
 use deepresearch_core::{
-    IngestDocument, IngestOptions, LoadOptions, RetrieverChoice, SessionOptions, SessionOutcome,
-    TraceEvent, ingest_documents, load_session_report, run_research_session_with_report,
+    CollectionInfo, DeepResearchError, HealthStatus, IngestDocument, IngestOptions, LoadOptions,
+    OTEL_SPAN_ID_KEY, OTEL_TRACE_ID_KEY, RetrieverChoice, SessionOptions, SessionOutcome,
+    SourceReference, TraceDiff, TraceEvent, TraceStream, check_health, clone_session,
+    ingest_documents, load_session_report, parse_traceparent, run_research_session_with_report,
+    session_index_info,
 };
 use graph_flow::{InMemorySessionStorage, SessionStorage};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::pin::Pin;
 use std::sync::Arc;
 use tokio::{
     net::TcpListener,
     signal,
     sync::{OwnedSemaphorePermit, Semaphore, TryAcquireError},
 };
-use tracing::{info, warn};
+use tokio_stream::{Stream, StreamExt, wrappers::BroadcastStream};
+use tower_http::cors::CorsLayer;
+use tracing::{Instrument, info, info_span, warn};
 use tracing_subscriber::EnvFilter;
 use uuid::Uuid;
 
+const REQUEST_ID_HEADER: &str = "x-request-id";
+const TRACEPARENT_HEADER: &str = "traceparent";
+
 #[derive(Clone)]
 struct AppState {
     storage: Arc<dyn SessionStorage>,
@@ -31,18 +47,14 @@ struct AppState {
     trace_dir: PathBuf,
     session_permits: Arc<Semaphore>,
     max_sessions: usize,
+    trace_streams: Arc<DashMap<String, TraceStream>>,
 }
 
+type TraceSseStream = Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>;
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("info,deepresearch_core=info"));
-
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
-        .with_target(false)
-        .init();
-
+    init_tracing()?;
     deepresearch_core::init_metrics_from_env("deepresearch-api")?;
 
     let addr: SocketAddr = std::env::var("DEEPRESEARCH_API_ADDR")
@@ -81,26 +93,327 @@ async fn main() -> Result<()> {
         trace_dir,
         session_permits,
         max_sessions: session_limit,
+        trace_streams: Arc::new(DashMap::new()),
     };
 
-    let app = Router::new()
-        .route("/health", get(handle_health))
-        .route("/query", post(handle_query))
-        .route("/session/:id", get(handle_session))
-        .route("/ingest", post(handle_ingest))
-        .with_state(state);
+    #[cfg(feature = "rate-limit")]
+    let app = match RateLimitConfig::from_env() {
+        Some(limit) => {
+            info!(
+                rps = limit.requests_per_second,
+                burst = limit.burst,
+                "rate limiting /query and /ingest per client IP"
+            );
+            build_rate_limited_router(state, &limit)
+        }
+        None => build_router(state),
+    };
+    #[cfg(not(feature = "rate-limit"))]
+    let app = build_router(state);
+    let app = match CorsConfig::from_env() {
+        Some(cors) => {
+            info!(origins = %cors.origins, "CORS enabled");
+            app.layer(cors.layer()?)
+        }
+        None => app,
+    };
 
     info!("DeepResearch API listening on {}", addr);
 
     let listener = TcpListener::bind(addr).await?;
-    axum::serve(listener, app.into_make_service())
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     deepresearch_core::shutdown_metrics();
+    #[cfg(feature = "otel")]
+    opentelemetry::global::shutdown_tracer_provider();
+    Ok(())
+}
+
+fn default_env_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new("info,deepresearch_core=info"))
+}
+
+#[cfg(not(feature = "otel"))]
+fn init_tracing() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(default_env_filter())
+        .with_target(false)
+        .init();
+    Ok(())
+}
+
+/// Exports spans over OTLP/gRPC (endpoint from `OTEL_EXPORTER_OTLP_ENDPOINT`,
+/// default `http://localhost:4317`) alongside the usual fmt logs.
+#[cfg(feature = "otel")]
+fn init_tracing() -> Result<()> {
+    use opentelemetry::KeyValue;
+    use opentelemetry_sdk::{Resource, runtime, trace};
+    use tracing_subscriber::prelude::*;
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic())
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                "deepresearch-api",
+            )])),
+        )
+        .install_batch(runtime::Tokio)?;
+
+    tracing_subscriber::registry()
+        .with(default_env_filter())
+        .with(tracing_subscriber::fmt::layer().with_target(false))
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .init();
     Ok(())
 }
 
+fn build_router(state: AppState) -> Router {
+    assemble_router(state, work_routes())
+}
+
+/// Like [`build_router`], but throttles `/query` and `/ingest` per client IP.
+#[cfg(feature = "rate-limit")]
+fn build_rate_limited_router(state: AppState, limit: &RateLimitConfig) -> Router {
+    assemble_router(state, work_routes().layer(limit.layer()))
+}
+
+/// Routes that start research or indexing work (the rate-limited surface).
+fn work_routes() -> Router<AppState> {
+    Router::new()
+        .route("/query", post(handle_query))
+        .route("/ingest", post(handle_ingest))
+}
+
+fn assemble_router(state: AppState, work_routes: Router<AppState>) -> Router {
+    Router::new()
+        .route("/health", get(handle_health))
+        .route("/health/ready", get(handle_ready))
+        .route("/session/:id", get(handle_session))
+        .route("/session/:id/trace/stream", get(handle_trace_stream))
+        .route("/session/:id/trace/diff", get(handle_trace_diff))
+        .route("/session/:id/clone", post(handle_clone))
+        .route("/session/:id/index-info", get(handle_index_info))
+        .merge(work_routes)
+        .layer(middleware::from_fn(request_id_middleware))
+        .with_state(state)
+}
+
+/// Per-IP token bucket for the work routes: `requests_per_second` tokens are replenished
+/// each second up to `burst`. Exceeding it yields 429 with a `Retry-After` header.
+#[cfg(feature = "rate-limit")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RateLimitConfig {
+    requests_per_second: u64,
+    burst: u32,
+    /// Key clients by forwarding headers instead of the socket peer address.
+    trust_forwarded_headers: bool,
+}
+
+#[cfg(feature = "rate-limit")]
+impl RateLimitConfig {
+    const DEFAULT_RPS: u64 = 5;
+    const DEFAULT_BURST: u32 = 10;
+
+    /// Read `DEEPRESEARCH_RATE_LIMIT_RPS` / `DEEPRESEARCH_RATE_LIMIT_BURST` /
+    /// `DEEPRESEARCH_RATE_LIMIT_TRUST_PROXY`; an RPS of `0` disables limiting.
+    fn from_env() -> Option<Self> {
+        let requests_per_second = std::env::var("DEEPRESEARCH_RATE_LIMIT_RPS")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(Self::DEFAULT_RPS);
+        let burst = std::env::var("DEEPRESEARCH_RATE_LIMIT_BURST")
+            .ok()
+            .and_then(|value| value.parse::<u32>().ok())
+            .filter(|burst| *burst > 0)
+            .unwrap_or(Self::DEFAULT_BURST);
+        let trust_forwarded_headers = std::env::var("DEEPRESEARCH_RATE_LIMIT_TRUST_PROXY")
+            .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        (requests_per_second > 0).then_some(Self {
+            requests_per_second,
+            burst,
+            trust_forwarded_headers,
+        })
+    }
+
+    /// Clients are keyed by the peer address unless forwarding headers are trusted, since
+    /// anyone reaching the API directly can forge them to get a fresh bucket per request.
+    fn layer(
+        &self,
+    ) -> tower_governor::GovernorLayer<ClientIpKeyExtractor, governor::middleware::NoOpMiddleware>
+    {
+        let key_extractor = if self.trust_forwarded_headers {
+            ClientIpKeyExtractor::Forwarded
+        } else {
+            ClientIpKeyExtractor::Peer
+        };
+        let config = Arc::new(
+            tower_governor::governor::GovernorConfigBuilder::default()
+                .per_nanosecond((1_000_000_000 / self.requests_per_second).max(1))
+                .burst_size(self.burst)
+                .key_extractor(key_extractor)
+                .finish()
+                .expect("rate limit period and burst are non-zero"),
+        );
+
+        // Drop buckets of clients that have been idle long enough to be full again.
+        let limiter = config.limiter().clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                limiter.retain_recent();
+            }
+        });
+
+        tower_governor::GovernorLayer { config }
+    }
+}
+
+/// How the rate limiter identifies a client.
+#[cfg(feature = "rate-limit")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClientIpKeyExtractor {
+    /// The socket peer address.
+    Peer,
+    /// `X-Forwarded-For` / `X-Real-Ip` / `Forwarded`, falling back to the peer address.
+    Forwarded,
+}
+
+#[cfg(feature = "rate-limit")]
+impl tower_governor::key_extractor::KeyExtractor for ClientIpKeyExtractor {
+    type Key = std::net::IpAddr;
+
+    fn extract<T>(
+        &self,
+        request: &axum::http::Request<T>,
+    ) -> Result<Self::Key, tower_governor::GovernorError> {
+        use tower_governor::key_extractor::{
+            KeyExtractor, PeerIpKeyExtractor, SmartIpKeyExtractor,
+        };
+
+        match self {
+            Self::Peer => PeerIpKeyExtractor.extract(request),
+            Self::Forwarded => SmartIpKeyExtractor.extract(request),
+        }
+    }
+}
+
+/// Browser access for SPA clients, read from `DEEPRESEARCH_CORS_*`. Applied as the
+/// outermost layer so preflights are answered before routing, rate limiting, or handlers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CorsConfig {
+    /// Comma-separated origins, or `*` for `CorsLayer::permissive()`.
+    origins: String,
+    methods: Option<String>,
+    headers: Option<String>,
+}
+
+impl CorsConfig {
+    const DEFAULT_METHODS: [Method; 3] = [Method::GET, Method::POST, Method::OPTIONS];
+
+    /// `None` unless `DEEPRESEARCH_CORS_ORIGINS` is set, keeping CORS headers off by default.
+    fn from_env() -> Option<Self> {
+        let origins = std::env::var("DEEPRESEARCH_CORS_ORIGINS")
+            .ok()
+            .filter(|value| !value.trim().is_empty())?;
+        Some(Self {
+            origins,
+            methods: std::env::var("DEEPRESEARCH_CORS_METHODS").ok(),
+            headers: std::env::var("DEEPRESEARCH_CORS_HEADERS").ok(),
+        })
+    }
+
+    fn layer(&self) -> Result<CorsLayer> {
+        if self.origins.trim() == "*" {
+            return Ok(CorsLayer::permissive());
+        }
+
+        let origins = comma_separated(&self.origins)
+            .map(|origin| {
+                HeaderValue::from_str(origin)
+                    .with_context(|| format!("invalid CORS origin '{origin}'"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let methods = match &self.methods {
+            Some(raw) => comma_separated(raw)
+                .map(|method| {
+                    Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                        .with_context(|| format!("invalid CORS method '{method}'"))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => Self::DEFAULT_METHODS.to_vec(),
+        };
+        let headers = match &self.headers {
+            Some(raw) => comma_separated(raw)
+                .map(|name| {
+                    HeaderName::from_bytes(name.as_bytes())
+                        .with_context(|| format!("invalid CORS header '{name}'"))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => vec![
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                HeaderName::from_static(REQUEST_ID_HEADER),
+                HeaderName::from_static(TRACEPARENT_HEADER),
+            ],
+        };
+
+        Ok(CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .expose_headers([HeaderName::from_static(REQUEST_ID_HEADER)]))
+    }
+}
+
+fn comma_separated(raw: &str) -> impl Iterator<Item = &str> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// Correlation ID attached to every request (taken from `X-Request-Id` or freshly generated).
+#[derive(Debug, Clone)]
+struct RequestId(String);
+
+async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    request
+        .extensions_mut()
+        .insert(RequestId(request_id.clone()));
+
+    let span = info_span!(
+        "http.request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        request_id = tracing::field::Empty,
+    );
+    span.record("request_id", request_id.as_str());
+
+    let mut response = next.run(request).instrument(span).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
@@ -132,6 +445,8 @@ enum ExplainFormat {
     Markdown,
     Mermaid,
     Graphviz,
+    Html,
+    Json,
 }
 
 impl ExplainFormat {
@@ -140,6 +455,8 @@ impl ExplainFormat {
             ExplainFormat::Markdown => outcome.explain_markdown(),
             ExplainFormat::Mermaid => outcome.explain_mermaid(),
             ExplainFormat::Graphviz => outcome.explain_graphviz(),
+            ExplainFormat::Html => outcome.explain_html(),
+            ExplainFormat::Json => outcome.explain_json(),
         }
     }
 
@@ -148,6 +465,8 @@ impl ExplainFormat {
             ExplainFormat::Markdown => "markdown",
             ExplainFormat::Mermaid => "mermaid",
             ExplainFormat::Graphviz => "graphviz",
+            ExplainFormat::Html => "html",
+            ExplainFormat::Json => "json",
         }
     }
 }
@@ -181,6 +500,20 @@ where
     }
 }
 
+/// Map core errors onto HTTP statuses: unknown sessions become 404, collisions with an
+/// existing session 409, everything else 500.
+fn session_error(error: DeepResearchError) -> AppError {
+    match error {
+        DeepResearchError::SessionNotFound(_) => {
+            AppError::new(StatusCode::NOT_FOUND, error.to_string())
+        }
+        DeepResearchError::SessionConflict(_) => {
+            AppError::new(StatusCode::CONFLICT, error.to_string())
+        }
+        other => AppError::from(other),
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let body = Json(ErrorResponse {
@@ -224,6 +557,8 @@ struct SessionPayload {
     explanation: Option<String>,
     explanation_format: Option<String>,
     trace_events: Vec<TraceEvent>,
+    sources: Vec<String>,
+    source_references: Vec<SourceReference>,
 }
 
 #[derive(Debug, Serialize)]
@@ -238,6 +573,8 @@ struct HealthResponse {
     status: &'static str,
     capacity: CapacityReport,
     retrieval_mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checks: Option<HealthStatus>,
 }
 
 fn capacity_report(state: &AppState) -> CapacityReport {
@@ -254,6 +591,7 @@ fn retrieval_mode(retriever: &RetrieverChoice) -> &'static str {
     match retriever {
         RetrieverChoice::Stub => "stub",
         RetrieverChoice::Qdrant { .. } => "qdrant",
+        RetrieverChoice::Sqlite { .. } => "sqlite",
     }
 }
 
@@ -263,9 +601,28 @@ async fn handle_health(State(state): State<AppState>) -> ApiResult<Json<HealthRe
         status: "ok",
         capacity: report,
         retrieval_mode: retrieval_mode(&state.retriever),
+        checks: None,
     }))
 }
 
+async fn handle_ready(State(state): State<AppState>) -> (StatusCode, Json<HealthResponse>) {
+    let checks = check_health(state.storage.clone(), &state.retriever, None).await;
+    let (code, status) = if checks.is_healthy() {
+        (StatusCode::OK, "ok")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "degraded")
+    };
+    (
+        code,
+        Json(HealthResponse {
+            status,
+            capacity: capacity_report(&state),
+            retrieval_mode: retrieval_mode(&state.retriever),
+            checks: Some(checks),
+        }),
+    )
+}
+
 #[derive(Debug, Deserialize)]
 struct SessionQuery {
     explain: Option<bool>,
@@ -296,16 +653,23 @@ struct IngestResponse {
 
 async fn handle_query(
     State(state): State<AppState>,
+    Extension(RequestId(request_id)): Extension<RequestId>,
+    headers: HeaderMap,
     Json(request): Json<QueryRequest>,
 ) -> ApiResult<Json<SessionPayload>> {
     let _permit = acquire_session_permit(&state)?;
+    let session_id = request
+        .session_id
+        .clone()
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let trace_stream = TraceStream::new();
     let mut options = SessionOptions::new(&request.query)
+        .with_session_id(session_id.clone())
         .with_shared_storage(state.storage.clone())
-        .with_retriever(state.retriever.clone());
-
-    if let Some(session_id) = request.session_id {
-        options = options.with_session_id(session_id);
-    }
+        .with_retriever(state.retriever.clone())
+        .with_trace_stream(trace_stream.clone())
+        .with_initial_context("request.id", Value::String(request_id));
+    options = with_trace_parent(options, &headers);
 
     let trace_requested = request.explain.unwrap_or(false)
         || request.persist_trace.unwrap_or(false)
@@ -319,9 +683,21 @@ async fn handle_query(
         options = options.with_trace_output_dir(dir);
     }
 
-    let outcome = run_research_session_with_report(options)
-        .await
-        .map_err(AppError::from)?;
+    match state.trace_streams.entry(session_id.clone()) {
+        Entry::Occupied(_) => {
+            return Err(AppError::new(
+                StatusCode::CONFLICT,
+                format!("session {session_id} is already running"),
+            ));
+        }
+        Entry::Vacant(slot) => {
+            slot.insert(trace_stream);
+        }
+    }
+    let result = run_research_session_with_report(options).await;
+    // Dropping the last sender closes live subscribers once the session settles.
+    state.trace_streams.remove(&session_id);
+    let outcome = result.map_err(AppError::from)?;
 
     let explain_format = request.explain_format.unwrap_or(ExplainFormat::Markdown);
     let (explanation, explanation_format) = if request.explain.unwrap_or(false) {
@@ -343,11 +719,43 @@ async fn handle_query(
         explanation,
         explanation_format,
         trace_events: outcome.trace_events,
+        sources: outcome.sources,
+        source_references: outcome.source_references,
     };
 
     Ok(Json(payload))
 }
 
+/// Propagate an incoming W3C `traceparent` so task spans join the caller's trace.
+fn with_trace_parent(options: SessionOptions, headers: &HeaderMap) -> SessionOptions {
+    let Some((trace_id, span_id)) = headers
+        .get(TRACEPARENT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_traceparent)
+    else {
+        return options;
+    };
+    options
+        .with_initial_context(OTEL_TRACE_ID_KEY, Value::String(trace_id))
+        .with_initial_context(OTEL_SPAN_ID_KEY, Value::String(span_id))
+}
+
+async fn handle_index_info(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Json<CollectionInfo>> {
+    if !matches!(state.retriever, RetrieverChoice::Qdrant { .. }) {
+        return Err(AppError::new(
+            StatusCode::BAD_REQUEST,
+            "index info requires the Qdrant retriever (set DEEPRESEARCH_QDRANT_URL)",
+        ));
+    }
+    let info = session_index_info(&state.retriever, &session_id)
+        .await
+        .map_err(session_error)?;
+    Ok(Json(info))
+}
+
 async fn handle_session(
     State(state): State<AppState>,
     Path(session_id): Path<String>,
@@ -365,16 +773,7 @@ async fn handle_session(
         options = options.with_trace_output_dir(dir);
     }
 
-    let outcome = match load_session_report(options).await {
-        Ok(outcome) => outcome,
-        Err(err) => {
-            let message = err.to_string();
-            if message.contains("not found") {
-                return Err(AppError::new(StatusCode::NOT_FOUND, message));
-            }
-            return Err(AppError::from(err));
-        }
-    };
+    let outcome = load_session_report(options).await.map_err(session_error)?;
 
     let explain_format = query.explain_format.unwrap_or(ExplainFormat::Markdown);
 
@@ -401,11 +800,125 @@ async fn handle_session(
         explanation,
         explanation_format,
         trace_events: outcome.trace_events,
+        sources: outcome.sources,
+        source_references: outcome.source_references,
     };
 
     Ok(Json(payload))
 }
 
+async fn handle_trace_stream(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+) -> ApiResult<Sse<TraceSseStream>> {
+    let live = state
+        .trace_streams
+        .get(&session_id)
+        .map(|entry| entry.value().subscribe());
+
+    let stream: TraceSseStream = match live {
+        Some((history, receiver)) => {
+            let live = BroadcastStream::new(receiver).filter_map(|event| match event {
+                Ok(event) => Some(event),
+                Err(err) => {
+                    warn!(error = %err, "trace stream lagged; dropping events");
+                    None
+                }
+            });
+            Box::pin(
+                tokio_stream::iter(history)
+                    .chain(live)
+                    .map(|event| Ok::<_, Infallible>(trace_sse_event(&event))),
+            )
+        }
+        None => {
+            // Finished sessions replay their stored trace and close the stream.
+            let options =
+                LoadOptions::new(session_id.clone()).with_shared_storage(state.storage.clone());
+            let outcome = load_session_report(options).await.map_err(session_error)?;
+            Box::pin(
+                tokio_stream::iter(outcome.trace_events)
+                    .map(|event| Ok::<_, Infallible>(trace_sse_event(&event))),
+            )
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[derive(Debug, Deserialize)]
+struct TraceDiffQuery {
+    other: String,
+}
+
+#[derive(Debug, Serialize)]
+struct TraceDiffPayload {
+    session_id: String,
+    other_session_id: String,
+    #[serde(flatten)]
+    diff: TraceDiff,
+    markdown: String,
+}
+
+async fn handle_trace_diff(
+    State(state): State<AppState>,
+    Path(session_id): Path<String>,
+    Query(query): Query<TraceDiffQuery>,
+) -> ApiResult<Json<TraceDiffPayload>> {
+    let load = |id: String| {
+        let options = LoadOptions::new(id).with_shared_storage(state.storage.clone());
+        async move { load_session_report(options).await.map_err(session_error) }
+    };
+    let outcome = load(session_id.clone()).await?;
+    let other = load(query.other.clone()).await?;
+
+    let diff = outcome.trace_summary.diff(&other.trace_summary);
+    Ok(Json(TraceDiffPayload {
+        session_id,
+        other_session_id: query.other,
+        markdown: diff.render_markdown(),
+        diff,
+    }))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CloneRequest {
+    new_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CloneResponse {
+    session_id: String,
+    cloned_from: String,
+}
+
+async fn handle_clone(
+    State(state): State<AppState>,
+    Path(source_id): Path<String>,
+    request: Option<Json<CloneRequest>>,
+) -> ApiResult<(StatusCode, Json<CloneResponse>)> {
+    let Json(request) = request.unwrap_or_default();
+    let new_id = request.new_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    clone_session(&source_id, &new_id, &state.storage)
+        .await
+        .map_err(session_error)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CloneResponse {
+            session_id: new_id,
+            cloned_from: source_id,
+        }),
+    ))
+}
+
+fn trace_sse_event(event: &TraceEvent) -> Event {
+    Event::default()
+        .event("trace")
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().event("trace").data(event.message.clone()))
+}
+
 async fn handle_ingest(
     State(state): State<AppState>,
     Json(request): Json<IngestRequest>,
@@ -435,6 +948,8 @@ async fn handle_ingest(
         session_id: session_id.clone(),
         documents,
         retriever: state.retriever.clone(),
+        chunk_size: None,
+        chunk_overlap: 0,
     })
     .await
     .map_err(AppError::from)?;
@@ -448,16 +963,23 @@ async fn handle_ingest(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use axum::body::Body;
+    use tower::ServiceExt;
 
-    #[test]
-    fn capacity_limit_returns_429() {
-        let state = AppState {
+    fn test_state(permits: usize) -> AppState {
+        AppState {
             storage: Arc::new(InMemorySessionStorage::new()),
             retriever: RetrieverChoice::default(),
             trace_dir: PathBuf::from("data/traces"),
-            session_permits: Arc::new(Semaphore::new(1)),
-            max_sessions: 1,
-        };
+            session_permits: Arc::new(Semaphore::new(permits)),
+            max_sessions: permits,
+            trace_streams: Arc::new(DashMap::new()),
+        }
+    }
+
+    #[test]
+    fn capacity_limit_returns_429() {
+        let state = test_state(1);
 
         let permit = acquire_session_permit(&state).expect("first permit should succeed");
         let err = acquire_session_permit(&state).expect_err("second permit should fail");
@@ -465,4 +987,408 @@ mod tests {
         assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
         drop(permit);
     }
+
+    #[tokio::test]
+    async fn request_id_is_echoed_or_generated() {
+        let state = test_state(1);
+        let app = build_router(state);
+
+        let request = Request::builder()
+            .uri("/health")
+            .header(REQUEST_ID_HEADER, "req-123")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-123");
+
+        let request = Request::builder()
+            .uri("/health")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let generated = response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .expect("request id header missing");
+        assert!(Uuid::parse_str(generated).is_ok());
+    }
+
+    #[tokio::test]
+    async fn readiness_reports_backend_checks() {
+        let state = test_state(1);
+        let app = build_router(state);
+
+        let request = Request::builder()
+            .uri("/health/ready")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["checks"]["storage_ok"], true);
+        assert_eq!(payload["checks"]["retriever_ok"], true);
+        assert!(payload["checks"]["sandbox_ok"].is_null());
+    }
+
+    #[test]
+    fn traceparent_is_injected_into_initial_context() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            TRACEPARENT_HEADER,
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+        let options = with_trace_parent(SessionOptions::new("otel"), &headers);
+        let lookup = |key: &str| {
+            options
+                .initial_context
+                .iter()
+                .find(|(candidate, _)| candidate == key)
+                .map(|(_, value)| value.clone())
+        };
+        assert_eq!(
+            lookup(OTEL_TRACE_ID_KEY),
+            Some(Value::String("4bf92f3577b34da6a3ce929d0e0e4736".into()))
+        );
+        assert_eq!(
+            lookup(OTEL_SPAN_ID_KEY),
+            Some(Value::String("00f067aa0ba902b7".into()))
+        );
+
+        headers.insert(TRACEPARENT_HEADER, HeaderValue::from_static("garbage"));
+        let options = with_trace_parent(SessionOptions::new("otel"), &headers);
+        assert!(options.initial_context.is_empty());
+    }
+
+    #[tokio::test]
+    async fn trace_stream_replays_finished_session() {
+        let state = test_state(1);
+        let app = build_router(state.clone());
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/query")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"query":"Trace streaming smoke test","session_id":"trace-stream-1"}"#,
+            ))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.trace_streams.is_empty());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let payload: Value = serde_json::from_slice(&body).unwrap();
+        assert!(
+            payload["trace_path"].is_null(),
+            "live streaming alone must not persist a trace: {payload}"
+        );
+
+        let request = Request::builder()
+            .uri("/session/trace-stream-1/trace/stream")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8_lossy(&body);
+        assert!(body.contains("event: trace"), "unexpected SSE body: {body}");
+        assert!(body.contains("researcher"), "unexpected SSE body: {body}");
+
+        let request = Request::builder()
+            .uri("/session/missing/trace/stream")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn query_rejects_session_id_already_streaming() {
+        let state = test_state(2);
+        state
+            .trace_streams
+            .insert("busy-session".into(), TraceStream::new());
+        let app = build_router(state.clone());
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/query")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"query":"Duplicate session","session_id":"busy-session"}"#,
+            ))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(state.trace_streams.contains_key("busy-session"));
+    }
+
+    #[cfg(feature = "rate-limit")]
+    #[tokio::test]
+    async fn rate_limit_rejects_requests_beyond_burst() {
+        let state = test_state(1);
+        let burst = 3;
+        let app = build_rate_limited_router(
+            state,
+            &RateLimitConfig {
+                requests_per_second: 1,
+                burst,
+                trust_forwarded_headers: true,
+            },
+        );
+        let ingest = || {
+            Request::builder()
+                .method("POST")
+                .uri("/ingest")
+                .header("content-type", "application/json")
+                .header("x-forwarded-for", "203.0.113.7")
+                .body(Body::from(r#"{"session_id":"rate-limit","documents":[]}"#))
+                .unwrap()
+        };
+
+        for _ in 0..burst {
+            let response = app.clone().oneshot(ingest()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = app.clone().oneshot(ingest()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("retry-after"));
+
+        let health = Request::builder()
+            .uri("/health")
+            .header("x-forwarded-for", "203.0.113.7")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(health).await.unwrap();
+        assert_ne!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[cfg(feature = "rate-limit")]
+    #[tokio::test]
+    async fn rate_limit_ignores_forwarded_headers_by_default() {
+        let state = test_state(1);
+        let burst = 2;
+        let app = build_rate_limited_router(
+            state,
+            &RateLimitConfig {
+                requests_per_second: 1,
+                burst,
+                trust_forwarded_headers: false,
+            },
+        );
+        let peer = SocketAddr::from(([198, 51, 100, 4], 40_000));
+        let ingest = |forwarded_for: String| {
+            Request::builder()
+                .method("POST")
+                .uri("/ingest")
+                .header("content-type", "application/json")
+                .header("x-forwarded-for", forwarded_for)
+                .extension(axum::extract::ConnectInfo(peer))
+                .body(Body::from(r#"{"session_id":"rate-limit","documents":[]}"#))
+                .unwrap()
+        };
+
+        for attempt in 0..burst {
+            let request = ingest(format!("203.0.113.{attempt}"));
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        // A fresh spoofed address does not buy a fresh bucket.
+        let response = app
+            .oneshot(ingest("203.0.113.200".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn index_info_requires_qdrant_retriever() {
+        let state = test_state(1);
+        let app = build_router(state);
+
+        let request = Request::builder()
+            .uri("/session/any-session/index-info")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn json_explanation_contains_trace_events() {
+        let state = test_state(1);
+        let app = build_router(state);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/query")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"query":"JSON explain smoke test","explain":true,"explain_format":"json"}"#,
+            ))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["explanation_format"], "json");
+        let events: serde_json::Value =
+            serde_json::from_str(payload["explanation"].as_str().expect("explanation")).unwrap();
+        assert!(
+            events
+                .as_array()
+                .is_some_and(|events| events.iter().any(|event| event["task_id"] == "researcher"))
+        );
+    }
+
+    #[tokio::test]
+    async fn trace_diff_compares_two_sessions() {
+        let state = test_state(1);
+        let app = build_router(state);
+
+        for session_id in ["diff-a", "diff-b"] {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/query")
+                .header("content-type", "application/json")
+                .body(Body::from(format!(
+                    r#"{{"query":"Trace diff smoke test","session_id":"{session_id}","explain":true}}"#
+                )))
+                .unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let request = Request::builder()
+            .uri("/session/diff-a/trace/diff?other=diff-b")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["other_session_id"], "diff-b");
+        assert!(payload["added"].is_array());
+        assert!(payload["markdown"].is_string());
+
+        let request = Request::builder()
+            .uri("/session/diff-a/trace/diff?other=missing")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn clone_endpoint_forks_existing_session() {
+        let state = test_state(1);
+        let app = build_router(state);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/query")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"query":"Clone smoke test","session_id":"clone-source"}"#,
+            ))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/session/clone-source/clone")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"new_id":"clone-target"}"#))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["session_id"], "clone-target");
+        assert_eq!(payload["cloned_from"], "clone-source");
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/session/clone-source/clone")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"new_id":"clone-target"}"#))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/session/missing/clone")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn cors_preflight_allows_configured_origin() {
+        let state = test_state(1);
+        let cors = CorsConfig {
+            origins: "https://app.example, https://admin.example".to_string(),
+            methods: Some("get,post".to_string()),
+            headers: None,
+        };
+        let app = build_router(state).layer(cors.layer().expect("valid CORS config"));
+
+        let preflight = Request::builder()
+            .method("OPTIONS")
+            .uri("/query")
+            .header("origin", "https://app.example")
+            .header("access-control-request-method", "POST")
+            .header("access-control-request-headers", "content-type")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(preflight).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["access-control-allow-origin"],
+            "https://app.example"
+        );
+        let methods = response.headers()["access-control-allow-methods"]
+            .to_str()
+            .unwrap();
+        assert!(methods.contains("POST"), "allowed methods: {methods}");
+
+        let foreign = Request::builder()
+            .method("OPTIONS")
+            .uri("/query")
+            .header("origin", "https://evil.example")
+            .header("access-control-request-method", "POST")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(foreign).await.unwrap();
+        assert!(
+            !response
+                .headers()
+                .contains_key("access-control-allow-origin")
+        );
+
+        let invalid = CorsConfig {
+            origins: "https://app.example".to_string(),
+            methods: Some("not a method".to_string()),
+            headers: None,
+        };
+        assert!(invalid.layer().is_err());
+    }
 }
@@ -1,26 +1,30 @@
-use anyhow::Result;
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use anyhow::{Context, Result};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
 use deepresearch_core::{
-    DeleteOptions, EvaluationHarness, LoadOptions, ResumeOptions, SessionOptions, SessionOutcome,
-    delete_session, load_session_report, remove_session_logs, resume_research_session_with_report,
-    run_research_session_with_report,
+    ArchiveOptions, CloneOptions, CollectionInfo, DeleteOptions, EvalComparison, EvaluationHarness,
+    ExportOptions, ListOptions, LoadOptions, ResumeOptions, RetrieverChoice, SessionOptions,
+    SessionOutcome, SessionSummary, StorageChoice, TraceCollector, TraceDiff, TraceEvent,
+    archive_old_sessions, clone_session_with_options, delete_session,
+    export_session_bundle_with_options, list_sessions, load_session_report, persist_trace_csv,
+    purge_old_sessions, remove_pipeline_records, remove_session_logs,
+    resume_research_session_with_report, run_research_session_with_report, session_index_info,
 };
-#[cfg(feature = "qdrant-retriever")]
-use deepresearch_core::{IngestDocument, IngestOptions, RetrieverChoice};
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
+use deepresearch_core::{IngestDocument, IngestOptions};
 use serde::Serialize;
-#[cfg(feature = "qdrant-retriever")]
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
 use std::path::Path;
-use std::{fs, path::PathBuf, sync::Arc};
+use std::{collections::BTreeMap, fs, path::PathBuf, sync::Arc, time::Duration};
 use tokio::{runtime::Runtime, sync::Semaphore, task::JoinSet, time::Instant};
+use tokio_stream::StreamExt;
 use tracing::{info, warn};
 use tracing_subscriber::EnvFilter;
 use uuid::Uuid;
 
-#[cfg(feature = "qdrant-retriever")]
-use anyhow::Context;
-#[cfg(feature = "qdrant-retriever")]
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
 use deepresearch_core::ingest_documents as ingest_docs;
-#[cfg(feature = "qdrant-retriever")]
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
 use walkdir::WalkDir;
 
 #[derive(Parser, Debug)]
@@ -46,10 +50,27 @@ enum Command {
     Ingest(IngestArgs),
     /// Aggregate evaluation metrics from a JSONL log.
     Eval(EvalArgs),
+    /// Fork a stored session under a new ID.
+    Clone(CloneArgs),
+    /// Package a stored session (context, trace, summary, sources) as a ZIP bundle
+    /// (requires the `bundle-export` feature).
+    Export(ExportArgs),
+    /// Report how many documents Qdrant holds for a session.
+    IndexInfo(IndexInfoArgs),
+    /// Play back a saved `trace.json` with its original timing.
+    Replay(ReplayArgs),
     /// Delete a session from the configured storage backend.
     Purge(PurgeArgs),
+    /// Delete every stored session older than the given age.
+    Prune(PruneArgs),
+    /// Move stored sessions older than the given number of days into an archive table.
+    Archive(ArchiveArgs),
+    /// List stored sessions, newest first.
+    List(ListArgs),
     /// Run synthetic load to benchmark session throughput.
     Bench(BenchArgs),
+    /// Generate shell completion scripts.
+    Completions(CompletionsArgs),
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum, Default)]
@@ -59,11 +80,32 @@ enum OutputFormat {
     Json,
 }
 
+#[derive(Copy, Clone, Debug, ValueEnum, Default)]
+enum ExplainOutputFormat {
+    #[default]
+    Text,
+    Json,
+    /// Emit the trace steps as CSV (`step_index,task_id,message,duration_ms`).
+    Csv,
+}
+
+/// Layout of the files handed to `ingest`.
+#[derive(Copy, Clone, Debug, ValueEnum, Default, PartialEq, Eq)]
+enum DocumentFormat {
+    /// Each file is one document (source = file path).
+    #[default]
+    Text,
+    /// Each line is an `{"id", "text", "source"}` document; malformed lines are skipped.
+    Jsonl,
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum)]
 enum ExplainFormat {
     Markdown,
     Mermaid,
     Graphviz,
+    Html,
+    Json,
 }
 
 impl ExplainFormat {
@@ -72,6 +114,8 @@ impl ExplainFormat {
             ExplainFormat::Markdown => outcome.explain_markdown(),
             ExplainFormat::Mermaid => outcome.explain_mermaid(),
             ExplainFormat::Graphviz => outcome.explain_graphviz(),
+            ExplainFormat::Html => outcome.explain_html(),
+            ExplainFormat::Json => outcome.explain_json(),
         }
     }
 
@@ -80,6 +124,8 @@ impl ExplainFormat {
             ExplainFormat::Markdown => "markdown",
             ExplainFormat::Mermaid => "mermaid",
             ExplainFormat::Graphviz => "graphviz",
+            ExplainFormat::Html => "html",
+            ExplainFormat::Json => "json",
         }
     }
 }
@@ -118,6 +164,7 @@ impl RenderText for SessionResponse {
                 .and_then(|fmt| match fmt {
                     "mermaid" => Some("```mermaid\n"),
                     "graphviz" => Some("```dot\n"),
+                    "json" => Some("```json\n"),
                     _ => None,
                 })
             {
@@ -147,6 +194,9 @@ struct EvalResponse {
     evaluated_sessions: usize,
     average_confidence: f32,
     failures: Vec<String>,
+    confidence_histogram: BTreeMap<String, usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rows_path: Option<String>,
     summary: String,
 }
 
@@ -156,6 +206,68 @@ impl RenderText for EvalResponse {
         if !self.failures.is_empty() {
             lines.push(format!("failing sessions: {}", self.failures.join(", ")));
         }
+        if !self.confidence_histogram.is_empty() {
+            lines.push("confidence histogram:".to_string());
+            for (bucket, count) in &self.confidence_histogram {
+                lines.push(format!("  {bucket}: {count}"));
+            }
+        }
+        if let Some(path) = &self.rows_path {
+            lines.push(format!("per-session rows written to {path}"));
+        }
+        lines.join("\n")
+    }
+}
+
+#[derive(Serialize)]
+struct EvalCompareResponse {
+    baseline: String,
+    candidate: String,
+    #[serde(flatten)]
+    comparison: EvalComparison,
+    summary: String,
+}
+
+#[derive(Serialize)]
+struct TraceDiffResponse {
+    action: &'static str,
+    session_id: String,
+    other_session_id: String,
+    #[serde(flatten)]
+    diff: TraceDiff,
+}
+
+impl RenderText for TraceDiffResponse {
+    fn render_text(&self) -> String {
+        format!(
+            "action: {}\nsession: {}\nother: {}\n\n{}",
+            self.action,
+            self.session_id,
+            self.other_session_id,
+            self.diff.render_markdown()
+        )
+    }
+}
+
+impl RenderText for EvalCompareResponse {
+    fn render_text(&self) -> String {
+        let mut lines = vec![
+            format!("baseline: {}", self.baseline),
+            format!("candidate: {}", self.candidate),
+            self.summary.clone(),
+        ];
+        if !self.comparison.new_failures.is_empty() {
+            lines.push(format!(
+                "new failures: {}",
+                self.comparison.new_failures.join(", ")
+            ));
+        }
+        if !self.comparison.fixed_sessions.is_empty() {
+            lines.push(format!(
+                "fixed sessions: {}",
+                self.comparison.fixed_sessions.join(", ")
+            ));
+        }
         lines.join("\n")
     }
 }
@@ -224,14 +336,14 @@ impl RenderText for BenchResponse {
     }
 }
 
-#[cfg(feature = "qdrant-retriever")]
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
 #[derive(Serialize)]
 struct IngestResponse {
     session_id: String,
     documents_indexed: usize,
 }
 
-#[cfg(feature = "qdrant-retriever")]
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
 impl RenderText for IngestResponse {
     fn render_text(&self) -> String {
         format!(
@@ -258,6 +370,103 @@ impl RenderText for PurgeResponse {
     }
 }
 
+#[derive(Serialize)]
+struct CloneResponse {
+    session_id: String,
+    cloned_from: String,
+}
+
+impl RenderText for CloneResponse {
+    fn render_text(&self) -> String {
+        format!("session {} cloned to {}", self.cloned_from, self.session_id)
+    }
+}
+
+#[derive(Serialize)]
+struct ExportResponse {
+    session_id: String,
+    output: String,
+}
+
+impl RenderText for ExportResponse {
+    fn render_text(&self) -> String {
+        format!("session {} exported to {}", self.session_id, self.output)
+    }
+}
+
+impl RenderText for CollectionInfo {
+    fn render_text(&self) -> String {
+        format!(
+            "session {}: {} document(s) indexed (vector dimension {})",
+            self.session_id, self.document_count, self.vector_dimension
+        )
+    }
+}
+
+#[derive(Serialize)]
+struct PruneResponse {
+    older_than_secs: u64,
+    deleted: usize,
+}
+
+impl RenderText for PruneResponse {
+    fn render_text(&self) -> String {
+        format!(
+            "pruned {} session(s) older than {}s",
+            self.deleted, self.older_than_secs
+        )
+    }
+}
+
+#[derive(Serialize)]
+struct ArchiveResponse {
+    older_than_days: u64,
+    archive_table: String,
+    moved_count: usize,
+}
+
+impl RenderText for ArchiveResponse {
+    fn render_text(&self) -> String {
+        format!(
+            "archived {} session(s) older than {}d into {}",
+            self.moved_count, self.older_than_days, self.archive_table
+        )
+    }
+}
+
+#[derive(Serialize)]
+struct ListResponse {
+    sessions: Vec<SessionSummary>,
+}
+
+impl RenderText for ListResponse {
+    fn render_text(&self) -> String {
+        if self.sessions.is_empty() {
+            return "no sessions found".to_string();
+        }
+        let id_width = self
+            .sessions
+            .iter()
+            .map(|session| session.session_id.len())
+            .max()
+            .unwrap_or_default()
+            .max("SESSION".len());
+        let mut lines = vec![format!(
+            "{:<id_width$}  {:<20}  QUERY",
+            "SESSION", "CREATED"
+        )];
+        for session in &self.sessions {
+            lines.push(format!(
+                "{:<id_width$}  {:<20}  {}",
+                session.session_id,
+                session.created_at.format("%Y-%m-%dT%H:%M:%SZ"),
+                session.query.as_deref().unwrap_or("-")
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
 fn emit_output<T>(format: OutputFormat, payload: &T) -> Result<()>
 where
     T: RenderText + Serialize,
@@ -295,6 +504,27 @@ struct QueryArgs {
     #[arg(long, default_value_t = 8)]
     qdrant_concurrency: usize,
 
+    /// SQLite database enabling local full-text retrieval (requires `sqlite-retriever`).
+    #[arg(long, value_name = "PATH")]
+    sqlite_db: Option<PathBuf>,
+
+    /// Abandon a task after the given number of seconds and continue with the next one
+    /// (repeatable, e.g. `--task-timeout researcher=30`).
+    #[arg(long = "task-timeout", value_name = "TASK_ID=SECONDS", value_parser = parse_task_timeout)]
+    task_timeouts: Vec<(String, Duration)>,
+
+    /// TOML file whose top-level keys seed the session's initial context.
+    #[arg(long, value_name = "PATH")]
+    context_file: Option<PathBuf>,
+
+    /// Cut each retrieved finding to at most this many words.
+    #[arg(long, value_name = "N")]
+    max_tokens_per_finding: Option<usize>,
+
+    /// Validate the graph and initial context without running any task.
+    #[arg(long)]
+    dry_run: bool,
+
     /// Persist trace events to disk even when not printing explanations.
     #[arg(long)]
     persist_trace: bool,
@@ -370,6 +600,19 @@ struct ResumeArgs {
     #[arg(long, default_value_t = 8)]
     qdrant_concurrency: usize,
 
+    /// SQLite database enabling local full-text retrieval (requires `sqlite-retriever`).
+    #[arg(long, value_name = "PATH")]
+    sqlite_db: Option<PathBuf>,
+
+    /// Abandon a task after the given number of seconds and continue with the next one
+    /// (repeatable, e.g. `--task-timeout researcher=30`).
+    #[arg(long = "task-timeout", value_name = "TASK_ID=SECONDS", value_parser = parse_task_timeout)]
+    task_timeouts: Vec<(String, Duration)>,
+
+    /// Cut each retrieved finding to at most this many words (use the session's original value).
+    #[arg(long, value_name = "N")]
+    max_tokens_per_finding: Option<usize>,
+
     /// Persist trace events to disk even when not printing explanations.
     #[arg(long)]
     persist_trace: bool,
@@ -418,9 +661,29 @@ struct ExplainArgs {
     #[arg(long, value_enum, default_value_t = ExplainFormat::Markdown)]
     explain_format: ExplainFormat,
 
-    /// Output format (text or JSON).
-    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
-    format: OutputFormat,
+    /// Compare this session's trace against another session instead of rendering it.
+    #[arg(long, value_name = "OTHER_SESSION_ID")]
+    diff: Option<String>,
+
+    /// Only render events emitted by this task (repeatable).
+    #[arg(long = "trace-task", value_name = "TASK_ID")]
+    trace_tasks: Vec<String>,
+
+    /// Keep polling a running session and print new trace events until it finishes.
+    #[arg(long, conflicts_with = "diff")]
+    watch: bool,
+
+    /// Poll interval for `--watch`, in milliseconds.
+    #[arg(long, value_name = "MS", default_value_t = 1000, requires = "watch")]
+    watch_interval_ms: u64,
+
+    /// Stop watching (with an error) if the session has not finished after this many seconds.
+    #[arg(long, value_name = "SECS", requires = "watch")]
+    watch_timeout_secs: Option<u64>,
+
+    /// Output format (text, JSON, or CSV trace export).
+    #[arg(long, value_enum, default_value_t = ExplainOutputFormat::Text)]
+    format: ExplainOutputFormat,
 
     /// Use Postgres-backed session storage.
     #[cfg(feature = "postgres-session")]
@@ -434,10 +697,14 @@ struct IngestArgs {
     #[arg(long)]
     session: String,
 
-    /// File or directory to ingest (text files expected).
+    /// File or directory to ingest (text files, or JSONL with `--input-format jsonl`).
     #[arg(long)]
     path: PathBuf,
 
+    /// How to read the ingested files (`--format` selects the output format).
+    #[arg(long, value_enum, default_value_t = DocumentFormat::Text)]
+    input_format: DocumentFormat,
+
     /// Recurse into subdirectories when ingesting.
     #[arg(long, default_value_t = true)]
     recursive: bool,
@@ -454,6 +721,18 @@ struct IngestArgs {
     #[arg(long, default_value_t = 8)]
     qdrant_concurrency: usize,
 
+    /// SQLite database enabling local full-text retrieval (requires `sqlite-retriever`).
+    #[arg(long, value_name = "PATH")]
+    sqlite_db: Option<PathBuf>,
+
+    /// Split documents into windows of this many words before embedding.
+    #[arg(long, value_name = "WORDS")]
+    chunk_size: Option<usize>,
+
+    /// Words shared between consecutive chunks (used with `--chunk-size`).
+    #[arg(long, value_name = "WORDS", default_value_t = 0)]
+    chunk_overlap: usize,
+
     /// Output format (text or JSON).
     #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
     format: OutputFormat,
@@ -462,12 +741,99 @@ struct IngestArgs {
 #[derive(Args, Debug)]
 struct EvalArgs {
     /// Path to the JSONL evaluation log.
-    #[arg(value_name = "LOG_PATH")]
-    path: PathBuf,
+    #[arg(value_name = "LOG_PATH", required_unless_present = "compare")]
+    path: Option<PathBuf>,
+
+    /// Diff two evaluation logs and report new failures, fixes, and confidence drift.
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["BASELINE", "CANDIDATE"],
+        conflicts_with_all = ["path", "output_jsonl"]
+    )]
+    compare: Option<Vec<PathBuf>>,
+
+    /// Output format (text or JSON).
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Write one JSON line per evaluated session (confidence, verdict, manual flag).
+    #[arg(long, value_name = "PATH")]
+    output_jsonl: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct CloneArgs {
+    /// Session ID to copy.
+    #[arg(value_name = "SOURCE_SESSION")]
+    source: String,
+
+    /// ID for the cloned session (defaults to a fresh UUID).
+    #[arg(long, value_name = "ID")]
+    new_id: Option<String>,
 
     /// Output format (text or JSON).
     #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
     format: OutputFormat,
+
+    /// Use Postgres-backed session storage.
+    #[cfg(feature = "postgres-session")]
+    #[arg(long, env = "DATABASE_URL")]
+    database_url: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct ExportArgs {
+    /// Session ID to export.
+    #[arg(value_name = "SESSION_ID")]
+    session: String,
+
+    /// Path of the ZIP bundle to write.
+    #[arg(long, value_name = "FILE")]
+    output: PathBuf,
+
+    /// Output format (text or JSON).
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Use Postgres-backed session storage.
+    #[cfg(feature = "postgres-session")]
+    #[arg(long, env = "DATABASE_URL")]
+    database_url: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct IndexInfoArgs {
+    /// Session ID whose indexed documents should be counted.
+    #[arg(value_name = "SESSION_ID")]
+    session: String,
+
+    /// Qdrant endpoint hosting the vector collection (requires `qdrant-retriever`).
+    #[arg(long, env = "DEEPRESEARCH_QDRANT_URL")]
+    qdrant_url: String,
+
+    /// Qdrant collection name.
+    #[arg(long, default_value = "deepresearch")]
+    qdrant_collection: String,
+
+    /// Output format (text or JSON).
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args, Debug)]
+struct ReplayArgs {
+    /// Trace file (JSON array of trace events) to play back.
+    #[arg(value_name = "TRACE_FILE")]
+    trace_file: PathBuf,
+
+    /// Playback speed multiplier; `0` prints every event immediately.
+    #[arg(long, default_value_t = 1.0)]
+    speed: f64,
+
+    /// Emit Server-Sent Events (`event: trace`) instead of plain lines.
+    #[arg(long)]
+    sse: bool,
 }
 
 #[derive(Args, Debug)]
@@ -476,6 +842,61 @@ struct PurgeArgs {
     #[arg(value_name = "SESSION_ID")]
     session: String,
 
+    /// Also erase the session's raw data-pipeline records (`DEEPRESEARCH_PIPELINE_DIR`).
+    #[arg(long)]
+    purge_pipeline: bool,
+
+    /// Qdrant endpoint whose points for this session should be deleted too.
+    #[cfg(feature = "qdrant-retriever")]
+    #[arg(long)]
+    qdrant_url: Option<String>,
+
+    /// Qdrant collection name (defaults to `deepresearch`).
+    #[cfg(feature = "qdrant-retriever")]
+    #[arg(long, default_value = "deepresearch")]
+    qdrant_collection: String,
+
+    /// Output format (text or JSON).
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Use Postgres-backed session storage.
+    #[cfg(feature = "postgres-session")]
+    #[arg(long, env = "DATABASE_URL")]
+    database_url: Option<String>,
+
+    /// Also delete the session's curated `session_records` rows in the same transaction.
+    #[cfg(feature = "postgres-session")]
+    #[arg(long)]
+    cascade_pipeline_records: bool,
+}
+
+#[derive(Args, Debug)]
+struct PruneArgs {
+    /// Minimum session age to delete (e.g. `30m`, `12h`, `7d`).
+    #[arg(long, value_name = "AGE", value_parser = parse_age)]
+    older_than: Duration,
+
+    /// Output format (text or JSON).
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Use Postgres-backed session storage.
+    #[cfg(feature = "postgres-session")]
+    #[arg(long, env = "DATABASE_URL")]
+    database_url: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct ArchiveArgs {
+    /// Minimum session age, in days, to archive.
+    #[arg(long, value_name = "N")]
+    older_than_days: u64,
+
+    /// Destination table (created if absent).
+    #[arg(long, default_value = "archived_sessions")]
+    archive_table: String,
+
     /// Output format (text or JSON).
     #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
     format: OutputFormat,
@@ -486,6 +907,76 @@ struct PurgeArgs {
     database_url: Option<String>,
 }
 
+#[derive(Args, Debug)]
+struct ListArgs {
+    /// Maximum number of sessions to return.
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Number of sessions to skip (for paging).
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+
+    /// Output format (text or JSON).
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Use Postgres-backed session storage.
+    #[cfg(feature = "postgres-session")]
+    #[arg(long, env = "DATABASE_URL")]
+    database_url: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct CompletionsArgs {
+    /// Shell to generate completions for.
+    #[arg(value_enum)]
+    shell: Shell,
+
+    /// Write completions to this file instead of stdout.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+fn parse_age(raw: &str) -> Result<Duration, String> {
+    let raw = raw.trim();
+    let split = raw
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(raw.len());
+    let (digits, unit) = raw.split_at(split);
+    let value: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid age '{raw}'; expected e.g. 30m, 12h or 7d"))?;
+    let seconds = match unit {
+        "" | "s" => value,
+        "m" => value * 60,
+        "h" => value * 60 * 60,
+        "d" => value * 60 * 60 * 24,
+        other => return Err(format!("unknown age unit '{other}'; use s, m, h or d")),
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+fn parse_task_timeout(raw: &str) -> Result<(String, Duration), String> {
+    let (task_id, seconds) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("invalid task timeout '{raw}'; expected TASK_ID=SECONDS"))?;
+    let task_id = task_id.trim();
+    if task_id.is_empty() {
+        return Err(format!("invalid task timeout '{raw}'; task id is empty"));
+    }
+    let seconds: f64 = seconds
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid timeout seconds in '{raw}'"))?;
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err(format!(
+            "timeout in '{raw}' must be a positive number of seconds"
+        ));
+    }
+    Ok((task_id.to_string(), Duration::from_secs_f64(seconds)))
+}
+
 fn main() -> Result<()> {
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new("info,deepresearch_core=info"));
@@ -507,8 +998,16 @@ fn main() -> Result<()> {
             Command::Explain(args) => explain_command(args).await?,
             Command::Ingest(args) => ingest_command(args).await?,
             Command::Eval(args) => eval_command(args).await?,
+            Command::Clone(args) => clone_command(args).await?,
+            Command::Export(args) => export_command(args).await?,
+            Command::IndexInfo(args) => index_info_command(args).await?,
+            Command::Replay(args) => replay_command(args).await?,
             Command::Purge(args) => purge_command(args).await?,
+            Command::Prune(args) => prune_command(args).await?,
+            Command::Archive(args) => archive_command(args).await?,
+            Command::List(args) => list_command(args).await?,
             Command::Bench(args) => bench_command(args).await?,
+            Command::Completions(args) => completions_command(args)?,
         }
         Ok::<(), anyhow::Error>(())
     })?;
@@ -544,6 +1043,28 @@ async fn query_command(args: QueryArgs) -> Result<()> {
         );
     }
 
+    #[cfg(not(feature = "sqlite-retriever"))]
+    if args.sqlite_db.is_some() {
+        warn!("sqlite retriever feature not enabled; ignoring --sqlite-db");
+    }
+
+    #[cfg(feature = "sqlite-retriever")]
+    if let Some(ref path) = args.sqlite_db {
+        options = options.with_sqlite_retriever(path.clone());
+    }
+
+    for (task_id, timeout) in &args.task_timeouts {
+        options = options.with_task_timeout(task_id.clone(), *timeout);
+    }
+
+    if let Some(path) = args.context_file.as_deref() {
+        options = options.with_context_file(path)?;
+    }
+
+    if let Some(limit) = args.max_tokens_per_finding {
+        options = options.with_max_tokens_per_finding(limit);
+    }
+
     if args.explain || args.persist_trace || args.trace_dir.is_some() {
         if let Some(dir) = args.trace_dir.as_ref() {
             options = options.with_trace_output_dir(dir.clone());
@@ -552,7 +1073,7 @@ async fn query_command(args: QueryArgs) -> Result<()> {
         }
     }
 
-    let outcome = run_research_session_with_report(options).await?;
+    let outcome = run_research_session_with_report(options.with_dry_run(args.dry_run)).await?;
     let (explanation, explanation_format) = if args.explain {
         match args.explain_format.render(&outcome) {
             Some(text) => (Some(text), Some(args.explain_format.label().to_string())),
@@ -602,6 +1123,24 @@ async fn resume_command(args: ResumeArgs) -> Result<()> {
         );
     }
 
+    #[cfg(not(feature = "sqlite-retriever"))]
+    if args.sqlite_db.is_some() {
+        warn!("sqlite retriever feature not enabled; ignoring --sqlite-db");
+    }
+
+    #[cfg(feature = "sqlite-retriever")]
+    if let Some(ref path) = args.sqlite_db {
+        options = options.with_sqlite_retriever(path.clone());
+    }
+
+    for (task_id, timeout) in &args.task_timeouts {
+        options = options.with_task_timeout(task_id.clone(), *timeout);
+    }
+
+    if let Some(limit) = args.max_tokens_per_finding {
+        options = options.with_max_tokens_per_finding(limit);
+    }
+
     if args.explain || args.persist_trace || args.trace_dir.is_some() {
         if let Some(dir) = args.trace_dir.as_ref() {
             options = options.with_trace_output_dir(dir.clone());
@@ -639,6 +1178,10 @@ async fn resume_command(args: ResumeArgs) -> Result<()> {
 }
 
 async fn explain_command(args: ExplainArgs) -> Result<()> {
+    if args.watch {
+        return explain_watch(&args).await;
+    }
+
     info!(session = %args.session, "rendering DeepResearch trace");
 
     let mut options = LoadOptions::new(args.session.clone());
@@ -648,15 +1191,45 @@ async fn explain_command(args: ExplainArgs) -> Result<()> {
         options = options.with_postgres_storage(url.clone());
     }
 
-    if args.persist_trace || args.trace_dir.is_some() {
-        if let Some(dir) = args.trace_dir.as_ref() {
-            options = options.with_trace_output_dir(dir.clone());
-        } else {
-            options = options.with_trace_output_dir(PathBuf::from("data/traces"));
-        }
+    let trace_dir = if args.persist_trace || args.trace_dir.is_some() {
+        let dir = args
+            .trace_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("data/traces"));
+        options = options.with_trace_output_dir(dir.clone());
+        Some(dir)
+    } else {
+        None
+    };
+
+    let mut outcome = load_session_report(options).await?;
+
+    if let Some(other_id) = args.diff.clone() {
+        return explain_diff(&args, outcome, other_id).await;
     }
 
-    let outcome = load_session_report(options).await?;
+    if !args.trace_tasks.is_empty() {
+        let task_ids: Vec<&str> = args.trace_tasks.iter().map(String::as_str).collect();
+        let filtered = TraceCollector::from_events(std::mem::take(&mut outcome.trace_events))
+            .filter_by_task(&task_ids);
+        outcome.trace_summary = filtered.summary();
+        outcome.trace_events = filtered.into_events();
+    }
+
+    let format = match args.format {
+        ExplainOutputFormat::Csv => {
+            if let Some(dir) = trace_dir.as_ref()
+                && let Err(err) =
+                    persist_trace_csv(dir, &outcome.session_id, &outcome.trace_summary)
+            {
+                warn!(session = %outcome.session_id, error = %err, "failed to persist trace CSV");
+            }
+            print!("{}", outcome.trace_summary.to_csv());
+            return Ok(());
+        }
+        ExplainOutputFormat::Text => OutputFormat::Text,
+        ExplainOutputFormat::Json => OutputFormat::Json,
+    };
 
     let explanation = args.explain_format.render(&outcome);
     let explanation_format = explanation
@@ -683,20 +1256,119 @@ async fn explain_command(args: ExplainArgs) -> Result<()> {
         explanation_format,
     };
 
-    emit_output(args.format, &response)
+    emit_output(format, &response)
 }
 
-#[cfg(feature = "qdrant-retriever")]
-async fn ingest_command(args: IngestArgs) -> Result<()> {
-    let qdrant_url = match args.qdrant_url {
-        Some(url) => url,
-        None => {
-            warn_stub_ingest();
-            return Ok(());
+/// Poll the stored session and print trace events added since the previous poll, one per line
+/// (`[task] message` or a JSON `TraceEvent`), until `final.summary` appears.
+async fn explain_watch(args: &ExplainArgs) -> Result<()> {
+    let format = match args.format {
+        ExplainOutputFormat::Text => OutputFormat::Text,
+        ExplainOutputFormat::Json => OutputFormat::Json,
+        ExplainOutputFormat::Csv => anyhow::bail!("--watch supports text or JSON output only"),
+    };
+    info!(
+        session = %args.session,
+        interval_ms = args.watch_interval_ms,
+        "watching DeepResearch trace"
+    );
+
+    let deadline = args
+        .watch_timeout_secs
+        .map(|secs| Instant::now() + Duration::from_secs(secs));
+    let interval = Duration::from_millis(args.watch_interval_ms.max(1));
+    let mut ticker = tokio::time::interval(interval);
+    let mut interrupted = std::pin::pin!(tokio::signal::ctrl_c());
+    let mut seen = 0;
+
+    loop {
+        tokio::select! {
+            _ = &mut interrupted => {
+                info!(session = %args.session, "watch interrupted");
+                return Ok(());
+            }
+            _ = ticker.tick() => {}
+        }
+
+        #[cfg_attr(not(feature = "postgres-session"), allow(unused_mut))]
+        let mut options = LoadOptions::new(args.session.clone());
+        #[cfg(feature = "postgres-session")]
+        if let Some(ref url) = args.database_url {
+            options = options.with_postgres_storage(url.clone());
+        }
+        let outcome = load_session_report(options).await?;
+
+        for event in outcome.trace_events.iter().skip(seen) {
+            if !args.trace_tasks.is_empty() && !args.trace_tasks.contains(&event.task_id) {
+                continue;
+            }
+            match format {
+                OutputFormat::Text => println!("[{}] {}", event.task_id, event.message),
+                OutputFormat::Json => println!("{}", serde_json::to_string(event)?),
+            }
+        }
+        seen = outcome.trace_events.len();
+
+        if outcome.completed {
+            let response = SessionResponse {
+                action: "explain-watch",
+                session_id: outcome.session_id,
+                summary: args.include_summary.then_some(outcome.summary),
+                trace_path: None,
+                explanation: None,
+                explanation_format: None,
+            };
+            return match format {
+                OutputFormat::Text => emit_output(format, &response),
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string(&response)?);
+                    Ok(())
+                }
+            };
         }
+
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            anyhow::bail!(
+                "session '{}' did not finish within {}s",
+                args.session,
+                args.watch_timeout_secs.unwrap_or_default()
+            );
+        }
+    }
+}
+
+async fn explain_diff(args: &ExplainArgs, outcome: SessionOutcome, other_id: String) -> Result<()> {
+    let format = match args.format {
+        ExplainOutputFormat::Text => OutputFormat::Text,
+        ExplainOutputFormat::Json => OutputFormat::Json,
+        ExplainOutputFormat::Csv => anyhow::bail!("--diff supports text or JSON output only"),
+    };
+
+    #[cfg_attr(not(feature = "postgres-session"), allow(unused_mut))]
+    let mut options = LoadOptions::new(other_id.clone());
+    #[cfg(feature = "postgres-session")]
+    if let Some(ref url) = args.database_url {
+        options = options.with_postgres_storage(url.clone());
+    }
+    let other = load_session_report(options).await?;
+
+    let response = TraceDiffResponse {
+        action: "explain-diff",
+        session_id: outcome.session_id,
+        other_session_id: other_id,
+        diff: outcome.trace_summary.diff(&other.trace_summary),
+    };
+    emit_output(format, &response)
+}
+
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
+async fn ingest_command(args: IngestArgs) -> Result<()> {
+    let Some(retriever) = ingest_retriever(&args) else {
+        warn_stub_ingest();
+        return Ok(());
     };
 
-    let documents = collect_documents(&args.path, args.recursive)?;
+    let documents = collect_documents(&args.path, args.recursive, args.input_format)?;
     if documents.is_empty() {
         info!(
             path = %args.path.display(),
@@ -714,11 +1386,9 @@ async fn ingest_command(args: IngestArgs) -> Result<()> {
     let options = IngestOptions {
         session_id: args.session.clone(),
         documents,
-        retriever: RetrieverChoice::qdrant(
-            qdrant_url,
-            args.qdrant_collection,
-            args.qdrant_concurrency,
-        ),
+        retriever,
+        chunk_size: args.chunk_size,
+        chunk_overlap: args.chunk_overlap,
     };
 
     ingest_docs(options).await?;
@@ -730,27 +1400,131 @@ async fn ingest_command(args: IngestArgs) -> Result<()> {
     emit_output(args.format, &response)
 }
 
-#[cfg(not(feature = "qdrant-retriever"))]
+/// Prefer the local SQLite index when both backends are configured.
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
+fn ingest_retriever(args: &IngestArgs) -> Option<RetrieverChoice> {
+    #[cfg(feature = "sqlite-retriever")]
+    if let Some(ref path) = args.sqlite_db {
+        return Some(RetrieverChoice::sqlite(path.clone()));
+    }
+
+    #[cfg(feature = "qdrant-retriever")]
+    if let Some(ref url) = args.qdrant_url {
+        return Some(RetrieverChoice::qdrant(
+            url.clone(),
+            args.qdrant_collection.clone(),
+            args.qdrant_concurrency,
+        ));
+    }
+
+    None
+}
+
+#[cfg(not(any(feature = "qdrant-retriever", feature = "sqlite-retriever")))]
 async fn ingest_command(args: IngestArgs) -> Result<()> {
     let _ = args;
     warn!(
-        "qdrant retriever feature not enabled; ingestion requires building with `--features deepresearch-cli/qdrant-retriever`"
+        "no retriever feature enabled; ingestion requires building with `--features deepresearch-cli/qdrant-retriever` or `deepresearch-cli/sqlite-retriever`"
     );
     Ok(())
 }
 
 async fn eval_command(args: EvalArgs) -> Result<()> {
-    let metrics = EvaluationHarness::analyze_log(&args.path)?;
+    if let Some(paths) = args.compare.as_deref() {
+        let [baseline, candidate] = paths else {
+            anyhow::bail!("--compare expects exactly two log paths");
+        };
+        let comparison = EvaluationHarness::compare_logs(baseline, candidate)?;
+        let response = EvalCompareResponse {
+            baseline: baseline.display().to_string(),
+            candidate: candidate.display().to_string(),
+            summary: comparison.summary(),
+            comparison,
+        };
+        return emit_output(args.format, &response);
+    }
+
+    let Some(path) = args.path.as_ref() else {
+        anyhow::bail!("missing evaluation log path");
+    };
+    let metrics = EvaluationHarness::analyze_log(path)?;
+    if let Some(path) = args.output_jsonl.as_ref() {
+        metrics.write_jsonl(path)?;
+    }
     let response = EvalResponse {
         total_sessions: metrics.total_sessions,
         evaluated_sessions: metrics.evaluated_sessions,
         average_confidence: metrics.average_confidence,
         failures: metrics.failures.clone(),
+        confidence_histogram: metrics.confidence_histogram.clone(),
+        rows_path: args
+            .output_jsonl
+            .as_ref()
+            .map(|path| path.display().to_string()),
         summary: metrics.summary(),
     };
     emit_output(args.format, &response)
 }
 
+async fn clone_command(args: CloneArgs) -> Result<()> {
+    let mut options = CloneOptions::new(args.source.clone());
+    if let Some(new_id) = args.new_id.clone() {
+        options = options.with_new_id(new_id);
+    }
+
+    #[cfg(feature = "postgres-session")]
+    if let Some(ref url) = args.database_url {
+        options = options.with_postgres_storage(url.clone());
+    }
+
+    let session_id = clone_session_with_options(options).await?;
+    let response = CloneResponse {
+        session_id,
+        cloned_from: args.source,
+    };
+    emit_output(args.format, &response)
+}
+
+async fn export_command(args: ExportArgs) -> Result<()> {
+    let options = ExportOptions::new(args.session.clone(), args.output.clone());
+
+    #[cfg(feature = "postgres-session")]
+    let options = match args.database_url.as_ref() {
+        Some(url) => options.with_postgres_storage(url.clone()),
+        None => options,
+    };
+
+    export_session_bundle_with_options(options).await?;
+    let response = ExportResponse {
+        session_id: args.session,
+        output: args.output.display().to_string(),
+    };
+    emit_output(args.format, &response)
+}
+
+async fn index_info_command(args: IndexInfoArgs) -> Result<()> {
+    let retriever = RetrieverChoice::qdrant(args.qdrant_url, args.qdrant_collection, 1);
+    let info = session_index_info(&retriever, &args.session).await?;
+    emit_output(args.format, &info)
+}
+
+async fn replay_command(args: ReplayArgs) -> Result<()> {
+    let raw = fs::read_to_string(&args.trace_file)
+        .with_context(|| format!("failed to read {}", args.trace_file.display()))?;
+    let events: Vec<TraceEvent> = serde_json::from_str(&raw)
+        .with_context(|| format!("{} is not a trace event array", args.trace_file.display()))?;
+
+    let mut stream = std::pin::pin!(TraceCollector::replay(events, args.speed));
+    while let Some(event) = stream.next().await {
+        if args.sse {
+            println!("event: trace\ndata: {}\n", serde_json::to_string(&event)?);
+        } else {
+            println!("[{}] {}", event.task_id, event.message);
+        }
+    }
+    Ok(())
+}
+
 async fn purge_command(args: PurgeArgs) -> Result<()> {
     let session_id = args.session.clone();
 
@@ -759,6 +1533,7 @@ async fn purge_command(args: PurgeArgs) -> Result<()> {
         let base = DeleteOptions::new(session_id.clone());
         if let Some(ref url) = args.database_url {
             base.with_postgres_storage(url.clone())
+                .with_cascade_delete_pipeline_records(args.cascade_pipeline_records)
         } else {
             base
         }
@@ -767,10 +1542,21 @@ async fn purge_command(args: PurgeArgs) -> Result<()> {
     #[cfg(not(feature = "postgres-session"))]
     let options = DeleteOptions::new(session_id.clone());
 
+    #[cfg(feature = "qdrant-retriever")]
+    let options = match args.qdrant_url.as_ref() {
+        Some(url) => options.with_qdrant_retriever(url.clone(), args.qdrant_collection.clone()),
+        None => options,
+    };
+
     let deleted = delete_session(options).await.is_ok();
     if let Err(err) = remove_session_logs(&session_id) {
         warn!(session = %session_id, error = %err, "failed to remove session logs");
     }
+    if args.purge_pipeline
+        && let Err(err) = remove_pipeline_records(&session_id)
+    {
+        warn!(session = %session_id, error = %err, "failed to remove pipeline records");
+    }
     purge_trace_file(&session_id);
     let response = PurgeResponse {
         session_id: session_id.clone(),
@@ -779,6 +1565,86 @@ async fn purge_command(args: PurgeArgs) -> Result<()> {
     emit_output(args.format, &response)
 }
 
+async fn prune_command(args: PruneArgs) -> Result<()> {
+    #[cfg(feature = "postgres-session")]
+    let storage = match args.database_url.as_ref() {
+        Some(url) => StorageChoice::postgres(url.clone()),
+        None => StorageChoice::InMemory,
+    };
+
+    #[cfg(not(feature = "postgres-session"))]
+    let storage = StorageChoice::InMemory;
+
+    let deleted = purge_old_sessions(&storage, args.older_than).await?;
+    let response = PruneResponse {
+        older_than_secs: args.older_than.as_secs(),
+        deleted,
+    };
+    emit_output(args.format, &response)
+}
+
+async fn archive_command(args: ArchiveArgs) -> Result<()> {
+    #[cfg(feature = "postgres-session")]
+    let storage = match args.database_url.as_ref() {
+        Some(url) => StorageChoice::postgres(url.clone()),
+        None => StorageChoice::InMemory,
+    };
+
+    #[cfg(not(feature = "postgres-session"))]
+    let storage = StorageChoice::InMemory;
+
+    let older_than = Duration::from_secs(args.older_than_days.saturating_mul(24 * 60 * 60));
+    let options = ArchiveOptions::new(older_than)
+        .with_storage(storage)
+        .with_archive_table(args.archive_table.clone());
+    let report = archive_old_sessions(options).await?;
+    let response = ArchiveResponse {
+        older_than_days: args.older_than_days,
+        archive_table: args.archive_table,
+        moved_count: report.moved_count,
+    };
+    emit_output(args.format, &response)
+}
+
+fn completions_command(args: CompletionsArgs) -> Result<()> {
+    let mut command = Cli::command();
+    match args.output {
+        Some(path) => {
+            let mut file = fs::File::create(&path)
+                .with_context(|| format!("failed to create {}", path.display()))?;
+            clap_complete::generate(args.shell, &mut command, "deepresearch-cli", &mut file);
+            info!(path = %path.display(), "wrote shell completions");
+        }
+        None => clap_complete::generate(
+            args.shell,
+            &mut command,
+            "deepresearch-cli",
+            &mut std::io::stdout(),
+        ),
+    }
+    Ok(())
+}
+
+async fn list_command(args: ListArgs) -> Result<()> {
+    #[cfg(feature = "postgres-session")]
+    let storage = match args.database_url.as_ref() {
+        Some(url) => StorageChoice::postgres(url.clone()),
+        None => StorageChoice::InMemory,
+    };
+
+    #[cfg(not(feature = "postgres-session"))]
+    let storage = StorageChoice::InMemory;
+
+    let mut options = ListOptions::new()
+        .with_storage(storage)
+        .with_offset(args.offset);
+    if let Some(limit) = args.limit {
+        options = options.with_limit(limit);
+    }
+    let sessions = list_sessions(options).await?;
+    emit_output(args.format, &ListResponse { sessions })
+}
+
 fn purge_trace_file(session_id: &str) {
     let trace_dir =
         std::env::var("DEEPRESEARCH_TRACE_DIR").unwrap_or_else(|_| "data/traces".to_string());
@@ -920,13 +1786,19 @@ async fn bench_command(args: BenchArgs) -> Result<()> {
     emit_output(args.format, &response)
 }
 
-#[cfg(feature = "qdrant-retriever")]
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
 fn warn_stub_ingest() {
-    warn!("no Qdrant URL provided; ingestion skipped (only stub retriever active)");
+    warn!(
+        "no Qdrant URL or SQLite database provided; ingestion skipped (only stub retriever active)"
+    );
 }
 
-#[cfg(feature = "qdrant-retriever")]
-fn collect_documents(path: &Path, recursive: bool) -> Result<Vec<IngestDocument>> {
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
+fn collect_documents(
+    path: &Path,
+    recursive: bool,
+    format: DocumentFormat,
+) -> Result<Vec<IngestDocument>> {
     let mut docs = Vec::new();
     let entries: Box<dyn Iterator<Item = PathBuf>> = if path.is_file() {
         Box::new(std::iter::once(path.to_path_buf()))
@@ -950,12 +1822,69 @@ fn collect_documents(path: &Path, recursive: bool) -> Result<Vec<IngestDocument>
         if text.trim().is_empty() {
             continue;
         }
-        docs.push(IngestDocument {
-            id: Uuid::new_v4().to_string(),
-            text,
-            source: Some(file.display().to_string()),
-        });
+        match format {
+            DocumentFormat::Text => docs.push(IngestDocument {
+                id: Uuid::new_v4().to_string(),
+                text,
+                source: Some(file.display().to_string()),
+            }),
+            DocumentFormat::Jsonl => docs.extend(parse_jsonl_documents(&file, &text)),
+        }
     }
 
     Ok(docs)
 }
+
+#[cfg(any(feature = "qdrant-retriever", feature = "sqlite-retriever"))]
+fn parse_jsonl_documents(file: &Path, text: &str) -> Vec<IngestDocument> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(
+            |(idx, line)| match serde_json::from_str::<IngestDocument>(line) {
+                Ok(doc) => Some(doc),
+                Err(err) => {
+                    warn!(
+                        path = %file.display(),
+                        line = idx + 1,
+                        error = %err,
+                        "skipping malformed JSONL document"
+                    );
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+#[cfg(all(test, any(feature = "qdrant-retriever", feature = "sqlite-retriever")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jsonl_ingest_parses_each_line_and_skips_malformed() {
+        let path = std::env::temp_dir().join(format!("ingest-{}.jsonl", Uuid::new_v4()));
+        fs::write(
+            &path,
+            concat!(
+                r#"{"id":"doc-1","text":"Solid-state battery pilot lines","source":"https://example.com/a"}"#,
+                "\n",
+                "not json\n",
+                "\n",
+                r#"{"id":"doc-2","text":"Sodium-ion cost curves"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let docs = collect_documents(&path, false, DocumentFormat::Jsonl).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].id, "doc-1");
+        assert_eq!(docs[0].source.as_deref(), Some("https://example.com/a"));
+        assert_eq!(docs[1].id, "doc-2");
+        assert_eq!(docs[1].text, "Sodium-ion cost curves");
+        assert!(docs[1].source.is_none());
+    }
+}
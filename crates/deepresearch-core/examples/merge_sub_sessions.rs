@@ -0,0 +1,43 @@
+//! Run two research sub-sessions in parallel and render a single merged trace.
+//!
+//! ```bash
+//! cargo run -p deepresearch-core --example merge_sub_sessions
+//! ```
+
+use anyhow::Result;
+use deepresearch_core::{
+    SessionOptions, TraceCollector, TraceSummary, run_research_session_with_report,
+};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let sub_queries = [
+        ("market", "Sodium-ion market share forecasts"),
+        ("policy", "Sodium-ion subsidy and policy landscape"),
+    ];
+
+    let (market, policy) = tokio::try_join!(
+        run_research_session_with_report(
+            SessionOptions::new(sub_queries[0].1)
+                .with_session_id("sub-market")
+                .enable_trace(),
+        ),
+        run_research_session_with_report(
+            SessionOptions::new(sub_queries[1].1)
+                .with_session_id("sub-policy")
+                .enable_trace(),
+        ),
+    )?;
+
+    let merged = TraceCollector::from_events(market.trace_events.clone())
+        .merge(TraceCollector::from_events(policy.trace_events.clone()));
+    println!("merged {} trace events\n", merged.events().len());
+
+    let summary = TraceSummary::from_merged_events([
+        (sub_queries[0].0, market.trace_events.as_slice()),
+        (sub_queries[1].0, policy.trace_events.as_slice()),
+    ]);
+    println!("{}", summary.render_markdown());
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! Register a custom `SummarizerTask` as a task plugin and splice it between the analyst
+//! and the fact-check step.
+//!
+//! ```bash
+//! cargo run -p deepresearch-core --features task-plugins --example summarizer_plugin
+//! ```
+
+use anyhow::Result;
+use async_trait::async_trait;
+use deepresearch_core::{
+    AnalystOutput, BaseGraphTasks, SessionOptions, TaskPlugin, run_research_session_with_report,
+};
+use graph_flow::{Context, GraphBuilder, NextAction, Task, TaskResult};
+use std::sync::Arc;
+
+const SUMMARIZER_ID: &str = "summarizer";
+
+/// Replaces the analyst's key insight with the first sentence of its summary.
+struct SummarizerTask;
+
+#[async_trait]
+impl Task for SummarizerTask {
+    fn id(&self) -> &str {
+        SUMMARIZER_ID
+    }
+
+    async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
+        let mut analysis: AnalystOutput = context.get("analysis.output").await.unwrap_or_default();
+        if let Some(first) = analysis.summary.split_inclusive('.').next() {
+            analysis.highlight = format!("TL;DR {}", first.trim());
+        }
+        context.set("analysis.output", analysis).await;
+
+        Ok(TaskResult::new(
+            Some("Summarizer condensed the analysis".to_string()),
+            NextAction::ContinueAndExecute,
+        ))
+    }
+}
+
+deepresearch_core::inventory::submit! {
+    TaskPlugin::new(SUMMARIZER_ID, || Arc::new(SummarizerTask))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Plugins are added as graph nodes automatically; edges added here run before the
+    // default wiring, so `analyst -> summarizer -> fact_check` takes precedence.
+    let options = SessionOptions::new("Sodium-ion market share forecasts").with_customizer(
+        Box::new(|builder: GraphBuilder, base: &BaseGraphTasks| {
+            builder
+                .add_edge(base.analyst.id(), SUMMARIZER_ID)
+                .add_edge(SUMMARIZER_ID, base.fact_check.id())
+        }),
+    );
+
+    let outcome = run_research_session_with_report(options).await?;
+    println!("{}", outcome.summary);
+    Ok(())
+}
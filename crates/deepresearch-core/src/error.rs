@@ -0,0 +1,61 @@
+use thiserror::Error;
+
+/// Error returned by the public session entry points so callers can branch on the failure
+/// kind instead of matching message text. Internals keep using `anyhow`; errors raised as a
+/// typed variant deep inside the workflow are recovered when converting back.
+#[derive(Debug, Error)]
+pub enum DeepResearchError {
+    #[error("session '{0}' not found")]
+    SessionNotFound(String),
+    /// The operation would overwrite or collide with an existing session.
+    #[error("{0}")]
+    SessionConflict(String),
+    #[error("{0}")]
+    StorageError(String),
+    #[error("{0}")]
+    SandboxError(String),
+    #[error("{0}")]
+    RetrieverError(String),
+    /// The graph failed validation (unknown start task, cycles, unreachable tasks).
+    #[error("{0}")]
+    PlanningError(String),
+    /// A task failed while the graph was running.
+    #[error("{0}")]
+    ExecutionError(String),
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<anyhow::Error> for DeepResearchError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<DeepResearchError>() {
+            Ok(typed) => typed,
+            Err(error) => DeepResearchError::Other(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Context, anyhow};
+
+    #[test]
+    fn typed_variants_survive_anyhow_round_trip() {
+        let wrapped = anyhow::Error::new(DeepResearchError::SessionNotFound("abc".into()))
+            .context("while resuming");
+        let err = DeepResearchError::from(wrapped);
+        assert!(matches!(err, DeepResearchError::SessionNotFound(ref id) if id == "abc"));
+        assert_eq!(err.to_string(), "session 'abc' not found");
+
+        let err = DeepResearchError::from(anyhow!("boom"));
+        assert!(matches!(err, DeepResearchError::Other(_)));
+        assert_eq!(err.to_string(), "boom");
+
+        let err: DeepResearchError = Err::<(), _>(std::io::Error::other("disk"))
+            .context("reading trace")
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, DeepResearchError::Other(_)));
+    }
+}
@@ -1,9 +1,10 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Clone, Deserialize)]
 pub struct FactCheckLog {
@@ -16,6 +17,22 @@ pub struct LogEntry {
     pub session_id: Option<String>,
     #[serde(default)]
     pub factcheck: Option<FactCheckLog>,
+    #[serde(default)]
+    pub verdict: Option<String>,
+    #[serde(default)]
+    pub requires_manual: bool,
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+/// Per-session evaluation result, emitted as one JSONL line by [`EvaluationMetrics::write_jsonl`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionEvalRow {
+    pub session_id: Option<String>,
+    pub confidence: f32,
+    pub verdict: Option<String>,
+    pub requires_manual: bool,
+    pub sources_count: usize,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -24,6 +41,11 @@ pub struct EvaluationMetrics {
     pub evaluated_sessions: usize,
     pub average_confidence: f32,
     pub failures: Vec<String>,
+    /// Session counts keyed by the lower bound of each 0.1-wide confidence bucket (`"0.0"`..`"0.9"`).
+    pub confidence_histogram: BTreeMap<String, usize>,
+    pub per_session: Vec<SessionEvalRow>,
+    /// Total sources cited across every logged session.
+    pub sources_count: usize,
 }
 
 impl EvaluationMetrics {
@@ -32,11 +54,27 @@ impl EvaluationMetrics {
         self.average_confidence =
             ((self.average_confidence * (self.evaluated_sessions - 1) as f32) + log.confidence)
                 / self.evaluated_sessions as f32;
+        *self
+            .confidence_histogram
+            .entry(confidence_bucket(log.confidence))
+            .or_default() += 1;
         if let (false, Some(id)) = (log.passed, session_id) {
             self.failures.push(id);
         }
     }
 
+    pub fn write_jsonl(&self, path: &Path) -> Result<()> {
+        let file =
+            File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        for row in &self.per_session {
+            serde_json::to_writer(&mut writer, row)?;
+            writeln!(writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
     pub fn summary(&self) -> String {
         format!(
             "evaluated {}/{} sessions • avg confidence {:.2} • {} failure(s)",
@@ -48,6 +86,41 @@ impl EvaluationMetrics {
     }
 }
 
+/// Diff between a baseline and a candidate evaluation log, produced by
+/// [`EvaluationHarness::compare_logs`].
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct EvalComparison {
+    /// Sessions failing fact-check in the candidate that did not fail in the baseline.
+    pub new_failures: Vec<String>,
+    /// Sessions failing in the baseline that pass in the candidate.
+    pub fixed_sessions: Vec<String>,
+    /// Candidate average confidence minus baseline average confidence.
+    pub confidence_delta: f32,
+    /// Sessions present in both logs that newly fail or lost confidence.
+    pub regression_count: usize,
+}
+
+impl EvalComparison {
+    pub fn has_regressions(&self) -> bool {
+        self.regression_count > 0
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "confidence delta {:+.2} • {} regression(s) • {} new failure(s) • {} fixed",
+            self.confidence_delta,
+            self.regression_count,
+            self.new_failures.len(),
+            self.fixed_sessions.len()
+        )
+    }
+}
+
+fn confidence_bucket(confidence: f32) -> String {
+    let bucket = (confidence.clamp(0.0, 1.0) * 10.0).floor().min(9.0);
+    format!("{:.1}", bucket / 10.0)
+}
+
 pub struct EvaluationHarness;
 
 impl EvaluationHarness {
@@ -64,7 +137,15 @@ impl EvaluationHarness {
             match serde_json::from_str::<LogEntry>(&line) {
                 Ok(entry) => {
                     metrics.total_sessions += 1;
+                    metrics.sources_count += entry.sources.len();
                     if let Some(fact) = entry.factcheck {
+                        metrics.per_session.push(SessionEvalRow {
+                            session_id: entry.session_id.clone(),
+                            confidence: fact.confidence,
+                            verdict: entry.verdict,
+                            requires_manual: entry.requires_manual,
+                            sources_count: entry.sources.len(),
+                        });
                         metrics.record(entry.session_id, &fact);
                     }
                 }
@@ -76,6 +157,55 @@ impl EvaluationHarness {
 
         Ok(metrics)
     }
+
+    /// Compare two evaluation logs (e.g. yesterday's nightly run against today's).
+    pub fn compare_logs(baseline: &Path, candidate: &Path) -> Result<EvalComparison> {
+        let baseline = Self::analyze_log(baseline)?;
+        let candidate = Self::analyze_log(candidate)?;
+        Ok(compare_metrics(&baseline, &candidate))
+    }
+}
+
+fn compare_metrics(baseline: &EvaluationMetrics, candidate: &EvaluationMetrics) -> EvalComparison {
+    let confidences = |metrics: &EvaluationMetrics| -> BTreeMap<String, f32> {
+        metrics
+            .per_session
+            .iter()
+            .filter_map(|row| row.session_id.clone().map(|id| (id, row.confidence)))
+            .collect()
+    };
+    let baseline_confidence = confidences(baseline);
+    let candidate_confidence = confidences(candidate);
+    let baseline_failures: BTreeSet<&String> = baseline.failures.iter().collect();
+    let candidate_failures: BTreeSet<&String> = candidate.failures.iter().collect();
+
+    let new_failures: Vec<String> = candidate_failures
+        .difference(&baseline_failures)
+        .map(|id| (*id).clone())
+        .collect();
+    let fixed_sessions: Vec<String> = baseline_failures
+        .difference(&candidate_failures)
+        .filter(|id| candidate_confidence.contains_key(id.as_str()))
+        .map(|id| (*id).clone())
+        .collect();
+
+    let regression_count = candidate_confidence
+        .iter()
+        .filter(|(id, confidence)| match baseline_confidence.get(*id) {
+            Some(previous) => {
+                **confidence < *previous
+                    || (candidate_failures.contains(id) && !baseline_failures.contains(id))
+            }
+            None => false,
+        })
+        .count();
+
+    EvalComparison {
+        new_failures,
+        fixed_sessions,
+        confidence_delta: candidate.average_confidence - baseline.average_confidence,
+        regression_count,
+    }
 }
 
 #[cfg(test)]
@@ -109,5 +239,82 @@ mod tests {
         assert_eq!(metrics.evaluated_sessions, 2);
         assert!((metrics.average_confidence - 0.6).abs() < f32::EPSILON);
         assert_eq!(metrics.failures, vec!["b".to_string()]);
+        assert_eq!(metrics.confidence_histogram.get("0.8"), Some(&1));
+        assert_eq!(metrics.confidence_histogram.get("0.4"), Some(&1));
+        assert_eq!(metrics.per_session.len(), 2);
+    }
+
+    #[test]
+    fn evaluation_metrics_write_session_rows_as_jsonl() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("deepresearch-eval-{}.log", Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"session_id":"a","factcheck":{"confidence":1.0,"passed":true},"verdict":"ok","sources":["a.txt","b.txt"]}"#,
+                "\n",
+                r#"{"session_id":"b","factcheck":{"confidence":0.05,"passed":false},"requires_manual":true}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        let metrics = EvaluationHarness::analyze_log(&path).expect("metrics");
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(metrics.confidence_histogram.get("0.9"), Some(&1));
+        assert_eq!(metrics.confidence_histogram.get("0.0"), Some(&1));
+        assert_eq!(metrics.sources_count, 2);
+
+        let output = path.with_extension("rows.jsonl");
+        metrics.write_jsonl(&output).expect("write jsonl");
+        let contents = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_file(&output).ok();
+
+        let rows: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["verdict"], "ok");
+        assert_eq!(rows[1]["requires_manual"], true);
+        assert_eq!(rows[0]["sources_count"], 2);
+    }
+
+    #[test]
+    fn compare_logs_reports_regressions_and_fixes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let baseline = dir.path().join("baseline.jsonl");
+        let candidate = dir.path().join("candidate.jsonl");
+        std::fs::write(
+            &baseline,
+            concat!(
+                r#"{"session_id":"a","factcheck":{"confidence":0.9,"passed":true}}"#,
+                "\n",
+                r#"{"session_id":"b","factcheck":{"confidence":0.4,"passed":false}}"#,
+                "\n",
+                r#"{"session_id":"c","factcheck":{"confidence":0.8,"passed":true}}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            &candidate,
+            concat!(
+                r#"{"session_id":"a","factcheck":{"confidence":0.5,"passed":false}}"#,
+                "\n",
+                r#"{"session_id":"b","factcheck":{"confidence":0.85,"passed":true}}"#,
+                "\n",
+                r#"{"session_id":"c","factcheck":{"confidence":0.7,"passed":true}}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let comparison = EvaluationHarness::compare_logs(&baseline, &candidate).expect("compare");
+        assert_eq!(comparison.new_failures, vec!["a".to_string()]);
+        assert_eq!(comparison.fixed_sessions, vec!["b".to_string()]);
+        assert_eq!(comparison.regression_count, 2);
+        assert!((comparison.confidence_delta - (-0.05 / 3.0)).abs() < 1e-5);
+        assert!(comparison.has_regressions());
     }
 }
@@ -0,0 +1,116 @@
+use crate::sandbox::SandboxChoice;
+use crate::workflow::{RetrieverChoice, build_retriever};
+use graph_flow::SessionStorage;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Session id used for storage and retriever probes; never written.
+const HEALTH_PROBE_SESSION: &str = "__deepresearch_health__";
+
+/// Readiness of the backends a research session depends on.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HealthStatus {
+    pub storage_ok: bool,
+    pub retriever_ok: bool,
+    /// `None` when no sandbox is configured.
+    pub sandbox_ok: Option<bool>,
+    /// Error messages keyed by component (`storage`, `retriever`, `sandbox`).
+    pub details: HashMap<String, String>,
+}
+
+impl HealthStatus {
+    /// True when every configured component responded.
+    pub fn is_healthy(&self) -> bool {
+        self.storage_ok && self.retriever_ok && self.sandbox_ok.unwrap_or(true)
+    }
+}
+
+/// Probe storage with a lookup, the retriever with a zero-document retrieve (Qdrant with a
+/// client health check), and the sandbox (when configured) by constructing its runner
+/// without executing anything.
+pub async fn check_health(
+    storage: Arc<dyn SessionStorage>,
+    retriever: &RetrieverChoice,
+    sandbox: Option<&SandboxChoice>,
+) -> HealthStatus {
+    let mut status = HealthStatus::default();
+
+    match storage.get(HEALTH_PROBE_SESSION).await {
+        Ok(_) => status.storage_ok = true,
+        Err(err) => {
+            status.details.insert("storage".into(), err.to_string());
+        }
+    }
+
+    match probe_retriever(retriever).await {
+        Ok(_) => status.retriever_ok = true,
+        Err(err) => {
+            status
+                .details
+                .insert("retriever".into(), format!("{err:#}"));
+        }
+    }
+
+    if let Some(choice) = sandbox {
+        match choice.build() {
+            Ok(_) => status.sandbox_ok = Some(true),
+            Err(err) => {
+                status.sandbox_ok = Some(false);
+                status.details.insert("sandbox".into(), format!("{err:#}"));
+            }
+        }
+    }
+
+    status
+}
+
+/// Qdrant gets a bare client health check: building a `HybridRetriever` would load the
+/// embedding model and ensure the collection exists on every readiness poll.
+async fn probe_retriever(retriever: &RetrieverChoice) -> anyhow::Result<()> {
+    match retriever {
+        RetrieverChoice::Qdrant { url, .. } => probe_qdrant(url).await,
+        other => build_retriever(other)
+            .await?
+            .retrieve(HEALTH_PROBE_SESSION, "", 0)
+            .await
+            .map(|_| ()),
+    }
+}
+
+#[cfg(feature = "qdrant-retriever")]
+async fn probe_qdrant(url: &str) -> anyhow::Result<()> {
+    let client = qdrant_client::Qdrant::from_url(url)
+        .build()
+        .map_err(|err| anyhow::anyhow!("failed to create Qdrant client: {err}"))?;
+    client
+        .health_check()
+        .await
+        .map_err(|err| anyhow::anyhow!("qdrant health check failed: {err}"))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "qdrant-retriever"))]
+async fn probe_qdrant(_url: &str) -> anyhow::Result<()> {
+    Err(anyhow::anyhow!(
+        "qdrant retriever support not enabled; rebuild with `--features deepresearch-core/qdrant-retriever`"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph_flow::InMemorySessionStorage;
+
+    #[tokio::test]
+    async fn in_memory_stub_backends_are_healthy() {
+        let storage: Arc<dyn SessionStorage> = Arc::new(InMemorySessionStorage::new());
+        let status = check_health(storage, &RetrieverChoice::Stub, None).await;
+
+        assert!(status.storage_ok);
+        assert!(status.retriever_ok);
+        assert_eq!(status.sandbox_ok, None);
+        assert!(status.details.is_empty());
+        assert!(status.is_healthy());
+    }
+}
@@ -3,35 +3,75 @@
 //! This crate provides reusable tasks and helper utilities to orchestrate a
 //! research workflow consisting of Researcher, Analyst, and Critic agents.
 
+mod error;
 mod eval;
+mod health;
 mod logging;
 mod memory;
 mod metrics;
+mod otel;
 mod pipeline;
+#[cfg(feature = "task-plugins")]
+mod plugin;
 mod sandbox;
 mod tasks;
 mod trace;
 mod workflow;
 
-pub use eval::{EvaluationHarness, EvaluationMetrics};
-pub use logging::remove_session_logs;
-pub use memory::{IngestDocument, RetrievedDocument};
-pub use metrics::{init_metrics_from_env, record_sandbox_metrics, shutdown_metrics};
-pub use pipeline::persist_session_record;
+pub use error::DeepResearchError;
+pub use eval::{EvalComparison, EvaluationHarness, EvaluationMetrics, SessionEvalRow};
+pub use health::{HealthStatus, check_health};
+#[cfg(feature = "task-plugins")]
+pub use inventory;
+pub use logging::{
+    AuditEntry, AuditQuery, LogRedactor, LogRotation, log_base_dir, read_audit_entries,
+    remove_session_logs,
+};
+pub use memory::{
+    CollectionInfo, DynRetriever, IngestDocument, RetrievedDocument, Retriever, SeedRetriever,
+};
+#[cfg(feature = "qdrant-retriever")]
+pub use memory::{HybridRetriever, QdrantConfig};
+pub use metrics::{
+    init_metrics_from_env, record_sandbox_metrics, record_sandbox_queue_change,
+    record_sandbox_resource_usage, record_session_metrics, shutdown_metrics,
+};
+pub use otel::{OTEL_SPAN_ID_KEY, OTEL_TRACE_ID_KEY, parse_traceparent};
+pub use pipeline::{persist_session_record, remove_pipeline_records};
+#[cfg(feature = "task-plugins")]
+pub use plugin::{TaskPlugin, registered_task_plugins};
 pub use sandbox::{
-    DockerRuntimeUser, DockerSandboxConfig, DockerSandboxRunner, SandboxExecutor, SandboxOutput,
-    SandboxOutputKind, SandboxOutputSpec, SandboxRequest, SandboxResult,
+    DockerRuntimeUser, DockerSandboxConfig, DockerSandboxRunner, SandboxCache, SandboxChoice,
+    SandboxExecutor, SandboxOutput, SandboxOutputKind, SandboxOutputSpec, SandboxRequest,
+    SandboxResourceUsage, SandboxResult, SandboxRuntime, SandboxVolumeMount,
 };
+#[cfg(any(test, feature = "test-utils"))]
+pub use sandbox::{MockSandboxExecutor, SandboxRequestPredicate};
+#[cfg(feature = "wasm-sandbox")]
+pub use sandbox::{WasmSandboxConfig, WasmSandboxRunner};
 pub use tasks::{
-    AnalystOutput, AnalystTask, CriticTask, FactCheckSettings, FactCheckTask, FinalizeTask,
-    ManualReviewTask, MathToolOutput, MathToolRequest, MathToolResult, MathToolStatus,
-    MathToolTask, ResearchTask,
+    AnalystOutput, AnalystTask, CitationStyle, CriticInput, CriticScorer, CriticTask,
+    CriticVerdict, FactCheckSettings, FactCheckTask, FinalizeOutputFormat, FinalizeTask,
+    ManualReviewTask, ManualReviewWebhook, MathResourceLimits, MathRetryPolicy, MathToolOutput,
+    MathToolRequest, MathToolResult, MathToolStatus, MathToolTask, ResearchTask, ResultScorer,
+    RetryConfig, SourceReference, SummaryTemplate, deduplicate_sources, extract_citations,
+};
+#[cfg(feature = "s3-traces")]
+pub use trace::persist_trace_s3;
+pub use trace::{
+    AppendTraceOptions, TRACE_TAG_CONFIDENCE_SCORE, TRACE_TAG_SANDBOX_EXIT_CODE, TRACE_TAG_VERDICT,
+    TraceCollector, TraceDiff, TraceEvent, TraceOutput, TraceStep, TraceStream, TraceSummary,
+    persist_trace, persist_trace_csv, persist_trace_replace, persist_trace_with_options,
+    trace_object_key,
 };
-pub use trace::{TraceCollector, TraceEvent, TraceStep, TraceSummary, persist_trace};
 pub use workflow::{
-    BaseGraphTasks, DeleteOptions, GraphCustomizer, IngestOptions, LoadOptions, ResumeOptions,
-    RetrieverChoice, SessionOptions, SessionOutcome, StorageChoice, delete_session,
-    ingest_documents, load_session_report, resume_research_session,
+    ArchiveOptions, ArchiveReport, BaseGraphTasks, BulkIngestReport, CloneOptions, ContextSchema,
+    ContextValueType, DeleteOptions, ExportOptions, GraphCustomizer, IngestOptions, ListOptions,
+    LoadOptions, QueryPreprocessor, ResumeOptions, RetrieverChoice, SessionOptions, SessionOutcome,
+    SessionSummary, StandardPipelineBuilder, StorageChoice, archive_old_sessions, clone_session,
+    clone_session_with_options, delete_session, export_session_bundle,
+    export_session_bundle_with_options, ingest_documents, ingest_documents_bulk, list_sessions,
+    load_session_report, purge_old_sessions, resume_research_session,
     resume_research_session_with_report, run_research_session, run_research_session_with_options,
-    run_research_session_with_report,
+    run_research_session_with_report, session_index_info,
 };
@@ -1,8 +1,8 @@
 use anyhow::{Context, Result};
-use chrono::{Datelike, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs::{self, File, OpenOptions, create_dir_all};
 use std::io::{BufRead, BufReader, BufWriter, Write};
@@ -12,31 +12,136 @@ use tracing::warn;
 
 const LOG_DIR_ENV: &str = "DEEPRESEARCH_LOG_DIR";
 const RETENTION_ENV: &str = "DEEPRESEARCH_LOG_RETENTION_DAYS";
+const ROTATION_ENV: &str = "DEEPRESEARCH_LOG_ROTATION";
 const DEFAULT_LOG_DIR: &str = "data/logs";
 const DEFAULT_RETENTION_DAYS: u64 = 90;
 
-static REDACTION_PATTERNS: Lazy<Vec<(String, Regex)>> = Lazy::new(|| {
-    vec![
-        (
-            "api_key".to_string(),
-            Regex::new(r"(?i)(api[_-]?key\s*[:=]\s*)([A-Za-z0-9\-_.+/]+)")
-                .expect("invalid api_key regex"),
-        ),
-        (
-            "secret".to_string(),
-            Regex::new(r"(?i)(secret\s*[:=]\s*)([A-Za-z0-9\-_.+/]+)")
-                .expect("invalid secret regex"),
-        ),
-        (
-            "bearer".to_string(),
-            Regex::new(r"(?i)(bearer\s+)([A-Za-z0-9\-_.+=/]+)").expect("invalid bearer regex"),
-        ),
-        (
-            "sk_token".to_string(),
-            Regex::new(r"(sk-[A-Za-z0-9]{16,})").expect("invalid sk_token regex"),
-        ),
-    ]
-});
+static DEFAULT_REDACTOR: Lazy<LogRedactor> = Lazy::new(LogRedactor::default);
+
+/// Scrubs API keys and other credentials from free text before it is persisted.
+///
+/// The default instance covers `api_key=`, `secret=`, bearer tokens, and `sk-` keys;
+/// callers can register additional patterns with [`LogRedactor::with_custom_pattern`].
+#[derive(Debug, Clone)]
+pub struct LogRedactor {
+    patterns: Vec<(String, Regex)>,
+}
+
+impl Default for LogRedactor {
+    fn default() -> Self {
+        Self {
+            patterns: vec![
+                (
+                    "api_key".to_string(),
+                    Regex::new(r"(?i)(api[_-]?key\s*[:=]\s*)([A-Za-z0-9\-_.+/]+)")
+                        .expect("invalid api_key regex"),
+                ),
+                (
+                    "secret".to_string(),
+                    Regex::new(r"(?i)(secret\s*[:=]\s*)([A-Za-z0-9\-_.+/]+)")
+                        .expect("invalid secret regex"),
+                ),
+                (
+                    "bearer".to_string(),
+                    Regex::new(r"(?i)(bearer\s+)([A-Za-z0-9\-_.+=/]+)")
+                        .expect("invalid bearer regex"),
+                ),
+                (
+                    "sk_token".to_string(),
+                    Regex::new(r"(sk-[A-Za-z0-9]{16,})").expect("invalid sk_token regex"),
+                ),
+            ],
+        }
+    }
+}
+
+impl LogRedactor {
+    /// Add a named pattern. When the regex has a capture group, the first group is kept
+    /// as a prefix and the rest of the match is replaced with `[REDACTED]`.
+    pub fn with_custom_pattern(mut self, name: impl Into<String>, regex: Regex) -> Self {
+        self.patterns.push((name.into(), regex));
+        self
+    }
+
+    /// Return the redacted text and the names of the patterns that matched.
+    pub fn sanitize(&self, input: &str) -> (String, Vec<String>) {
+        let mut redactions = HashSet::new();
+        let output = self.sanitize_into(input, &mut redactions);
+        let mut names: Vec<String> = redactions.into_iter().collect();
+        names.sort();
+        (output, names)
+    }
+
+    fn sanitize_into(&self, input: &str, redactions: &mut HashSet<String>) -> String {
+        let mut output = input.to_string();
+        for (name, regex) in &self.patterns {
+            let mut matched = false;
+            output = regex
+                .replace_all(&output, |caps: &Captures| {
+                    matched = true;
+                    if caps.len() > 1 {
+                        format!("{}[REDACTED]", &caps[1])
+                    } else {
+                        "[REDACTED]".to_string()
+                    }
+                })
+                .to_string();
+            if matched {
+                redactions.insert(name.clone());
+            }
+        }
+        output
+    }
+}
+
+/// Directory granularity for session and audit logs under [`log_base_dir`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogRotation {
+    /// `<year>/<month>/` (the historical layout).
+    #[default]
+    Monthly,
+    /// `<year>/<month>/<day>/`
+    Daily,
+    /// `<year>/<month>/<day>/<hour>/` (UTC)
+    Hourly,
+}
+
+impl LogRotation {
+    /// Read `DEEPRESEARCH_LOG_ROTATION` (`monthly`, `daily`, `hourly`); unset or unknown
+    /// values fall back to monthly.
+    pub fn from_env() -> Self {
+        match std::env::var(ROTATION_ENV) {
+            Ok(value) => Self::parse(&value).unwrap_or_else(|| {
+                warn!(value = %value, "unknown {ROTATION_ENV}; using monthly rotation");
+                Self::Monthly
+            }),
+            Err(_) => Self::Monthly,
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "monthly" => Some(Self::Monthly),
+            "daily" => Some(Self::Daily),
+            "hourly" => Some(Self::Hourly),
+            _ => None,
+        }
+    }
+
+    /// Directory that receives log lines written at `timestamp`.
+    pub fn log_dir(self, base_dir: &Path, timestamp: DateTime<Utc>) -> PathBuf {
+        let mut dir = base_dir
+            .join(format!("{:04}", timestamp.year()))
+            .join(format!("{:02}", timestamp.month()));
+        if matches!(self, Self::Daily | Self::Hourly) {
+            dir = dir.join(format!("{:02}", timestamp.day()));
+        }
+        if self == Self::Hourly {
+            dir = dir.join(format!("{:02}", timestamp.hour()));
+        }
+        dir
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct SessionLogInput {
@@ -62,14 +167,60 @@ struct SessionLogRecord {
     redactions: Vec<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct AuditLogRecord {
     timestamp: String,
     session_id: String,
     redactions: Vec<String>,
 }
 
-fn log_base_dir() -> PathBuf {
+/// Redaction audit record as exposed to compliance tooling.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    pub session_id: String,
+    pub redacted_fields: Vec<String>,
+}
+
+/// Filters applied when reading `audit.jsonl` files; date bounds are inclusive (UTC).
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub session_id: Option<String>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl AuditQuery {
+    fn includes_month(&self, year: i32, month: u32) -> bool {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return false;
+        };
+        let last = first
+            .checked_add_months(chrono::Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .unwrap_or(first);
+        self.from.is_none_or(|from| last >= from) && self.to.is_none_or(|to| first <= to)
+    }
+
+    fn matches(&self, record: &AuditLogRecord) -> bool {
+        if let Some(session_id) = self.session_id.as_deref()
+            && record.session_id != session_id
+        {
+            return false;
+        }
+        if self.from.is_none() && self.to.is_none() {
+            return true;
+        }
+        let Ok(timestamp) = DateTime::parse_from_rfc3339(&record.timestamp) else {
+            return false;
+        };
+        let date = timestamp.with_timezone(&Utc).date_naive();
+        self.from.is_none_or(|from| date >= from) && self.to.is_none_or(|to| date <= to)
+    }
+}
+
+/// Directory holding the `<year>/<month>[/<day>[/<hour>]]/*.jsonl` session and audit logs.
+pub fn log_base_dir() -> PathBuf {
     std::env::var(LOG_DIR_ENV)
         .map(PathBuf::from)
         .unwrap_or_else(|_| PathBuf::from(DEFAULT_LOG_DIR))
@@ -101,44 +252,31 @@ fn append_json_line<T: Serialize>(path: &Path, value: &T) -> Result<()> {
     Ok(())
 }
 
-fn sanitize_text(input: &str, redactions: &mut HashSet<String>) -> String {
-    let mut output = input.to_string();
-    for (name, regex) in REDACTION_PATTERNS.iter() {
-        let mut matched = false;
-        output = regex
-            .replace_all(&output, |caps: &Captures| {
-                matched = true;
-                if caps.len() > 1 {
-                    format!("{}[REDACTED]", &caps[1])
-                } else {
-                    "[REDACTED]".to_string()
-                }
-            })
-            .to_string();
-        if matched {
-            redactions.insert(name.clone());
-        }
-    }
-    output
+pub fn log_session_completion(input: SessionLogInput) -> Result<()> {
+    let base_dir = log_base_dir();
+    write_session_log(&base_dir, LogRotation::from_env(), input)?;
+    enforce_retention(&base_dir)?;
+    Ok(())
 }
 
-pub fn log_session_completion(input: SessionLogInput) -> Result<()> {
+fn write_session_log(base_dir: &Path, rotation: LogRotation, input: SessionLogInput) -> Result<()> {
     let timestamp = Utc::now();
+    let redactor = &*DEFAULT_REDACTOR;
     let mut redactions = HashSet::new();
 
     let query = input
         .query
         .as_deref()
-        .map(|value| sanitize_text(value, &mut redactions));
-    let summary = sanitize_text(&input.summary, &mut redactions);
+        .map(|value| redactor.sanitize_into(value, &mut redactions));
+    let summary = redactor.sanitize_into(&input.summary, &mut redactions);
     let verdict = input
         .verdict
         .as_deref()
-        .map(|value| sanitize_text(value, &mut redactions));
+        .map(|value| redactor.sanitize_into(value, &mut redactions));
     let sources: Vec<String> = input
         .sources
         .into_iter()
-        .map(|source| sanitize_text(&source, &mut redactions))
+        .map(|source| redactor.sanitize_into(&source, &mut redactions))
         .collect();
 
     let record = SessionLogRecord {
@@ -153,11 +291,8 @@ pub fn log_session_completion(input: SessionLogInput) -> Result<()> {
         redactions: redactions.iter().cloned().collect(),
     };
 
-    let base_dir = log_base_dir();
-    let month_dir = base_dir
-        .join(format!("{:04}", timestamp.year()))
-        .join(format!("{:02}", timestamp.month()));
-    let session_log_path = month_dir.join("session.jsonl");
+    let log_dir = rotation.log_dir(base_dir, timestamp);
+    let session_log_path = log_dir.join("session.jsonl");
     append_json_line(&session_log_path, &record)?;
 
     if !record.redactions.is_empty() {
@@ -166,7 +301,7 @@ pub fn log_session_completion(input: SessionLogInput) -> Result<()> {
             session_id: input.session_id.clone(),
             redactions: record.redactions.clone(),
         };
-        let audit_path = month_dir.join("audit.jsonl");
+        let audit_path = log_dir.join("audit.jsonl");
         append_json_line(&audit_path, &audit)?;
         warn!(
             session_id = %input.session_id,
@@ -175,11 +310,11 @@ pub fn log_session_completion(input: SessionLogInput) -> Result<()> {
         );
     }
 
-    enforce_retention(&base_dir)?;
-
     Ok(())
 }
 
+/// Prune log files older than the retention window at every depth, so monthly, daily
+/// and hourly layouts (and mixes left behind by a rotation change) age out alike.
 fn enforce_retention(base_dir: &Path) -> Result<()> {
     let retention = retention_days();
     if retention == 0 || !base_dir.exists() {
@@ -225,38 +360,111 @@ pub fn remove_session_logs(session_id: &str) -> Result<()> {
     if !base_dir.exists() {
         return Ok(());
     }
+    for entry in fs::read_dir(&base_dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            remove_session_logs_in(&entry.path(), session_id)?;
+        }
+    }
+    Ok(())
+}
+
+/// Scrub `session_id` from every log directory below `dir`. Walks the full tree so logs
+/// written under a previous rotation setting are cleaned too; emptied directories are removed.
+fn remove_session_logs_in(dir: &Path, session_id: &str) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            remove_session_logs_in(&entry.path(), session_id)?;
+        }
+    }
+
+    for name in ["session.jsonl", "audit.jsonl"] {
+        let path = dir.join(name);
+        rewrite_jsonl_without(&path, session_id)?;
+        cleanup_empty_file(&path)?;
+    }
+
+    if dir.read_dir()?.next().is_none() {
+        fs::remove_dir(dir).ok();
+    }
+    Ok(())
+}
+
+/// Read redaction audit entries under `base_dir`, oldest first.
+pub fn read_audit_entries(base_dir: &Path, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+    let mut entries = Vec::new();
+    if !base_dir.exists() {
+        return Ok(entries);
+    }
 
-    for year_entry in fs::read_dir(&base_dir)? {
+    for year_entry in fs::read_dir(base_dir)? {
         let year_entry = year_entry?;
-        if !year_entry.file_type()?.is_dir() {
+        let Some(year) = dir_number::<i32>(&year_entry)? else {
             continue;
-        }
+        };
         for month_entry in fs::read_dir(year_entry.path())? {
             let month_entry = month_entry?;
-            if !month_entry.file_type()?.is_dir() {
+            let Some(month) = dir_number::<u32>(&month_entry)? else {
+                continue;
+            };
+            if !query.includes_month(year, month) {
                 continue;
             }
-            let session_path = month_entry.path().join("session.jsonl");
-            rewrite_jsonl_without(&session_path, session_id)?;
-            cleanup_empty_file(&session_path)?;
-
-            let audit_path = month_entry.path().join("audit.jsonl");
-            rewrite_jsonl_without(&audit_path, session_id)?;
-            cleanup_empty_file(&audit_path)?;
 
-            if month_entry.path().read_dir()?.next().is_none() {
-                fs::remove_dir(month_entry.path()).ok();
+            let mut audit_paths = Vec::new();
+            collect_audit_files(&month_entry.path(), &mut audit_paths)?;
+            for audit_path in audit_paths {
+                let file = File::open(&audit_path)
+                    .with_context(|| format!("failed to open log file {}", audit_path.display()))?;
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    let Ok(record) = serde_json::from_str::<AuditLogRecord>(&line) else {
+                        warn!(path = %audit_path.display(), "skipping malformed audit entry");
+                        continue;
+                    };
+                    if query.matches(&record) {
+                        entries.push(AuditEntry {
+                            timestamp: record.timestamp,
+                            session_id: record.session_id,
+                            redacted_fields: record.redactions,
+                        });
+                    }
+                }
             }
         }
-        if year_entry.path().read_dir()?.next().is_none() {
-            fs::remove_dir(year_entry.path()).ok();
-        }
     }
 
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    Ok(entries)
+}
+
+/// `audit.jsonl` files in `dir` and its day/hour subdirectories.
+fn collect_audit_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    let audit_path = dir.join("audit.jsonl");
+    if audit_path.is_file() {
+        paths.push(audit_path);
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            collect_audit_files(&entry.path(), paths)?;
+        }
+    }
     Ok(())
 }
 
-fn rewrite_jsonl_without(path: &Path, session_id: &str) -> Result<()> {
+fn dir_number<T: std::str::FromStr>(entry: &fs::DirEntry) -> Result<Option<T>> {
+    if !entry.file_type()?.is_dir() {
+        return Ok(None);
+    }
+    Ok(entry
+        .file_name()
+        .to_str()
+        .and_then(|name| name.parse().ok()))
+}
+
+pub(crate) fn rewrite_jsonl_without(path: &Path, session_id: &str) -> Result<()> {
     if !path.exists() {
         return Ok(());
     }
@@ -290,7 +498,7 @@ fn rewrite_jsonl_without(path: &Path, session_id: &str) -> Result<()> {
     Ok(())
 }
 
-fn cleanup_empty_file(path: &Path) -> Result<()> {
+pub(crate) fn cleanup_empty_file(path: &Path) -> Result<()> {
     if path.exists() {
         let metadata = path.metadata()?;
         if metadata.len() == 0 {
@@ -345,4 +553,135 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn rotation_parses_and_nests_directories() {
+        assert_eq!(LogRotation::parse("Hourly"), Some(LogRotation::Hourly));
+        assert_eq!(LogRotation::parse(" daily "), Some(LogRotation::Daily));
+        assert_eq!(LogRotation::parse("weekly"), None);
+
+        let base = Path::new("logs");
+        let timestamp = DateTime::parse_from_rfc3339("2025-03-07T09:15:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            LogRotation::Monthly.log_dir(base, timestamp),
+            base.join("2025").join("03")
+        );
+        assert_eq!(
+            LogRotation::Daily.log_dir(base, timestamp),
+            base.join("2025").join("03").join("07")
+        );
+        assert_eq!(
+            LogRotation::Hourly.log_dir(base, timestamp),
+            base.join("2025").join("03").join("07").join("09")
+        );
+    }
+
+    #[test]
+    fn hourly_logs_are_audited_removed_and_pruned() -> Result<()> {
+        let temp = TempDir::new().expect("temp dir");
+        let input = SessionLogInput {
+            session_id: "hourly-session".to_string(),
+            query: None,
+            summary: "secret=abcd".to_string(),
+            verdict: None,
+            requires_manual: false,
+            sources: Vec::new(),
+            trace_path: None,
+        };
+        write_session_log(temp.path(), LogRotation::Hourly, input)?;
+
+        let year_dir = temp.path().read_dir()?.next().unwrap()?.path();
+        let hour_dir = year_dir
+            .read_dir()?
+            .next()
+            .unwrap()?
+            .path()
+            .read_dir()?
+            .next()
+            .unwrap()?
+            .path()
+            .read_dir()?
+            .next()
+            .unwrap()?
+            .path();
+        assert!(hour_dir.join("session.jsonl").exists());
+        let audits = read_audit_entries(temp.path(), &AuditQuery::default())?;
+        assert_eq!(audits.len(), 1);
+        assert_eq!(audits[0].session_id, "hourly-session");
+
+        remove_session_logs_in(temp.path(), "hourly-session")?;
+        assert!(!temp.path().exists() || temp.path().read_dir()?.next().is_none());
+
+        let stale_dir = temp.path().join("2020").join("01").join("02").join("03");
+        let stale = stale_dir.join("session.jsonl");
+        append_json_line(&stale, &serde_json::json!({ "session_id": "old" }))?;
+        File::options()
+            .write(true)
+            .open(&stale)?
+            .set_modified(SystemTime::UNIX_EPOCH)?;
+        prune_directory(temp.path(), SystemTime::now())?;
+        assert!(!temp.path().join("2020").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn redactor_reports_matched_patterns() {
+        let redactor = LogRedactor::default().with_custom_pattern(
+            "internal_host",
+            Regex::new(r"(host=)([a-z0-9.-]+\.internal)").unwrap(),
+        );
+
+        let (text, names) = redactor.sanitize("api_key=abcd1234 host=db.corp.internal");
+        assert_eq!(text, "api_key=[REDACTED] host=[REDACTED]");
+        assert_eq!(names, vec!["api_key", "internal_host"]);
+
+        let (text, names) = LogRedactor::default().sanitize("nothing to hide");
+        assert_eq!(text, "nothing to hide");
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn audit_entries_filter_by_session_and_date() -> Result<()> {
+        let temp = TempDir::new().expect("temp dir");
+        let records = [
+            ("2025-01-31T23:00:00+00:00", "alpha", "api_key"),
+            ("2025-02-03T10:00:00+00:00", "beta", "secret"),
+            ("2025-02-20T10:00:00+00:00", "alpha", "bearer"),
+        ];
+        for (timestamp, session_id, field) in records {
+            let date = DateTime::parse_from_rfc3339(timestamp).unwrap();
+            let path = temp
+                .path()
+                .join(format!("{:04}", date.year()))
+                .join(format!("{:02}", date.month()))
+                .join("audit.jsonl");
+            append_json_line(
+                &path,
+                &AuditLogRecord {
+                    timestamp: timestamp.to_string(),
+                    session_id: session_id.to_string(),
+                    redactions: vec![field.to_string()],
+                },
+            )?;
+        }
+
+        let all = read_audit_entries(temp.path(), &AuditQuery::default())?;
+        assert_eq!(all.len(), 3);
+
+        let alpha_in_february = read_audit_entries(
+            temp.path(),
+            &AuditQuery {
+                session_id: Some("alpha".to_string()),
+                from: NaiveDate::from_ymd_opt(2025, 2, 1),
+                to: NaiveDate::from_ymd_opt(2025, 2, 28),
+            },
+        )?;
+        assert_eq!(alpha_in_february.len(), 1);
+        assert_eq!(alpha_in_february[0].redacted_fields, vec!["bearer"]);
+
+        Ok(())
+    }
 }
@@ -2,11 +2,16 @@
 pub mod qdrant;
 #[cfg(feature = "qdrant-retriever")]
 pub use qdrant::{HybridRetriever, QdrantConfig};
+#[cfg(feature = "sqlite-retriever")]
+pub mod sqlite;
+#[cfg(feature = "sqlite-retriever")]
+pub use sqlite::SqliteRetriever;
 
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct RetrievedDocument {
@@ -15,13 +20,52 @@ pub struct RetrievedDocument {
     pub source: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IngestDocument {
     pub id: String,
     pub text: String,
     pub source: Option<String>,
 }
 
+impl IngestDocument {
+    /// Split the text into windows of `chunk_size` words that overlap by `overlap` words.
+    /// Documents that already fit in one window are returned unchanged.
+    pub fn into_chunks(self, chunk_size: usize, overlap: usize) -> Vec<IngestDocument> {
+        let chunk_size = chunk_size.max(1);
+        let words: Vec<&str> = self.text.split_whitespace().collect();
+        if words.len() <= chunk_size {
+            return vec![self];
+        }
+
+        let step = chunk_size.saturating_sub(overlap).max(1);
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        loop {
+            let end = (start + chunk_size).min(words.len());
+            chunks.push(IngestDocument {
+                id: format!("{}_chunk_{}", self.id, chunks.len()),
+                text: words[start..end].join(" "),
+                source: self.source.clone(),
+            });
+            if end == words.len() {
+                break;
+            }
+            start += step;
+        }
+        chunks
+    }
+}
+
+/// Index statistics for one session's documents in a vector collection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionInfo {
+    pub session_id: String,
+    /// Points stored for the session (one per ingested document or chunk).
+    pub document_count: u64,
+    /// Dimension of the collection's dense vectors.
+    pub vector_dimension: usize,
+}
+
 #[async_trait]
 pub trait Retriever: Send + Sync {
     async fn retrieve(
@@ -32,11 +76,36 @@ pub trait Retriever: Send + Sync {
     ) -> anyhow::Result<Vec<RetrievedDocument>>;
 
     async fn ingest(&self, session_id: &str, docs: Vec<IngestDocument>) -> anyhow::Result<()>;
+
+    /// Ingest documents for several sessions at once. Backends that can batch writes
+    /// (e.g. a single Qdrant upsert) should override the per-session default.
+    async fn ingest_batches(
+        &self,
+        batches: Vec<(String, Vec<IngestDocument>)>,
+    ) -> anyhow::Result<()> {
+        for (session_id, docs) in batches {
+            self.ingest(&session_id, docs).await?;
+        }
+        Ok(())
+    }
+
+    /// Replace a previously ingested document that shares `doc.id`. The default simply
+    /// re-ingests, which appends; backends keyed by document ID should overwrite instead.
+    async fn update_document(&self, session_id: &str, doc: IngestDocument) -> anyhow::Result<()> {
+        self.ingest(session_id, vec![doc]).await
+    }
+
+    /// Drop every document indexed for `session_id`. Called when a session is purged;
+    /// backends without persistent state can keep the no-op default.
+    async fn delete_session(&self, _session_id: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 pub type DynRetriever = Arc<dyn Retriever>;
 
-/// Simple in-memory retriever for tests and offline runs.
+/// Simple in-memory retriever for tests and offline runs. Documents are ranked with BM25
+/// over lowercase alphanumeric tokens; those sharing no term with the query score `0.0`.
 pub struct StubRetriever {
     store: DashMap<String, Vec<IngestDocument>>,
 }
@@ -54,7 +123,7 @@ impl Retriever for StubRetriever {
     async fn retrieve(
         &self,
         session_id: &str,
-        _query: &str,
+        query: &str,
         limit: usize,
     ) -> anyhow::Result<Vec<RetrievedDocument>> {
         let docs = self
@@ -71,12 +140,17 @@ impl Retriever for StubRetriever {
             }]);
         }
 
-        Ok(docs
+        let scores = bm25_scores(&docs, query);
+        let mut ranked: Vec<(f32, IngestDocument)> = scores.into_iter().zip(docs).collect();
+        // Stable sort: equally scored documents keep their ingestion order.
+        ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        Ok(ranked
             .into_iter()
             .take(limit)
-            .map(|doc| RetrievedDocument {
+            .map(|(score, doc)| RetrievedDocument {
                 text: doc.text,
-                score: 1.0,
+                score,
                 source: doc.source.or_else(|| Some("stub://memory".to_string())),
             })
             .collect())
@@ -89,4 +163,194 @@ impl Retriever for StubRetriever {
             .extend(docs);
         Ok(())
     }
+
+    async fn delete_session(&self, session_id: &str) -> anyhow::Result<()> {
+        self.store.remove(session_id);
+        Ok(())
+    }
+}
+
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Okapi BM25 score of each document against `query`, in document order.
+fn bm25_scores(docs: &[IngestDocument], query: &str) -> Vec<f32> {
+    let tokenized: Vec<Vec<String>> = docs.iter().map(|doc| tokenize(&doc.text)).collect();
+    let doc_count = tokenized.len() as f32;
+    let avg_len = tokenized.iter().map(Vec::len).sum::<usize>() as f32 / doc_count.max(1.0);
+
+    let mut query_terms = tokenize(query);
+    query_terms.sort();
+    query_terms.dedup();
+
+    let idf: Vec<f32> = query_terms
+        .iter()
+        .map(|term| {
+            let df = tokenized
+                .iter()
+                .filter(|tokens| tokens.contains(term))
+                .count() as f32;
+            (1.0 + (doc_count - df + 0.5) / (df + 0.5)).ln()
+        })
+        .collect();
+
+    tokenized
+        .iter()
+        .map(|tokens| {
+            let len_norm = 1.0 - BM25_B + BM25_B * tokens.len() as f32 / avg_len.max(1.0);
+            query_terms
+                .iter()
+                .zip(&idf)
+                .map(|(term, idf)| {
+                    let tf = tokens.iter().filter(|token| *token == term).count() as f32;
+                    idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * len_norm)
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// Retriever that replays caller-supplied documents (highest score first) instead of querying a backend.
+pub struct SeedRetriever {
+    documents: Vec<RetrievedDocument>,
+}
+
+impl SeedRetriever {
+    pub fn new(mut documents: Vec<RetrievedDocument>) -> Self {
+        documents.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Self { documents }
+    }
+}
+
+#[async_trait]
+impl Retriever for SeedRetriever {
+    async fn retrieve(
+        &self,
+        _session_id: &str,
+        _query: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RetrievedDocument>> {
+        Ok(self.documents.iter().take(limit).cloned().collect())
+    }
+
+    async fn ingest(&self, _session_id: &str, _docs: Vec<IngestDocument>) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!(
+            "pre-seeded retriever is read-only; ingest into a real backend instead"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_chunks_emits_overlapping_windows() {
+        let doc = IngestDocument {
+            id: "doc".into(),
+            text: "one two three four five six seven".into(),
+            source: Some("notes.txt".into()),
+        };
+
+        let chunks = doc.into_chunks(3, 1);
+        let texts: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["one two three", "three four five", "five six seven"]
+        );
+        assert_eq!(chunks[2].id, "doc_chunk_2");
+        assert!(
+            chunks
+                .iter()
+                .all(|chunk| chunk.source.as_deref() == Some("notes.txt"))
+        );
+    }
+
+    #[test]
+    fn into_chunks_keeps_short_documents_whole() {
+        let doc = IngestDocument {
+            id: "short".into(),
+            text: "brief note".into(),
+            source: None,
+        };
+        let chunks = doc.into_chunks(8, 2);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].id, "short");
+    }
+
+    #[tokio::test]
+    async fn stub_delete_session_only_drops_that_session() {
+        let retriever = StubRetriever::new();
+        let doc = |id: &str| IngestDocument {
+            id: id.into(),
+            text: format!("text for {id}"),
+            source: None,
+        };
+        retriever.ingest("keep", vec![doc("a")]).await.unwrap();
+        retriever.ingest("drop", vec![doc("b")]).await.unwrap();
+
+        retriever.delete_session("drop").await.unwrap();
+
+        let dropped = retriever.retrieve("drop", "text", 5).await.unwrap();
+        assert_eq!(dropped[0].score, 0.0, "expected placeholder after delete");
+        let kept = retriever.retrieve("keep", "text", 5).await.unwrap();
+        assert_eq!(kept[0].text, "text for a");
+    }
+
+    #[tokio::test]
+    async fn stub_ranks_documents_by_bm25() {
+        let retriever = StubRetriever::new();
+        let doc = |id: &str, text: &str| IngestDocument {
+            id: id.into(),
+            text: text.into(),
+            source: Some(format!("doc://{id}")),
+        };
+        retriever
+            .ingest(
+                "bm25",
+                vec![
+                    doc("weather", "Rain and wind are expected across the coast."),
+                    doc(
+                        "battery",
+                        "Lithium battery supply tightened; battery prices rose.",
+                    ),
+                    doc("grid", "Grid operators expanded battery storage capacity."),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let results = retriever
+            .retrieve("bm25", "battery prices", 3)
+            .await
+            .unwrap();
+        let sources: Vec<_> = results
+            .iter()
+            .map(|doc| doc.source.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            sources,
+            vec!["doc://battery", "doc://grid", "doc://weather"]
+        );
+        assert!(results[0].score > results[1].score);
+        assert_eq!(results[2].score, 0.0);
+
+        let top = retriever
+            .retrieve("bm25", "battery prices", 1)
+            .await
+            .unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].source.as_deref(), Some("doc://battery"));
+    }
 }
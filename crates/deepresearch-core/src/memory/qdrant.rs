@@ -5,15 +5,15 @@ use anyhow::{Context, anyhow};
 use async_trait::async_trait;
 use fastembed::TextEmbedding;
 use qdrant_client::qdrant::{
-    Condition, CreateCollectionBuilder, Distance, Filter, ListValue, PointStruct,
-    SearchPointsBuilder, UpsertPointsBuilder, Value as QValue, VectorParamsBuilder,
-    value::Kind as QValueKind,
+    Condition, CountPointsBuilder, CreateCollectionBuilder, DeletePointsBuilder, Distance, Filter,
+    ListValue, PointStruct, SearchPointsBuilder, UpsertPointsBuilder, Value as QValue,
+    VectorParamsBuilder, value::Kind as QValueKind, vectors_config::Config as VectorsConfigKind,
 };
 use qdrant_client::{Payload, Qdrant};
 use tokio::sync::Semaphore;
 use tracing::{debug, info, warn};
 
-use super::{IngestDocument, RetrievedDocument, Retriever};
+use super::{CollectionInfo, IngestDocument, RetrievedDocument, Retriever};
 
 const KEY_SESSION: &str = "session_id";
 const KEY_TEXT: &str = "text";
@@ -21,12 +21,34 @@ const KEY_SOURCE: &str = "source";
 const KEY_KEYWORDS: &str = "keywords";
 const MIN_KEYWORD_LEN: usize = 3;
 const MAX_KEYWORDS: usize = 32;
+const DEFAULT_EMBEDDING_BATCH_SIZE: usize = 32;
+const DEFAULT_UPSERT_BATCH_SIZE: usize = 100;
 
 #[derive(Clone, Debug)]
 pub struct QdrantConfig {
     pub url: String,
     pub collection: String,
     pub concurrency_limit: usize,
+    /// Texts handed to the embedding model per call during ingestion.
+    pub embedding_batch_size: usize,
+    /// Points written per Qdrant upsert request during ingestion.
+    pub upsert_batch_size: usize,
+}
+
+impl QdrantConfig {
+    pub fn new(
+        url: impl Into<String>,
+        collection: impl Into<String>,
+        concurrency_limit: usize,
+    ) -> Self {
+        Self {
+            url: url.into(),
+            collection: collection.into(),
+            concurrency_limit,
+            embedding_batch_size: DEFAULT_EMBEDDING_BATCH_SIZE,
+            upsert_batch_size: DEFAULT_UPSERT_BATCH_SIZE,
+        }
+    }
 }
 
 pub struct HybridRetriever {
@@ -34,6 +56,8 @@ pub struct HybridRetriever {
     collection: String,
     semaphore: Arc<Semaphore>,
     dense_model: Arc<Mutex<TextEmbedding>>,
+    embedding_batch_size: usize,
+    upsert_batch_size: usize,
 }
 
 impl HybridRetriever {
@@ -66,10 +90,57 @@ impl HybridRetriever {
             collection: config.collection,
             semaphore: Arc::new(Semaphore::new(config.concurrency_limit.max(1))),
             dense_model: Arc::new(Mutex::new(dense_model)),
+            embedding_batch_size: config.embedding_batch_size.max(1),
+            upsert_batch_size: config.upsert_batch_size.max(1),
         })
     }
 }
 
+/// Count `session_id`'s points and read the vector dimension of `collection`.
+pub(crate) async fn session_collection_info(
+    client: &Qdrant,
+    collection: &str,
+    session_id: &str,
+) -> anyhow::Result<CollectionInfo> {
+    let info = client
+        .collection_info(collection)
+        .await
+        .map_err(|err| anyhow!("failed to read qdrant collection '{collection}': {err}"))?;
+    let vectors = info
+        .result
+        .and_then(|result| result.config)
+        .and_then(|config| config.params)
+        .and_then(|params| params.vectors_config)
+        .and_then(|vectors| vectors.config);
+    let vector_dimension = match vectors {
+        Some(VectorsConfigKind::Params(params)) => params.size as usize,
+        // Named vectors: report the first (this crate only writes a single unnamed vector).
+        Some(VectorsConfigKind::ParamsMap(map)) => map
+            .map
+            .values()
+            .next()
+            .map(|params| params.size as usize)
+            .unwrap_or_default(),
+        None => 0,
+    };
+
+    let filter = Filter::all([Condition::matches(KEY_SESSION, session_id.to_string())]);
+    let count = client
+        .count(
+            CountPointsBuilder::new(collection)
+                .filter(filter)
+                .exact(true),
+        )
+        .await
+        .map_err(|err| anyhow!("failed to count qdrant points for {session_id}: {err}"))?;
+
+    Ok(CollectionInfo {
+        session_id: session_id.to_string(),
+        document_count: count.result.map(|result| result.count).unwrap_or_default(),
+        vector_dimension,
+    })
+}
+
 async fn ensure_collection(
     client: &Qdrant,
     collection: &str,
@@ -90,6 +161,17 @@ async fn ensure_collection(
     Ok(())
 }
 
+/// Split `items` into consecutive batches of at most `size` elements.
+fn split_batches<T>(items: Vec<T>, size: usize) -> Vec<Vec<T>> {
+    let size = size.max(1);
+    let mut batches = Vec::with_capacity(items.len().div_ceil(size));
+    let mut items = items.into_iter().peekable();
+    while items.peek().is_some() {
+        batches.push(items.by_ref().take(size).collect());
+    }
+    batches
+}
+
 fn tokenize(text: &str) -> Vec<String> {
     let mut seen = HashSet::new();
     let mut keywords = Vec::new();
@@ -297,7 +379,99 @@ impl Retriever for HybridRetriever {
     }
 
     async fn ingest(&self, session_id: &str, docs: Vec<IngestDocument>) -> anyhow::Result<()> {
-        if docs.is_empty() {
+        self.upsert_batches(vec![(session_id.to_string(), docs)])
+            .await
+    }
+
+    async fn ingest_batches(
+        &self,
+        batches: Vec<(String, Vec<IngestDocument>)>,
+    ) -> anyhow::Result<()> {
+        self.upsert_batches(batches).await
+    }
+
+    async fn update_document(&self, session_id: &str, doc: IngestDocument) -> anyhow::Result<()> {
+        self.delete_document_point(session_id, &doc.id).await?;
+        self.upsert_batches(vec![(session_id.to_string(), vec![doc])])
+            .await
+    }
+
+    async fn delete_session(&self, session_id: &str) -> anyhow::Result<()> {
+        self.delete_session_points(session_id).await
+    }
+}
+
+impl HybridRetriever {
+    /// Number of points indexed for `session_id` and the collection's vector dimension.
+    pub async fn collection_info(&self, session_id: &str) -> anyhow::Result<CollectionInfo> {
+        let _permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .context("semaphore closed unexpectedly")?;
+        session_collection_info(&self.client, &self.collection, session_id).await
+    }
+
+    /// Remove every point whose `session_id` payload matches, e.g. when a session is purged.
+    pub async fn delete_session_points(&self, session_id: &str) -> anyhow::Result<()> {
+        let _permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .context("semaphore closed unexpectedly")?;
+
+        let filter = Filter::all([Condition::matches(KEY_SESSION, session_id.to_string())]);
+        self.client
+            .delete_points(
+                DeletePointsBuilder::new(&self.collection)
+                    .points(filter)
+                    .wait(true),
+            )
+            .await
+            .map_err(|err| anyhow!("failed to delete qdrant points for {session_id}: {err}"))?;
+
+        info!(%session_id, collection = %self.collection, "deleted session points from qdrant");
+        Ok(())
+    }
+
+    /// Remove the point stored for `doc_id` within `session_id`, leaving other sessions untouched.
+    async fn delete_document_point(&self, session_id: &str, doc_id: &str) -> anyhow::Result<()> {
+        let _permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .context("semaphore closed unexpectedly")?;
+
+        let filter = Filter::all([
+            Condition::matches(KEY_SESSION, session_id.to_string()),
+            Condition::has_id([doc_id.to_string()]),
+        ]);
+        self.client
+            .delete_points(
+                DeletePointsBuilder::new(&self.collection)
+                    .points(filter)
+                    .wait(true),
+            )
+            .await
+            .map_err(|err| {
+                anyhow!("failed to delete qdrant point {doc_id} for {session_id}: {err}")
+            })?;
+
+        debug!(%session_id, %doc_id, "deleted document point from qdrant");
+        Ok(())
+    }
+
+    /// Embed every document across `batches` in chunks of `embedding_batch_size` and write
+    /// them in upserts of at most `upsert_batch_size` points.
+    async fn upsert_batches(
+        &self,
+        batches: Vec<(String, Vec<IngestDocument>)>,
+    ) -> anyhow::Result<()> {
+        let count: usize = batches.iter().map(|(_, docs)| docs.len()).sum();
+        if count == 0 {
             return Ok(());
         }
 
@@ -308,33 +482,57 @@ impl Retriever for HybridRetriever {
             .await
             .context("semaphore closed unexpectedly")?;
 
-        let texts: Vec<String> = docs.iter().map(|doc| doc.text.clone()).collect();
+        let texts: Vec<String> = batches
+            .iter()
+            .flat_map(|(_, docs)| docs.iter().map(|doc| doc.text.clone()))
+            .collect();
         let dense_model = self.dense_model.clone();
+        let embedding_batch_size = self.embedding_batch_size;
 
         let embeddings = tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<Vec<f32>>> {
             let mut model = dense_model
                 .lock()
                 .map_err(|_| anyhow!("embedding model poisoned"))?;
-            model
-                .embed(texts, Some(32))
-                .map_err(|err| anyhow!("failed to embed documents: {err}"))
+            let mut embeddings = Vec::with_capacity(texts.len());
+            for chunk in split_batches(texts, embedding_batch_size) {
+                let vectors = model
+                    .embed(chunk, Some(embedding_batch_size))
+                    .map_err(|err| anyhow!("failed to embed documents: {err}"))?;
+                embeddings.extend(vectors);
+            }
+            Ok(embeddings)
         })
         .await??;
 
-        let mut points = Vec::with_capacity(docs.len());
+        let mut points = Vec::with_capacity(count);
+        let mut vectors = embeddings.into_iter();
 
-        for (doc, vector) in docs.iter().zip(embeddings.into_iter()) {
-            let keywords = tokenize(&doc.text);
-            let payload = build_payload(session_id, &doc.text, doc.source.as_ref(), keywords)?;
-            points.push(PointStruct::new(doc.id.clone(), vector, payload));
+        for (session_id, docs) in &batches {
+            for doc in docs {
+                let vector = vectors
+                    .next()
+                    .ok_or_else(|| anyhow!("embedding model returned too few vectors"))?;
+                let keywords = tokenize(&doc.text);
+                let payload = build_payload(session_id, &doc.text, doc.source.as_ref(), keywords)?;
+                points.push(PointStruct::new(doc.id.clone(), vector, payload));
+            }
         }
 
-        self.client
-            .upsert_points(UpsertPointsBuilder::new(&self.collection, points).wait(true))
-            .await
-            .map_err(|err| anyhow!("failed to upsert documents into qdrant: {err}"))?;
+        let upserts = split_batches(points, self.upsert_batch_size);
+        let upsert_count = upserts.len();
+        for chunk in upserts {
+            self.client
+                .upsert_points(UpsertPointsBuilder::new(&self.collection, chunk).wait(true))
+                .await
+                .map_err(|err| anyhow!("failed to upsert documents into qdrant: {err}"))?;
+        }
 
-        debug!(session_id, count = %docs.len(), "ingested documents into qdrant");
+        debug!(
+            sessions = batches.len(),
+            count,
+            upserts = upsert_count,
+            "ingested documents into qdrant"
+        );
         Ok(())
     }
 }
@@ -344,6 +542,14 @@ mod tests {
     use super::*;
     use std::collections::HashSet;
 
+    #[test]
+    fn split_batches_respects_size() {
+        let batches = split_batches((0..7).collect::<Vec<_>>(), 3);
+        assert_eq!(batches, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+        assert!(split_batches(Vec::<u8>::new(), 3).is_empty());
+        assert_eq!(split_batches(vec![1, 2], 0), vec![vec![1], vec![2]]);
+    }
+
     #[test]
     fn tokenize_deduplicates_keywords() {
         let tokens = tokenize("Rust enables resilient Rust research agents, rust!");
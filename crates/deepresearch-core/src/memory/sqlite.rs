@@ -0,0 +1,215 @@
+use std::path::Path;
+
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use sqlx::Row;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use tracing::{debug, info};
+
+use super::{IngestDocument, RetrievedDocument, Retriever};
+
+const MIN_TOKEN_LEN: usize = 2;
+const MAX_QUERY_TOKENS: usize = 32;
+
+/// Offline retriever backed by an SQLite FTS5 table ranked with BM25.
+pub struct SqliteRetriever {
+    pool: SqlitePool,
+}
+
+impl SqliteRetriever {
+    pub async fn new(db_path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let db_path = db_path.as_ref();
+        if let Some(parent) = db_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(4)
+            .connect_with(options)
+            .await
+            .map_err(|err| {
+                anyhow!(
+                    "failed to open SQLite database {}: {err}",
+                    db_path.display()
+                )
+            })?;
+
+        sqlx::query(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS documents USING fts5(\
+             session_id UNINDEXED, doc_id UNINDEXED, text, source UNINDEXED, \
+             tokenize = 'porter unicode61')",
+        )
+        .execute(&pool)
+        .await
+        .map_err(|err| anyhow!("failed to create FTS5 table: {err}"))?;
+
+        info!(path = %db_path.display(), "sqlite retriever ready");
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl Retriever for SqliteRetriever {
+    async fn retrieve(
+        &self,
+        session_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<RetrievedDocument>> {
+        let Some(expression) = match_expression(query) else {
+            debug!(%session_id, "query produced no searchable tokens");
+            return Ok(Vec::new());
+        };
+
+        let rows = sqlx::query(
+            "SELECT text, source, bm25(documents) AS rank FROM documents \
+             WHERE documents MATCH ?1 AND session_id = ?2 \
+             ORDER BY rank LIMIT ?3",
+        )
+        .bind(&expression)
+        .bind(session_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| anyhow!("sqlite retrieval failed: {err}"))?;
+
+        rows.into_iter()
+            .map(|row| -> anyhow::Result<RetrievedDocument> {
+                let rank: f64 = row.try_get("rank")?;
+                Ok(RetrievedDocument {
+                    text: row.try_get("text")?,
+                    // BM25 ranks are negative with lower meaning better; flip to a positive score.
+                    score: (-rank) as f32,
+                    source: row.try_get("source")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn ingest(&self, session_id: &str, docs: Vec<IngestDocument>) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for doc in &docs {
+            sqlx::query("DELETE FROM documents WHERE session_id = ?1 AND doc_id = ?2")
+                .bind(session_id)
+                .bind(&doc.id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                "INSERT INTO documents (session_id, doc_id, text, source) VALUES (?1, ?2, ?3, ?4)",
+            )
+            .bind(session_id)
+            .bind(&doc.id)
+            .bind(&doc.text)
+            .bind(&doc.source)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        debug!(%session_id, count = docs.len(), "ingested documents into sqlite");
+        Ok(())
+    }
+
+    async fn delete_session(&self, session_id: &str) -> anyhow::Result<()> {
+        let deleted = sqlx::query("DELETE FROM documents WHERE session_id = ?1")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|err| anyhow!("failed to delete sqlite documents for {session_id}: {err}"))?
+            .rows_affected();
+        debug!(%session_id, deleted, "deleted session documents from sqlite");
+        Ok(())
+    }
+}
+
+/// Build an FTS5 `MATCH` expression that ORs the quoted query tokens together.
+fn match_expression(query: &str) -> Option<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    for token in query
+        .split(|ch: char| !ch.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|token| token.chars().count() >= MIN_TOKEN_LEN)
+    {
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+        if tokens.len() == MAX_QUERY_TOKENS {
+            break;
+        }
+    }
+
+    if tokens.is_empty() {
+        return None;
+    }
+    Some(
+        tokens
+            .iter()
+            .map(|token| format!("\"{token}\""))
+            .collect::<Vec<_>>()
+            .join(" OR "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn match_expression_quotes_unique_tokens() {
+        assert_eq!(
+            match_expression("Sodium-ion vs sodium? a").as_deref(),
+            Some("\"sodium\" OR \"ion\" OR \"vs\"")
+        );
+        assert!(match_expression("?!").is_none());
+    }
+
+    #[tokio::test]
+    async fn retrieves_ranked_documents_per_session() {
+        let temp = TempDir::new().expect("temp dir");
+        let retriever = SqliteRetriever::new(temp.path().join("memory.db"))
+            .await
+            .expect("sqlite retriever");
+
+        retriever
+            .ingest(
+                "alpha",
+                vec![
+                    IngestDocument {
+                        id: "1".into(),
+                        text: "Sodium-ion batteries scale for grid storage".into(),
+                        source: Some("notes/sodium.txt".into()),
+                    },
+                    IngestDocument {
+                        id: "2".into(),
+                        text: "Lithium prices fell sharply".into(),
+                        source: None,
+                    },
+                ],
+            )
+            .await
+            .expect("ingest");
+        retriever
+            .ingest(
+                "beta",
+                vec![IngestDocument {
+                    id: "1".into(),
+                    text: "Sodium supply chains in Asia".into(),
+                    source: None,
+                }],
+            )
+            .await
+            .expect("ingest");
+
+        let results = retriever
+            .retrieve("alpha", "sodium grid", 5)
+            .await
+            .expect("retrieve");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].source.as_deref(), Some("notes/sodium.txt"));
+        assert!(results[0].score > 0.0);
+    }
+}
@@ -1,16 +1,33 @@
 use anyhow::Result;
 use once_cell::sync::OnceCell;
-use opentelemetry::metrics::{Counter, Histogram, Meter};
+use opentelemetry::metrics::{Counter, Histogram, Meter, ObservableGauge, UpDownCounter};
 use opentelemetry::{KeyValue, global};
-use tracing::info;
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(not(feature = "prometheus-push"))]
+use tracing::warn;
+use tracing::{debug, info};
+
+const PUSHGATEWAY_URL_ENV: &str = "DEEPRESEARCH_PROMETHEUS_PUSHGATEWAY_URL";
 
 struct SandboxMetrics {
     runs: Counter<u64>,
     duration_ms: Histogram<f64>,
     alerts: Counter<u64>,
+    queued: UpDownCounter<i64>,
+    _cpu_percent: ObservableGauge<f64>,
+    _memory_mib: ObservableGauge<f64>,
+    sessions: Counter<u64>,
+    session_duration_ms: Histogram<f64>,
 }
 
 static METRICS: OnceCell<SandboxMetrics> = OnceCell::new();
+/// Latest sampled resource usage (`f64` bits), reported by the observable gauges.
+static LAST_CPU_PERCENT: AtomicU64 = AtomicU64::new(0);
+static LAST_MEMORY_MIB: AtomicU64 = AtomicU64::new(0);
+
+fn load_f64(cell: &AtomicU64) -> f64 {
+    f64::from_bits(cell.load(Ordering::Relaxed))
+}
 
 fn handles() -> &'static SandboxMetrics {
     METRICS.get_or_init(|| {
@@ -28,11 +45,34 @@ fn handles() -> &'static SandboxMetrics {
                 .u64_counter("sandbox_alerts_total")
                 .with_description("Number of sandbox executions triggering alert thresholds")
                 .init(),
+            queued: meter
+                .i64_up_down_counter("sandbox.queued_count")
+                .with_description("Sandbox executions waiting for a concurrency permit")
+                .init(),
+            _cpu_percent: meter
+                .f64_observable_gauge("sandbox.cpu_percent")
+                .with_description("Mean CPU utilisation of the most recent sandbox run")
+                .with_callback(|observer| observer.observe(load_f64(&LAST_CPU_PERCENT), &[]))
+                .init(),
+            _memory_mib: meter
+                .f64_observable_gauge("sandbox.memory_mib")
+                .with_description("Memory (MiB) in use at the end of the most recent sandbox run")
+                .with_callback(|observer| observer.observe(load_f64(&LAST_MEMORY_MIB), &[]))
+                .init(),
+            sessions: meter
+                .u64_counter("session_runs_total")
+                .with_description("Completed research sessions by manual-review outcome")
+                .init(),
+            session_duration_ms: meter
+                .f64_histogram("session_duration_ms")
+                .with_description("End-to-end research session runtime in milliseconds")
+                .init(),
         }
     })
 }
 
-/// Hint to operators that OTEL metrics export can be configured externally.
+/// Hint to operators that OTEL metrics export can be configured externally, and install
+/// the Prometheus push-gateway exporter when `DEEPRESEARCH_PROMETHEUS_PUSHGATEWAY_URL` is set.
 pub fn init_metrics_from_env(service_name: &str) -> Result<()> {
     if std::env::var("DEEPRESEARCH_OTEL_METRICS_ENDPOINT").is_ok() {
         info!(
@@ -40,11 +80,104 @@ pub fn init_metrics_from_env(service_name: &str) -> Result<()> {
             "DEEPRESEARCH_OTEL_METRICS_ENDPOINT detected for {service_name}. Configure an OTLP meter provider in your deployment to export sandbox metrics."
         );
     }
+    if let Ok(url) = std::env::var(PUSHGATEWAY_URL_ENV)
+        && !url.trim().is_empty()
+    {
+        #[cfg(feature = "prometheus-push")]
+        pushgateway::init(url.trim(), service_name)?;
+        #[cfg(not(feature = "prometheus-push"))]
+        warn!(
+            target = "telemetry",
+            %url,
+            "{PUSHGATEWAY_URL_ENV} is set but deepresearch-core was built without the prometheus-push feature"
+        );
+    }
     Ok(())
 }
 
-/// No-op placeholder for symmetry with tracer shutdown.
-pub fn shutdown_metrics() {}
+/// Push buffered metrics to the Prometheus push gateway (if configured) before exit.
+pub fn shutdown_metrics() {
+    #[cfg(feature = "prometheus-push")]
+    pushgateway::push();
+}
+
+#[cfg(feature = "prometheus-push")]
+mod pushgateway {
+    use anyhow::{Context, Result};
+    use once_cell::sync::OnceCell;
+    use opentelemetry::global;
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+    use std::collections::HashMap;
+    use tracing::{info, warn};
+
+    struct PushGateway {
+        url: String,
+        job: String,
+        registry: prometheus::Registry,
+        provider: SdkMeterProvider,
+    }
+
+    static PUSH_GATEWAY: OnceCell<PushGateway> = OnceCell::new();
+
+    pub(super) fn init(url: &str, job: &str) -> Result<()> {
+        let registry = prometheus::Registry::new();
+        let exporter = opentelemetry_prometheus::exporter()
+            .with_registry(registry.clone())
+            .build()
+            .context("failed to build Prometheus exporter")?;
+        let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+        global::set_meter_provider(provider.clone());
+        let _ = PUSH_GATEWAY.set(PushGateway {
+            url: url.to_string(),
+            job: job.to_string(),
+            registry,
+            provider,
+        });
+        info!(target = "telemetry", %url, job, "Prometheus push gateway export enabled");
+        Ok(())
+    }
+
+    pub(super) fn push() {
+        let Some(gateway) = PUSH_GATEWAY.get() else {
+            return;
+        };
+        let families = gateway.registry.gather();
+        let (url, job) = (gateway.url.clone(), gateway.job.clone());
+        // The push client blocks; run it off any async runtime the caller may be inside.
+        let pushed = std::thread::spawn(move || {
+            prometheus::push_metrics(&job, HashMap::new(), &url, families, None)
+        })
+        .join();
+        match pushed {
+            Ok(Ok(())) => info!(target = "telemetry", url = %gateway.url, "pushed metrics"),
+            Ok(Err(err)) => warn!(
+                target = "telemetry",
+                url = %gateway.url,
+                error = %err,
+                "failed to push metrics to Prometheus push gateway"
+            ),
+            Err(_) => warn!(target = "telemetry", "Prometheus push thread panicked"),
+        }
+        if let Err(err) = gateway.provider.shutdown() {
+            warn!(target = "telemetry", error = %err, "meter provider shutdown failed");
+        }
+    }
+}
+
+/// Record a finished research session. `session_id` is only logged: per-session labels
+/// would explode metric cardinality.
+pub fn record_session_metrics(session_id: &str, duration_ms: u64, requires_manual: bool) {
+    let metrics = handles();
+    let attrs = [KeyValue::new("requires_manual", requires_manual)];
+    metrics.sessions.add(1, &attrs);
+    metrics
+        .session_duration_ms
+        .record(duration_ms as f64, &attrs);
+    debug!(
+        target = "telemetry",
+        session_id, duration_ms, requires_manual, "session metrics recorded"
+    );
+}
 
 /// Record OTEL metrics for a sandbox execution (no-op if no provider installed).
 pub fn record_sandbox_metrics(status: &str, duration_ms: u64, outputs: usize, failure_streak: u64) {
@@ -61,3 +194,15 @@ pub fn record_sandbox_metrics(status: &str, duration_ms: u64, outputs: usize, fa
         metrics.alerts.add(1, &attrs);
     }
 }
+
+/// Update the `sandbox.cpu_percent` / `sandbox.memory_mib` gauges with a run's sampled usage.
+pub fn record_sandbox_resource_usage(cpu_percent: f64, memory_mib: f64) {
+    handles();
+    LAST_CPU_PERCENT.store(cpu_percent.to_bits(), Ordering::Relaxed);
+    LAST_MEMORY_MIB.store(memory_mib.to_bits(), Ordering::Relaxed);
+}
+
+/// Adjust the `sandbox.queued_count` gauge as executions enter (+1) or leave (-1) the wait queue.
+pub fn record_sandbox_queue_change(delta: i64) {
+    handles().queued.add(delta, &[]);
+}
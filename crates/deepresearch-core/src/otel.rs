@@ -0,0 +1,111 @@
+use graph_flow::Context;
+use tracing::{Span, info_span};
+
+/// Context key holding the W3C trace id propagated from the caller (32 hex chars).
+pub const OTEL_TRACE_ID_KEY: &str = "otel.trace_id";
+/// Context key holding the caller's span id (16 hex chars), used as the parent of task spans.
+pub const OTEL_SPAN_ID_KEY: &str = "otel.span_id";
+
+/// Parse a W3C `traceparent` header into `(trace_id, span_id)`.
+///
+/// Returns `None` for malformed headers, the reserved `ff` version, or all-zero ids.
+pub fn parse_traceparent(header: &str) -> Option<(String, String)> {
+    let mut parts = header.trim().split('-');
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let span_id = parts.next()?;
+    let flags = parts.next()?;
+
+    let is_hex = |value: &str, len: usize| {
+        value.len() == len && value.bytes().all(|b| b.is_ascii_hexdigit())
+    };
+    let is_zero = |value: &str| value.bytes().all(|b| b == b'0');
+
+    if !is_hex(version, 2) || version.eq_ignore_ascii_case("ff") {
+        return None;
+    }
+    // Version 00 defines exactly four fields; future versions may append more.
+    if version == "00" && parts.next().is_some() {
+        return None;
+    }
+    if !is_hex(trace_id, 32) || !is_hex(span_id, 16) || !is_hex(flags, 2) {
+        return None;
+    }
+    if is_zero(trace_id) || is_zero(span_id) {
+        return None;
+    }
+
+    Some((trace_id.to_ascii_lowercase(), span_id.to_ascii_lowercase()))
+}
+
+/// Span wrapping a single task execution. With the `otel` feature enabled and
+/// `otel.trace_id`/`otel.span_id` present in the context, the span is exported as a
+/// child of the caller's remote span.
+pub(crate) async fn task_span(context: &Context, task_id: &str) -> Span {
+    let session_id: String = context.get("session_id").await.unwrap_or_default();
+    let span = info_span!("task", task_id, session_id = %session_id);
+    attach_remote_parent(&span, context).await;
+    span
+}
+
+#[cfg(feature = "otel")]
+async fn attach_remote_parent(span: &Span, context: &Context) {
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let (Some(trace_id), Some(span_id)) = (
+        context.get::<String>(OTEL_TRACE_ID_KEY).await,
+        context.get::<String>(OTEL_SPAN_ID_KEY).await,
+    ) else {
+        return;
+    };
+    let (Ok(trace_id), Ok(span_id)) = (TraceId::from_hex(&trace_id), SpanId::from_hex(&span_id))
+    else {
+        return;
+    };
+
+    let remote = SpanContext::new(
+        trace_id,
+        span_id,
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+    if remote.is_valid() {
+        span.set_parent(opentelemetry::Context::new().with_remote_span_context(remote));
+    }
+}
+
+#[cfg(not(feature = "otel"))]
+async fn attach_remote_parent(_span: &Span, _context: &Context) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_valid_traceparent() {
+        let parsed =
+            parse_traceparent("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01").unwrap();
+        assert_eq!(parsed.0, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(parsed.1, "00f067aa0ba902b7");
+    }
+
+    #[test]
+    fn rejects_malformed_traceparent() {
+        for header in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-zzf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert!(parse_traceparent(header).is_none(), "accepted {header:?}");
+        }
+    }
+}
@@ -1,5 +1,7 @@
+use crate::logging::{cleanup_empty_file, rewrite_jsonl_without};
 use crate::tasks::MathToolResult;
 use crate::workflow::SessionOutcome;
+use anyhow::Result;
 use chrono::{DateTime, Utc};
 use graph_flow::Session;
 use serde::Serialize;
@@ -135,6 +137,26 @@ pub fn persist_session_record(session: &Session, outcome: &SessionOutcome) {
     }
 }
 
+/// Erase every raw pipeline record for `session_id` (data-subject erasure), dropping files
+/// left empty afterwards.
+pub fn remove_pipeline_records(session_id: &str) -> Result<()> {
+    remove_pipeline_records_in(&pipeline_dir(), session_id)
+}
+
+fn remove_pipeline_records_in(dir: &Path, session_id: &str) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "jsonl") {
+            rewrite_jsonl_without(&path, session_id)?;
+            cleanup_empty_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,6 +190,10 @@ mod tests {
             factcheck_passed: None,
             factcheck_verified_sources: vec![],
             critic_confident: None,
+            sources: vec![],
+            source_references: vec![],
+            cancelled: false,
+            completed: true,
         };
 
         persist_session_record(&session, &outcome);
@@ -185,4 +211,24 @@ mod tests {
             std::env::remove_var("DEEPRESEARCH_PIPELINE_DIR");
         }
     }
+
+    #[test]
+    fn removes_only_matching_session_records() {
+        let dir = tempdir().unwrap();
+        let day_one = dir.path().join("2025-01-01.jsonl");
+        let day_two = dir.path().join("2025-01-02.jsonl");
+        std::fs::write(
+            &day_one,
+            "{\"session_id\":\"erase\"}\n{\"session_id\":\"keep\"}\n",
+        )
+        .unwrap();
+        std::fs::write(&day_two, "{\"session_id\":\"erase\"}\n").unwrap();
+
+        remove_pipeline_records_in(dir.path(), "erase").unwrap();
+
+        let remaining = std::fs::read_to_string(&day_one).unwrap();
+        assert!(!remaining.contains("erase"));
+        assert!(remaining.contains("keep"));
+        assert!(!day_two.exists());
+    }
 }
@@ -0,0 +1,67 @@
+use graph_flow::Task;
+use std::sync::Arc;
+
+/// A custom task registered at link time and added to every session graph.
+///
+/// Downstream crates register plugins with the re-exported `inventory` crate:
+///
+/// ```ignore
+/// deepresearch_core::inventory::submit! {
+///     deepresearch_core::TaskPlugin::new("summarizer", || Arc::new(SummarizerTask))
+/// }
+/// ```
+///
+/// Plugins are only added as graph nodes; wire them in with a
+/// [`GraphCustomizer`](crate::GraphCustomizer) edge so the runner reaches them.
+pub struct TaskPlugin {
+    pub id: &'static str,
+    pub factory: fn() -> Arc<dyn Task>,
+}
+
+impl TaskPlugin {
+    pub const fn new(id: &'static str, factory: fn() -> Arc<dyn Task>) -> Self {
+        Self { id, factory }
+    }
+}
+
+inventory::collect!(TaskPlugin);
+
+/// Every registered [`TaskPlugin`], sorted by id so graph assembly is deterministic.
+pub fn registered_task_plugins() -> Vec<&'static TaskPlugin> {
+    let mut plugins: Vec<&'static TaskPlugin> = inventory::iter::<TaskPlugin>.into_iter().collect();
+    plugins.sort_by_key(|plugin| plugin.id);
+    plugins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use graph_flow::{Context, NextAction, TaskResult};
+
+    struct EchoTask;
+
+    #[async_trait]
+    impl Task for EchoTask {
+        fn id(&self) -> &str {
+            "plugin_echo"
+        }
+
+        async fn run(&self, _context: Context) -> graph_flow::Result<TaskResult> {
+            Ok(TaskResult::new(None, NextAction::End))
+        }
+    }
+
+    inventory::submit! {
+        TaskPlugin::new("plugin_echo", || Arc::new(EchoTask))
+    }
+
+    #[test]
+    fn submitted_plugins_are_discoverable() {
+        let plugin = registered_task_plugins()
+            .into_iter()
+            .find(|plugin| plugin.id == "plugin_echo")
+            .expect("plugin registered");
+        assert_eq!((plugin.factory)().id(), "plugin_echo");
+    }
+}
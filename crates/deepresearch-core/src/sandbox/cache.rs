@@ -0,0 +1,170 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use super::{SandboxExecutor, SandboxRequest, SandboxResult};
+
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Clone)]
+struct CachedResult {
+    result: SandboxResult,
+    stored_at: Instant,
+}
+
+/// `SandboxExecutor` wrapper that memoises successful runs keyed by
+/// [`SandboxRequest::to_reproducible_hash`].
+pub struct SandboxCache {
+    inner: Arc<dyn SandboxExecutor>,
+    store: DashMap<String, CachedResult>,
+    max_entries: usize,
+    ttl: Duration,
+}
+
+impl SandboxCache {
+    pub fn new(inner: Arc<dyn SandboxExecutor>, max_entries: usize) -> Self {
+        Self {
+            inner,
+            store: DashMap::new(),
+            max_entries: max_entries.max(1),
+            ttl: DEFAULT_CACHE_TTL,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    fn lookup(&self, key: &str) -> Option<SandboxResult> {
+        let entry = self.store.get(key)?;
+        if entry.stored_at.elapsed() < self.ttl {
+            return Some(entry.result.clone());
+        }
+        drop(entry);
+        self.store.remove(key);
+        None
+    }
+
+    fn insert(&self, key: String, result: SandboxResult) {
+        if self.store.len() >= self.max_entries {
+            let ttl = self.ttl;
+            self.store
+                .retain(|_, cached| cached.stored_at.elapsed() < ttl);
+        }
+        if self.store.len() >= self.max_entries {
+            let oldest = self
+                .store
+                .iter()
+                .min_by_key(|entry| entry.stored_at)
+                .map(|entry| entry.key().clone());
+            if let Some(oldest) = oldest {
+                self.store.remove(&oldest);
+            }
+        }
+        self.store.insert(
+            key,
+            CachedResult {
+                result,
+                stored_at: Instant::now(),
+            },
+        );
+    }
+}
+
+#[async_trait]
+impl SandboxExecutor for SandboxCache {
+    async fn execute(&self, request: SandboxRequest) -> Result<SandboxResult> {
+        let key = request.to_reproducible_hash();
+        if let Some(result) = self.lookup(&key) {
+            debug!(script = %request.script_name, %key, "sandbox cache hit");
+            return Ok(result);
+        }
+
+        let result = self.inner.execute(request).await?;
+        // Failures and timeouts may be transient, so only clean runs are memoised.
+        if result.exit_code == Some(0) && !result.timed_out {
+            self.insert(key, result.clone());
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingExecutor {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SandboxExecutor for CountingExecutor {
+        async fn execute(&self, request: SandboxRequest) -> Result<SandboxResult> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(SandboxResult {
+                exit_code: Some(0),
+                stdout: request.script_name,
+                stderr: String::new(),
+                outputs: Vec::new(),
+                timed_out: false,
+                duration: Duration::from_millis(5),
+                stderr_overflow_warning: None,
+                resource_usage: None,
+                partial_output_errors: Vec::new(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn cache_reuses_results_until_ttl_expires() {
+        let inner = Arc::new(CountingExecutor {
+            calls: AtomicUsize::new(0),
+        });
+        let cache = SandboxCache::new(inner.clone(), 4).with_ttl(Duration::from_millis(50));
+
+        let request = SandboxRequest::new("calc.py", "print(1 + 1)");
+        cache.execute(request.clone()).await.expect("first run");
+        cache.execute(request.clone()).await.expect("cached run");
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        cache.execute(request).await.expect("expired run");
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn cache_evicts_oldest_entry_when_full() {
+        let inner = Arc::new(CountingExecutor {
+            calls: AtomicUsize::new(0),
+        });
+        let cache = SandboxCache::new(inner.clone(), 2);
+
+        for name in ["a.py", "b.py", "c.py"] {
+            cache
+                .execute(SandboxRequest::new(name, "print('x')"))
+                .await
+                .expect("run");
+        }
+        assert_eq!(cache.len(), 2);
+
+        cache
+            .execute(SandboxRequest::new("a.py", "print('x')"))
+            .await
+            .expect("re-run evicted entry");
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 4);
+    }
+}
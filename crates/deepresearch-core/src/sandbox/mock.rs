@@ -0,0 +1,186 @@
+use super::{SandboxExecutor, SandboxRequest, SandboxResult};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Predicate checked against the request that consumes a queued response.
+pub type SandboxRequestPredicate = Box<dyn Fn(&SandboxRequest) -> bool + Send + Sync>;
+
+struct QueuedResponse {
+    predicate: Option<SandboxRequestPredicate>,
+    result: SandboxResult,
+}
+
+/// Scripted [`SandboxExecutor`] for tests: each `execute` pops the next queued response
+/// (checking its predicate, if any) and records the request. An empty queue answers with
+/// [`MockSandboxExecutor::success`].
+#[derive(Default)]
+pub struct MockSandboxExecutor {
+    responses: Mutex<VecDeque<QueuedResponse>>,
+    calls: Mutex<Vec<SandboxRequest>>,
+}
+
+impl MockSandboxExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `result` for the next unanswered call.
+    pub fn with_response(self, result: SandboxResult) -> Self {
+        self.push_response(None, result);
+        self
+    }
+
+    /// Queue `result` for the next unanswered call, panicking if that call's request does
+    /// not satisfy `predicate`.
+    pub fn with_expected_response<F>(self, predicate: F, result: SandboxResult) -> Self
+    where
+        F: Fn(&SandboxRequest) -> bool + Send + Sync + 'static,
+    {
+        self.push_response(Some(Box::new(predicate)), result);
+        self
+    }
+
+    fn push_response(&self, predicate: Option<SandboxRequestPredicate>, result: SandboxResult) {
+        self.responses
+            .lock()
+            .expect("mock sandbox response queue poisoned")
+            .push_back(QueuedResponse { predicate, result });
+    }
+
+    /// Successful run with exit code 0 and the given stdout.
+    pub fn success(stdout: impl Into<String>) -> SandboxResult {
+        Self::exit(0, stdout, "")
+    }
+
+    /// Completed run with an explicit exit code, stdout and stderr.
+    pub fn exit(
+        exit_code: i32,
+        stdout: impl Into<String>,
+        stderr: impl Into<String>,
+    ) -> SandboxResult {
+        SandboxResult {
+            exit_code: Some(exit_code),
+            stdout: stdout.into(),
+            stderr: stderr.into(),
+            outputs: Vec::new(),
+            timed_out: false,
+            duration: Duration::from_millis(1),
+            stderr_overflow_warning: None,
+            resource_usage: None,
+            partial_output_errors: Vec::new(),
+        }
+    }
+
+    /// Requests received so far, in call order.
+    pub fn calls(&self) -> Vec<SandboxRequest> {
+        self.calls
+            .lock()
+            .expect("mock sandbox call log poisoned")
+            .clone()
+    }
+
+    /// Panic unless exactly `count` requests were executed.
+    pub fn expect_calls(&self, count: usize) {
+        let actual = self
+            .calls
+            .lock()
+            .expect("mock sandbox call log poisoned")
+            .len();
+        assert_eq!(
+            actual, count,
+            "expected {count} sandbox call(s), got {actual}"
+        );
+    }
+
+    /// Panic unless at least one recorded request satisfies `predicate`.
+    pub fn assert_called_with<F>(&self, predicate: F)
+    where
+        F: Fn(&SandboxRequest) -> bool,
+    {
+        let calls = self.calls.lock().expect("mock sandbox call log poisoned");
+        let scripts: Vec<&str> = calls.iter().map(|call| call.script_name.as_str()).collect();
+        assert!(
+            calls.iter().any(&predicate),
+            "no sandbox call matched the predicate; scripts called: {scripts:?}"
+        );
+    }
+}
+
+#[async_trait]
+impl SandboxExecutor for MockSandboxExecutor {
+    async fn execute(&self, request: SandboxRequest) -> Result<SandboxResult> {
+        let next = self
+            .responses
+            .lock()
+            .expect("mock sandbox response queue poisoned")
+            .pop_front();
+        self.calls
+            .lock()
+            .expect("mock sandbox call log poisoned")
+            .push(request.clone());
+
+        let Some(QueuedResponse { predicate, result }) = next else {
+            return Ok(Self::success(String::new()));
+        };
+        if let Some(predicate) = predicate {
+            assert!(
+                predicate(&request),
+                "sandbox request for '{}' did not match the expected predicate",
+                request.script_name
+            );
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn replays_queued_responses_then_defaults_to_success() {
+        let mock = MockSandboxExecutor::new()
+            .with_expected_response(
+                |request| request.script_name == "first.py",
+                MockSandboxExecutor::exit(2, "", "boom"),
+            )
+            .with_response(MockSandboxExecutor::success("second"));
+
+        let first = mock
+            .execute(SandboxRequest::new("first.py", "print(1)"))
+            .await
+            .unwrap();
+        assert_eq!(first.exit_code, Some(2));
+        assert_eq!(first.stderr, "boom");
+
+        let second = mock
+            .execute(SandboxRequest::new("second.py", "print(2)"))
+            .await
+            .unwrap();
+        assert_eq!(second.stdout, "second");
+
+        let fallback = mock
+            .execute(SandboxRequest::new("third.py", "print(3)"))
+            .await
+            .unwrap();
+        assert_eq!(fallback.exit_code, Some(0));
+
+        mock.expect_calls(3);
+        mock.assert_called_with(|request| request.script_contents == "print(2)");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "did not match the expected predicate")]
+    async fn panics_when_request_misses_predicate() {
+        let mock = MockSandboxExecutor::new().with_expected_response(
+            |request| request.script_name == "expected.py",
+            MockSandboxExecutor::success(""),
+        );
+        let _ = mock
+            .execute(SandboxRequest::new("other.py", "print(1)"))
+            .await;
+    }
+}
@@ -1,16 +1,33 @@
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::path::{Component, Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
-use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::process::Command;
+use tokio::sync::{Semaphore, SemaphorePermit};
 use tokio::time;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+mod cache;
+#[cfg(any(test, feature = "test-utils"))]
+mod mock;
+#[cfg(feature = "wasm-sandbox")]
+mod wasm;
+
+pub use cache::SandboxCache;
+#[cfg(any(test, feature = "test-utils"))]
+pub use mock::{MockSandboxExecutor, SandboxRequestPredicate};
+#[cfg(feature = "wasm-sandbox")]
+pub use wasm::{WasmSandboxConfig, WasmSandboxRunner};
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum SandboxOutputKind {
     Binary,
@@ -21,6 +38,9 @@ pub enum SandboxOutputKind {
 pub struct SandboxOutputSpec {
     pub path: String,
     pub kind: SandboxOutputKind,
+    /// Artefacts larger than this are skipped (empty bytes) instead of being read into memory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_limit_bytes: Option<u64>,
 }
 
 impl SandboxOutputSpec {
@@ -28,8 +48,14 @@ impl SandboxOutputSpec {
         Self {
             path: path.into(),
             kind,
+            size_limit_bytes: None,
         }
     }
+
+    pub fn with_size_limit(mut self, bytes: u64) -> Self {
+        self.size_limit_bytes = Some(bytes);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +73,37 @@ impl SandboxFile {
     }
 }
 
+/// Environment keys with this prefix configure the host and may not be set per request.
+const RESERVED_ENV_PREFIX: &str = "DEEPRESEARCH_";
+
+/// Interpreter used to run a request's script inside the container.
+///
+/// The stock image only ships Python; other runtimes need an image that provides the
+/// matching binary (see `DockerSandboxConfig::{bash,node,r}_binary`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxRuntime {
+    #[default]
+    Python,
+    Bash,
+    Node,
+    R,
+    /// Interpreter binary invoked as `<binary> <script> <args…>`.
+    Custom(String),
+}
+
+impl SandboxRuntime {
+    fn label(&self) -> &str {
+        match self {
+            SandboxRuntime::Python => "python",
+            SandboxRuntime::Bash => "bash",
+            SandboxRuntime::Node => "node",
+            SandboxRuntime::R => "r",
+            SandboxRuntime::Custom(binary) => binary,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SandboxRequest {
     pub script_name: String,
@@ -55,6 +112,20 @@ pub struct SandboxRequest {
     pub files: Vec<SandboxFile>,
     pub expected_outputs: Vec<SandboxOutputSpec>,
     pub timeout: Duration,
+    /// Bytes piped to the interpreter's stdin. With empty `script_contents` the program
+    /// itself is read from stdin (`python -`).
+    pub stdin: Option<Vec<u8>>,
+    /// Per-request environment variables, applied after `DockerSandboxConfig::env` so they
+    /// win on conflicts. Keys may not use the reserved `DEEPRESEARCH_` prefix.
+    pub env: Vec<(String, String)>,
+    /// Docker `--cpus` override for this run (e.g. `"0.5"`); falls back to
+    /// `DockerSandboxConfig::cpus`.
+    pub cpu_limit: Option<String>,
+    /// Docker `--memory` override for this run (e.g. `"256m"`); falls back to
+    /// `DockerSandboxConfig::memory_limit`.
+    pub memory_limit: Option<String>,
+    /// Interpreter for the script (Python by default).
+    pub runtime: SandboxRuntime,
 }
 
 impl SandboxRequest {
@@ -66,13 +137,49 @@ impl SandboxRequest {
             files: Vec::new(),
             expected_outputs: Vec::new(),
             timeout: Duration::from_secs(60),
+            stdin: None,
+            env: Vec::new(),
+            cpu_limit: None,
+            memory_limit: None,
+            runtime: SandboxRuntime::Python,
         }
     }
 
+    pub fn with_runtime(mut self, runtime: SandboxRuntime) -> Self {
+        self.runtime = runtime;
+        self
+    }
+
+    pub fn with_stdin(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.stdin = Some(bytes.into());
+        self
+    }
+
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn with_cpu_limit(mut self, cpus: impl Into<String>) -> Self {
+        self.cpu_limit = Some(cpus.into());
+        self
+    }
+
+    pub fn with_memory_limit(mut self, memory: impl Into<String>) -> Self {
+        self.memory_limit = Some(memory.into());
+        self
+    }
+
+    fn reads_script_from_stdin(&self) -> bool {
+        self.stdin.is_some() && self.script_contents.trim().is_empty()
+    }
+
     pub fn validate(&self) -> Result<()> {
         ensure_relpath(&self.script_name)
             .with_context(|| format!("script name '{}' must be relative", self.script_name))?;
-        ensure_not_empty(&self.script_contents, "script_contents")?;
+        if self.stdin.is_none() {
+            ensure_not_empty(&self.script_contents, "script_contents")?;
+        }
         for file in &self.files {
             ensure_relpath(&file.path)
                 .with_context(|| format!("file path '{}' must be relative", file.path))?;
@@ -81,8 +188,126 @@ impl SandboxRequest {
             ensure_relpath(&spec.path)
                 .with_context(|| format!("output path '{}' must be relative", spec.path))?;
         }
+        for (key, _) in &self.env {
+            ensure_not_empty(key, "env key")?;
+            if key.contains('=') {
+                return Err(anyhow!("env key '{key}' must not contain '='"));
+            }
+            if key.starts_with(RESERVED_ENV_PREFIX) {
+                return Err(anyhow!(
+                    "env key '{key}' uses the reserved {RESERVED_ENV_PREFIX} prefix"
+                ));
+            }
+        }
+        if let Some(cpus) = &self.cpu_limit {
+            match cpus.trim().parse::<f64>() {
+                Ok(value) if value.is_finite() && value > 0.0 => {}
+                _ => return Err(anyhow!("cpu limit '{cpus}' must be a positive number")),
+            }
+        }
+        if let Some(memory) = &self.memory_limit
+            && !is_docker_memory_limit(memory)
+        {
+            return Err(anyhow!(
+                "memory limit '{memory}' must be digits with an optional k, m, or g suffix"
+            ));
+        }
         Ok(())
     }
+
+    /// Stable SHA-256 (hex) over everything that influences execution, independent of the
+    /// order in which input files and expected outputs were supplied.
+    pub fn to_reproducible_hash(&self) -> String {
+        fn field(hasher: &mut Sha256, bytes: &[u8]) {
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        }
+
+        let mut hasher = Sha256::new();
+        field(&mut hasher, self.script_name.as_bytes());
+        field(&mut hasher, self.script_contents.as_bytes());
+
+        hasher.update((self.args.len() as u64).to_le_bytes());
+        for arg in &self.args {
+            field(&mut hasher, arg.as_bytes());
+        }
+
+        let mut files: Vec<&SandboxFile> = self.files.iter().collect();
+        files.sort_by(|a, b| {
+            a.path
+                .cmp(&b.path)
+                .then_with(|| a.contents.cmp(&b.contents))
+        });
+        hasher.update((files.len() as u64).to_le_bytes());
+        for file in files {
+            field(&mut hasher, file.path.as_bytes());
+            field(&mut hasher, &file.contents);
+        }
+
+        let mut outputs: Vec<(&str, u8, Option<u64>)> = self
+            .expected_outputs
+            .iter()
+            .map(|spec| {
+                let kind = match spec.kind {
+                    SandboxOutputKind::Binary => 0,
+                    SandboxOutputKind::Text => 1,
+                };
+                (spec.path.as_str(), kind, spec.size_limit_bytes)
+            })
+            .collect();
+        outputs.sort();
+        hasher.update((outputs.len() as u64).to_le_bytes());
+        for (path, kind, size_limit) in outputs {
+            field(&mut hasher, path.as_bytes());
+            hasher.update([kind]);
+            match size_limit {
+                Some(limit) => {
+                    hasher.update([1]);
+                    hasher.update(limit.to_le_bytes());
+                }
+                None => hasher.update([0]),
+            }
+        }
+
+        hasher.update(self.timeout.as_secs().to_le_bytes());
+
+        match &self.stdin {
+            Some(bytes) => {
+                hasher.update([1]);
+                field(&mut hasher, bytes);
+            }
+            None => hasher.update([0]),
+        }
+
+        // Docker applies the last value for a repeated key, so only that one counts.
+        let env: BTreeMap<&str, &str> = self
+            .env
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        hasher.update((env.len() as u64).to_le_bytes());
+        for (key, value) in env {
+            field(&mut hasher, key.as_bytes());
+            field(&mut hasher, value.as_bytes());
+        }
+
+        for limit in [&self.cpu_limit, &self.memory_limit] {
+            match limit {
+                Some(value) => {
+                    hasher.update([1]);
+                    field(&mut hasher, value.as_bytes());
+                }
+                None => hasher.update([0]),
+            }
+        }
+
+        // Python requests keep the hashes they had before runtimes were selectable.
+        if self.runtime != SandboxRuntime::Python {
+            field(&mut hasher, self.runtime.label().as_bytes());
+        }
+
+        format!("{:x}", hasher.finalize())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -109,6 +334,25 @@ pub struct SandboxResult {
     pub outputs: Vec<SandboxOutput>,
     pub timed_out: bool,
     pub duration: Duration,
+    /// Set when one or more expected outputs exceeded `size_limit_bytes` and were skipped.
+    pub stderr_overflow_warning: Option<String>,
+    /// CPU/memory consumed by the run, when the runtime could sample it.
+    pub resource_usage: Option<SandboxResourceUsage>,
+    /// `(path, error)` for each expected output that could not be read after the run,
+    /// so a wrong output path is distinguishable from a failed script.
+    pub partial_output_errors: Vec<(String, String)>,
+}
+
+/// CPU and memory consumed by a sandbox run, sampled from `docker stats` while the
+/// container is alive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SandboxResourceUsage {
+    /// Mean CPU utilisation across samples (`100.0` = one full core).
+    pub cpu_percent: f64,
+    /// Memory in use at the last sample.
+    pub memory_mib: f64,
+    /// Highest memory usage observed.
+    pub peak_memory_mib: f64,
 }
 
 #[async_trait]
@@ -116,12 +360,92 @@ pub trait SandboxExecutor: Send + Sync {
     async fn execute(&self, request: SandboxRequest) -> Result<SandboxResult>;
 }
 
+/// Sandbox runtime to construct for a session when no executor is supplied directly.
+#[derive(Debug, Clone)]
+pub enum SandboxChoice {
+    Docker(DockerSandboxConfig),
+    /// WASI CPython under wasmtime, for hosts without a container runtime.
+    #[cfg(feature = "wasm-sandbox")]
+    Wasm(WasmSandboxConfig),
+}
+
+impl SandboxChoice {
+    pub fn build(&self) -> Result<std::sync::Arc<dyn SandboxExecutor>> {
+        match self {
+            SandboxChoice::Docker(config) => Ok(std::sync::Arc::new(DockerSandboxRunner::new(
+                config.clone(),
+            )?)),
+            #[cfg(feature = "wasm-sandbox")]
+            SandboxChoice::Wasm(config) => {
+                Ok(std::sync::Arc::new(WasmSandboxRunner::new(config.clone())?))
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum DockerRuntimeUser {
     CurrentUser,
     Explicit(String),
 }
 
+/// Container paths managed by the runner itself; user mounts may not shadow them.
+const RESERVED_CONTAINER_PATHS: &[&str] = &["/workspace", "/tmp", "/var/tmp", "/run"];
+
+/// Host directory bind-mounted into every sandbox container (e.g. model weights, corpora).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxVolumeMount {
+    pub host_path: PathBuf,
+    pub container_path: PathBuf,
+    pub read_only: bool,
+}
+
+impl SandboxVolumeMount {
+    pub fn read_only(host_path: impl Into<PathBuf>, container_path: impl Into<PathBuf>) -> Self {
+        Self {
+            host_path: host_path.into(),
+            container_path: container_path.into(),
+            read_only: true,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        ensure_abspath(&self.host_path)
+            .with_context(|| format!("host path '{}' is invalid", self.host_path.display()))?;
+        ensure_abspath(&self.container_path).with_context(|| {
+            format!(
+                "container path '{}' is invalid",
+                self.container_path.display()
+            )
+        })?;
+        for reserved in RESERVED_CONTAINER_PATHS {
+            let reserved = Path::new(reserved);
+            if self.container_path.starts_with(reserved)
+                || reserved.starts_with(&self.container_path)
+            {
+                return Err(anyhow!(
+                    "container path '{}' overlaps reserved sandbox path '{}'",
+                    self.container_path.display(),
+                    reserved.display()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn docker_arg(&self) -> String {
+        let mut arg = format!(
+            "{}:{}",
+            self.host_path.display(),
+            self.container_path.display()
+        );
+        if self.read_only {
+            arg.push_str(":ro");
+        }
+        arg
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DockerSandboxConfig {
     pub image: String,
@@ -136,7 +460,28 @@ pub struct DockerSandboxConfig {
     pub read_only_root: bool,
     pub disable_network: bool,
     pub python_binary: String,
+    /// Interpreters for `SandboxRuntime::{Bash, Node, R}` requests.
+    pub bash_binary: String,
+    pub node_binary: String,
+    pub r_binary: String,
     pub user: DockerRuntimeUser,
+    pub volume_mounts: Vec<SandboxVolumeMount>,
+    /// Poll `docker stats` while the container runs and report `SandboxResult::resource_usage`.
+    /// Runs shorter than one stats round-trip (~1-2s) may yield no sample.
+    pub collect_resource_usage: bool,
+}
+
+impl DockerSandboxConfig {
+    /// Interpreter binary for `runtime`.
+    pub fn interpreter(&self, runtime: &SandboxRuntime) -> &str {
+        match runtime {
+            SandboxRuntime::Python => &self.python_binary,
+            SandboxRuntime::Bash => &self.bash_binary,
+            SandboxRuntime::Node => &self.node_binary,
+            SandboxRuntime::R => &self.r_binary,
+            SandboxRuntime::Custom(binary) => binary,
+        }
+    }
 }
 
 impl Default for DockerSandboxConfig {
@@ -159,7 +504,12 @@ impl Default for DockerSandboxConfig {
             read_only_root: true,
             disable_network: true,
             python_binary: "python".to_string(),
+            bash_binary: "bash".to_string(),
+            node_binary: "node".to_string(),
+            r_binary: "Rscript".to_string(),
             user: DockerRuntimeUser::CurrentUser,
+            volume_mounts: Vec::new(),
+            collect_resource_usage: false,
         }
     }
 }
@@ -172,8 +522,55 @@ pub struct DockerSandboxRunner {
 
 static SANDBOX_FAILURE_STREAK: AtomicUsize = AtomicUsize::new(0);
 
+const MAX_SANDBOX_CONCURRENCY_ENV: &str = "DEEPRESEARCH_MAX_SANDBOX_CONCURRENCY";
+const DEFAULT_MAX_SANDBOX_CONCURRENCY: usize = 3;
+
+/// Process-wide cap on concurrently running containers, shared by every runner instance.
+static SANDBOX_PERMITS: Lazy<Semaphore> = Lazy::new(|| {
+    Semaphore::new(parse_max_concurrency(
+        std::env::var(MAX_SANDBOX_CONCURRENCY_ENV).ok().as_deref(),
+    ))
+});
+static SANDBOX_QUEUED: AtomicUsize = AtomicUsize::new(0);
+
+fn parse_max_concurrency(raw: Option<&str>) -> usize {
+    raw.and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_MAX_SANDBOX_CONCURRENCY)
+}
+
+/// Tracks a caller waiting for a sandbox permit; leaves the queue on drop, including cancellation.
+struct QueuedSandbox;
+
+impl QueuedSandbox {
+    fn enter() -> Self {
+        let queued = SANDBOX_QUEUED.fetch_add(1, Ordering::Relaxed) + 1;
+        crate::record_sandbox_queue_change(1);
+        debug!(queued, "waiting for sandbox concurrency permit");
+        Self
+    }
+}
+
+impl Drop for QueuedSandbox {
+    fn drop(&mut self) {
+        SANDBOX_QUEUED.fetch_sub(1, Ordering::Relaxed);
+        crate::record_sandbox_queue_change(-1);
+    }
+}
+
+async fn acquire_sandbox_permit() -> Result<SemaphorePermit<'static>> {
+    let _queued = QueuedSandbox::enter();
+    SANDBOX_PERMITS
+        .acquire()
+        .await
+        .map_err(|_| anyhow!("sandbox concurrency semaphore closed"))
+}
+
 impl DockerSandboxRunner {
     pub fn new(config: DockerSandboxConfig) -> Result<Self> {
+        for mount in &config.volume_mounts {
+            mount.validate()?;
+        }
         std::fs::create_dir_all(&config.workspace_root).with_context(|| {
             format!(
                 "failed to create workspace root {}",
@@ -190,7 +587,7 @@ impl DockerSandboxRunner {
     }
 
     #[tracing::instrument(skip(self, request), fields(script = %request.script_name))]
-    async fn execute_internal(&self, request: SandboxRequest) -> Result<SandboxResult> {
+    async fn execute_internal(&self, mut request: SandboxRequest) -> Result<SandboxResult> {
         request.validate()?;
 
         let run_id = Uuid::new_v4().to_string();
@@ -204,27 +601,40 @@ impl DockerSandboxRunner {
 
         let guard = WorkspaceGuard::new(workspace_dir.clone());
 
-        write_file(
-            &workspace_dir,
-            &request.script_name,
-            request.script_contents.as_bytes(),
-        )?;
+        if !request.reads_script_from_stdin() {
+            write_file(
+                &workspace_dir,
+                &request.script_name,
+                request.script_contents.as_bytes(),
+            )?;
+        }
         for file in &request.files {
             write_file(&workspace_dir, &file.path, &file.contents)?;
         }
 
+        // Docker refuses to overwrite an existing cidfile, so keep it outside the mounted workspace.
+        let cidfile = self
+            .config
+            .collect_resource_usage
+            .then(|| self.config.workspace_root.join(format!("{run_id}.cid")));
         let docker_args = build_docker_args(
             &self.config,
             &workspace_dir,
             &request,
             self.uid_gid.as_deref(),
+            cidfile.as_deref(),
         );
         debug!(args = ?docker_args, "prepared docker invocation");
 
         let mut cmd = Command::new(&self.config.docker_binary);
-        cmd.stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .kill_on_drop(true);
+        cmd.stdin(if request.stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
 
         for arg in &docker_args {
             cmd.arg(arg);
@@ -238,11 +648,28 @@ impl DockerSandboxRunner {
         );
 
         let mut child = cmd.spawn().context("failed to spawn docker process")?;
+        let resource_samples = Arc::new(std::sync::Mutex::new(ResourceSamples::default()));
+        let sampler = cidfile.clone().map(|path| {
+            AbortOnDrop(tokio::spawn(sample_resource_usage(
+                self.config.docker_binary.clone(),
+                path,
+                resource_samples.clone(),
+            )))
+        });
         let stdout_reader = child.stdout.take();
         let stderr_reader = child.stderr.take();
 
         let stdout_task = tokio::spawn(async move { read_pipe(stdout_reader).await });
         let stderr_task = tokio::spawn(async move { read_pipe(stderr_reader).await });
+        let stdin_task = match (child.stdin.take(), request.stdin.take()) {
+            (Some(mut writer), Some(bytes)) => Some(tokio::spawn(async move {
+                // Dropping the writer afterwards closes stdin so the script sees EOF.
+                if let Err(err) = writer.write_all(&bytes).await {
+                    warn!(error = %err, "failed to write sandbox stdin");
+                }
+            })),
+            _ => None,
+        };
 
         let wait_result = time::timeout(request.timeout, child.wait()).await;
 
@@ -264,6 +691,19 @@ impl DockerSandboxRunner {
             }
         };
 
+        // The child has exited; stop a writer still blocked on a pipe nobody reads.
+        if let Some(task) = stdin_task {
+            task.abort();
+        }
+        drop(sampler);
+        if let Some(path) = &cidfile {
+            let _ = std::fs::remove_file(path);
+        }
+        let resource_usage = resource_samples
+            .lock()
+            .ok()
+            .and_then(|samples| samples.finish());
+
         let stdout_bytes = stdout_task
             .await
             .context("failed to join stdout collection task")??;
@@ -276,25 +716,11 @@ impl DockerSandboxRunner {
         let exit_code = status.code();
         let duration = start.elapsed();
 
-        let mut collected_outputs = Vec::with_capacity(request.expected_outputs.len());
-        for spec in &request.expected_outputs {
-            let output_path = workspace_dir.join(&spec.path);
-            match std::fs::read(&output_path) {
-                Ok(bytes) => {
-                    collected_outputs.push(SandboxOutput {
-                        spec: spec.clone(),
-                        bytes,
-                    });
-                }
-                Err(err) => {
-                    warn!(
-                        path = %output_path.display(),
-                        error = %err,
-                        "expected output missing from sandbox workspace"
-                    );
-                }
-            }
-        }
+        let CollectedOutputs {
+            outputs: collected_outputs,
+            overflow_warnings,
+            partial_output_errors,
+        } = collect_outputs(&workspace_dir, &request.expected_outputs);
 
         drop(guard);
 
@@ -328,6 +754,7 @@ impl DockerSandboxRunner {
             timed_out,
             duration_ms = duration.as_millis() as u64,
             outputs = collected_outputs.len(),
+            missing_outputs = partial_output_errors.len(),
             failure_streak,
             "sandbox execution finished"
         );
@@ -348,6 +775,15 @@ impl DockerSandboxRunner {
             collected_outputs.len(),
             failure_streak as u64,
         );
+        if let Some(usage) = &resource_usage {
+            debug!(
+                cpu_percent = usage.cpu_percent,
+                memory_mib = usage.memory_mib,
+                peak_memory_mib = usage.peak_memory_mib,
+                "sandbox resource usage"
+            );
+            crate::record_sandbox_resource_usage(usage.cpu_percent, usage.memory_mib);
+        }
 
         Ok(SandboxResult {
             exit_code,
@@ -356,10 +792,18 @@ impl DockerSandboxRunner {
             outputs: collected_outputs,
             timed_out,
             duration,
+            stderr_overflow_warning: if overflow_warnings.is_empty() {
+                None
+            } else {
+                Some(overflow_warnings.join("; "))
+            },
+            resource_usage,
+            partial_output_errors,
         })
     }
 
     pub async fn execute(&self, request: SandboxRequest) -> Result<SandboxResult> {
+        let _permit = acquire_sandbox_permit().await?;
         self.execute_internal(request).await
     }
 }
@@ -367,31 +811,160 @@ impl DockerSandboxRunner {
 #[async_trait]
 impl SandboxExecutor for DockerSandboxRunner {
     async fn execute(&self, request: SandboxRequest) -> Result<SandboxResult> {
-        self.execute_internal(request).await
+        DockerSandboxRunner::execute(self, request).await
+    }
+}
+
+const RESOURCE_SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Aborts the wrapped task when dropped, so early returns cannot leave it running.
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[derive(Debug, Default)]
+struct ResourceSamples {
+    cpu_total: f64,
+    count: usize,
+    last_memory_mib: f64,
+    peak_memory_mib: f64,
+}
+
+impl ResourceSamples {
+    fn push(&mut self, cpu_percent: f64, memory_mib: f64) {
+        self.cpu_total += cpu_percent;
+        self.count += 1;
+        self.last_memory_mib = memory_mib;
+        self.peak_memory_mib = self.peak_memory_mib.max(memory_mib);
+    }
+
+    fn finish(&self) -> Option<SandboxResourceUsage> {
+        (self.count > 0).then(|| SandboxResourceUsage {
+            cpu_percent: self.cpu_total / self.count as f64,
+            memory_mib: self.last_memory_mib,
+            peak_memory_mib: self.peak_memory_mib,
+        })
+    }
+}
+
+/// Sample `docker stats --no-stream` for the container named in `cidfile` until the
+/// container disappears or the task is aborted.
+async fn sample_resource_usage(
+    docker_binary: String,
+    cidfile: PathBuf,
+    samples: Arc<std::sync::Mutex<ResourceSamples>>,
+) {
+    let container_id = loop {
+        match tokio::fs::read_to_string(&cidfile).await {
+            Ok(id) if !id.trim().is_empty() => break id.trim().to_string(),
+            _ => time::sleep(Duration::from_millis(50)).await,
+        }
+    };
+
+    loop {
+        let output = Command::new(&docker_binary)
+            .args(["stats", "--no-stream", "--format", "{{json .}}"])
+            .arg(&container_id)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .output()
+            .await;
+        let Ok(output) = output else {
+            break;
+        };
+        if !output.status.success() {
+            // `--rm` removed the container; nothing left to sample.
+            break;
+        }
+        if let Some((cpu_percent, memory_mib)) =
+            parse_docker_stats(&String::from_utf8_lossy(&output.stdout))
+            && let Ok(mut samples) = samples.lock()
+        {
+            samples.push(cpu_percent, memory_mib);
+        }
+        time::sleep(RESOURCE_SAMPLE_INTERVAL).await;
     }
 }
 
+#[derive(Deserialize)]
+struct DockerStatsLine {
+    #[serde(rename = "CPUPerc")]
+    cpu_perc: String,
+    #[serde(rename = "MemUsage")]
+    mem_usage: String,
+}
+
+/// Parse one `docker stats --format '{{json .}}'` line into `(cpu_percent, memory_mib)`.
+fn parse_docker_stats(raw: &str) -> Option<(f64, f64)> {
+    let line = raw.lines().find(|line| !line.trim().is_empty())?;
+    let stats: DockerStatsLine = serde_json::from_str(line).ok()?;
+    let cpu_percent = stats.cpu_perc.trim().trim_end_matches('%').parse().ok()?;
+    let used = stats.mem_usage.split('/').next()?;
+    Some((cpu_percent, parse_memory_mib(used)?))
+}
+
+/// Convert a docker size such as `12.5MiB` or `1.2GB` to MiB.
+fn parse_memory_mib(raw: &str) -> Option<f64> {
+    const MIB: f64 = 1024.0 * 1024.0;
+    let raw = raw.trim();
+    let split = raw.find(|ch: char| ch.is_ascii_alphabetic())?;
+    let (value, unit) = raw.split_at(split);
+    let value: f64 = value.trim().parse().ok()?;
+    let bytes = match unit {
+        "B" => value,
+        "KiB" => value * 1024.0,
+        "kB" | "KB" => value * 1e3,
+        "MiB" => value * MIB,
+        "MB" => value * 1e6,
+        "GiB" => value * MIB * 1024.0,
+        "GB" => value * 1e9,
+        "TiB" => value * MIB * MIB,
+        "TB" => value * 1e12,
+        _ => return None,
+    };
+    Some(bytes / MIB)
+}
+
 fn build_docker_args(
     config: &DockerSandboxConfig,
     workspace_dir: &Path,
     request: &SandboxRequest,
     uid_gid: Option<&str>,
+    cidfile: Option<&Path>,
 ) -> Vec<String> {
     let mut args = Vec::new();
     args.push("run".to_string());
     args.push("--rm".to_string());
+    if let Some(path) = cidfile {
+        args.push("--cidfile".to_string());
+        args.push(path.display().to_string());
+    }
+    if request.stdin.is_some() {
+        args.push("--interactive".to_string());
+    }
 
     if config.disable_network {
         args.push("--network".to_string());
         args.push("none".to_string());
     }
 
-    if let Some(memory) = &config.memory_limit {
+    if let Some(memory) = capped_limit(
+        request.memory_limit.as_ref(),
+        config.memory_limit.as_ref(),
+        docker_memory_bytes,
+    ) {
         args.push("--memory".to_string());
         args.push(memory.clone());
     }
 
-    if let Some(cpus) = &config.cpus {
+    if let Some(cpus) = capped_limit(request.cpu_limit.as_ref(), config.cpus.as_ref(), |value| {
+        value.trim().parse().ok()
+    }) {
         args.push("--cpus".to_string());
         args.push(cpus.clone());
     }
@@ -421,10 +994,14 @@ fn build_docker_args(
 
     args.push("-v".to_string());
     args.push(format!("{}:/workspace:rw", workspace_dir.display()));
+    for mount in &config.volume_mounts {
+        args.push("-v".to_string());
+        args.push(mount.docker_arg());
+    }
     args.push("-w".to_string());
     args.push("/workspace".to_string());
 
-    for (key, value) in &config.env {
+    for (key, value) in config.env.iter().chain(&request.env) {
         args.push("--env".to_string());
         args.push(format!("{}={}", key, value));
     }
@@ -437,13 +1014,117 @@ fn build_docker_args(
     args.extend(config.additional_args.iter().cloned());
 
     args.push(config.image.clone());
-    args.push(config.python_binary.clone());
-    args.push(format!("/workspace/{}", request.script_name));
+    args.push(config.interpreter(&request.runtime).to_string());
+    if request.reads_script_from_stdin() {
+        args.push("-".to_string());
+    } else {
+        args.push(format!("/workspace/{}", request.script_name));
+    }
     args.extend(request.args.iter().cloned());
 
     args
 }
 
+/// Expected outputs read back from a sandbox workspace.
+struct CollectedOutputs {
+    outputs: Vec<SandboxOutput>,
+    overflow_warnings: Vec<String>,
+    partial_output_errors: Vec<(String, String)>,
+}
+
+/// Read expected outputs from the workspace, skipping (with a warning) files over their size
+/// limit and recording the ones that could not be read.
+fn collect_outputs(workspace_dir: &Path, specs: &[SandboxOutputSpec]) -> CollectedOutputs {
+    let mut collected_outputs = Vec::with_capacity(specs.len());
+    let mut overflow_warnings = Vec::new();
+    let mut partial_output_errors = Vec::new();
+    for spec in specs {
+        let output_path = workspace_dir.join(&spec.path);
+        if let Some(limit) = spec.size_limit_bytes
+            && let Ok(metadata) = std::fs::metadata(&output_path)
+            && metadata.len() > limit
+        {
+            warn!(
+                path = %output_path.display(),
+                size = metadata.len(),
+                limit,
+                "sandbox output exceeds size limit; skipping collection"
+            );
+            overflow_warnings.push(format!(
+                "output '{}' is {} bytes, exceeding the {limit}-byte limit; contents dropped",
+                spec.path,
+                metadata.len()
+            ));
+            collected_outputs.push(SandboxOutput {
+                spec: spec.clone(),
+                bytes: Vec::new(),
+            });
+            continue;
+        }
+        match std::fs::read(&output_path) {
+            Ok(bytes) => {
+                collected_outputs.push(SandboxOutput {
+                    spec: spec.clone(),
+                    bytes,
+                });
+            }
+            Err(err) => {
+                warn!(
+                    path = %output_path.display(),
+                    error = %err,
+                    "expected output missing from sandbox workspace"
+                );
+                partial_output_errors.push((spec.path.clone(), err.to_string()));
+            }
+        }
+    }
+    CollectedOutputs {
+        outputs: collected_outputs,
+        overflow_warnings,
+        partial_output_errors,
+    }
+}
+
+/// A per-request limit may tighten the configured ceiling but never raise it; when either
+/// value does not parse, the configured one wins.
+fn capped_limit<'a>(
+    requested: Option<&'a String>,
+    ceiling: Option<&'a String>,
+    parse: impl Fn(&str) -> Option<f64>,
+) -> Option<&'a String> {
+    match (requested, ceiling) {
+        (Some(requested), Some(ceiling)) => match (parse(requested), parse(ceiling)) {
+            (Some(wanted), Some(allowed)) if wanted < allowed => Some(requested),
+            _ => Some(ceiling),
+        },
+        (requested, ceiling) => requested.or(ceiling),
+    }
+}
+
+/// Bytes for a Docker `--memory` value (digits with an optional b/k/m/g suffix).
+fn docker_memory_bytes(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let (digits, scale) = match value.chars().last()? {
+        'b' | 'B' => (&value[..value.len() - 1], 1.0),
+        'k' | 'K' => (&value[..value.len() - 1], 1024.0),
+        'm' | 'M' => (&value[..value.len() - 1], 1024.0 * 1024.0),
+        'g' | 'G' => (&value[..value.len() - 1], 1024.0 * 1024.0 * 1024.0),
+        _ => (value, 1.0),
+    };
+    digits
+        .parse::<u64>()
+        .ok()
+        .map(|amount| amount as f64 * scale)
+}
+
+/// Docker memory sizes: digits with an optional `k`, `m`, or `g` suffix.
+fn is_docker_memory_limit(value: &str) -> bool {
+    let digits = value
+        .strip_suffix(['k', 'm', 'g', 'K', 'M', 'G'])
+        .unwrap_or(value);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
 fn ensure_not_empty(value: &str, field: &str) -> Result<()> {
     if value.trim().is_empty() {
         Err(anyhow!("{field} must not be empty"))
@@ -463,6 +1144,16 @@ fn ensure_relpath(path: &str) -> Result<PathBuf> {
     Ok(pb)
 }
 
+fn ensure_abspath(path: &Path) -> Result<()> {
+    if !path.is_absolute() {
+        return Err(anyhow!("path must be absolute"));
+    }
+    if path.components().any(|c| matches!(c, Component::ParentDir)) {
+        return Err(anyhow!("path may not contain parent components (..)"));
+    }
+    Ok(())
+}
+
 fn write_file(base: &Path, rel: &str, contents: &[u8]) -> Result<()> {
     let rel_path = ensure_relpath(rel)?;
     let full = base.join(&rel_path);
@@ -544,7 +1235,12 @@ mod tests {
             read_only_root: true,
             disable_network: true,
             python_binary: "python".to_string(),
+            bash_binary: "bash".to_string(),
+            node_binary: "node".to_string(),
+            r_binary: "Rscript".to_string(),
             user: DockerRuntimeUser::Explicit("1000:1000".to_string()),
+            volume_mounts: Vec::new(),
+            collect_resource_usage: false,
         };
 
         let request = SandboxRequest {
@@ -554,9 +1250,14 @@ mod tests {
             files: Vec::new(),
             expected_outputs: Vec::new(),
             timeout: Duration::from_secs(5),
+            stdin: None,
+            env: Vec::new(),
+            cpu_limit: None,
+            memory_limit: None,
+            runtime: SandboxRuntime::Python,
         };
         let workspace = PathBuf::from("/tmp/workspace");
-        let args = build_docker_args(&config, &workspace, &request, Some("1000:1000"));
+        let args = build_docker_args(&config, &workspace, &request, Some("1000:1000"), None);
 
         assert!(args.contains(&"--read-only".to_string()));
         assert!(args.contains(&"--network".to_string()));
@@ -569,4 +1270,311 @@ mod tests {
         assert!(args.iter().any(|a| a.contains("/workspace/script.py")));
         assert!(args.ends_with(&["--foo".to_string()]));
     }
+
+    #[test]
+    fn stdin_requests_run_interactively_with_cidfile() {
+        let config = DockerSandboxConfig::default();
+        let workspace = PathBuf::from("/tmp/workspace");
+
+        let plain = SandboxRequest::new("script.py", "print('hello')");
+        let args = build_docker_args(&config, &workspace, &plain, None, None);
+        assert!(!args.contains(&"--interactive".to_string()));
+        assert!(!args.contains(&"--cidfile".to_string()));
+
+        let piped = SandboxRequest::new("script.py", "").with_stdin("print('from stdin')");
+        piped
+            .validate()
+            .expect("empty script is allowed when stdin is supplied");
+        let cidfile = PathBuf::from("/tmp/run.cid");
+        let args = build_docker_args(&config, &workspace, &piped, None, Some(&cidfile));
+        assert!(args.contains(&"--interactive".to_string()));
+        let cid_flag = args
+            .iter()
+            .position(|a| a == "--cidfile")
+            .expect("cidfile flag");
+        assert_eq!(args[cid_flag + 1], "/tmp/run.cid");
+        assert!(args.ends_with(&["-".to_string()]));
+
+        assert!(SandboxRequest::new("script.py", "").validate().is_err());
+    }
+
+    #[test]
+    fn docker_stats_lines_are_parsed() {
+        let line = r#"{"BlockIO":"0B / 0B","CPUPerc":"87.50%","Container":"abc","MemPerc":"1.2%","MemUsage":"256MiB / 2GiB","Name":"x"}"#;
+        let (cpu, memory) = parse_docker_stats(line).expect("parsed");
+        assert!((cpu - 87.5).abs() < f64::EPSILON);
+        assert!((memory - 256.0).abs() < f64::EPSILON);
+
+        assert_eq!(parse_memory_mib("1.5GiB"), Some(1536.0));
+        assert_eq!(parse_memory_mib("512KiB"), Some(0.5));
+        assert_eq!(parse_memory_mib("0B"), Some(0.0));
+        assert_eq!(parse_memory_mib("--"), None);
+        assert!(parse_docker_stats(r#"{"CPUPerc":"--","MemUsage":"-- / --"}"#).is_none());
+
+        let mut samples = ResourceSamples::default();
+        assert!(samples.finish().is_none());
+        samples.push(50.0, 100.0);
+        samples.push(150.0, 80.0);
+        let usage = samples.finish().expect("usage");
+        assert_eq!(usage.cpu_percent, 100.0);
+        assert_eq!(usage.memory_mib, 80.0);
+        assert_eq!(usage.peak_memory_mib, 100.0);
+    }
+
+    #[test]
+    fn oversized_outputs_are_skipped_with_warning() {
+        let workspace = tempfile::TempDir::new().expect("temp dir");
+        std::fs::write(workspace.path().join("small.txt"), "ok").expect("write");
+        std::fs::write(workspace.path().join("large.bin"), vec![0u8; 64]).expect("write");
+
+        let specs = vec![
+            SandboxOutputSpec::new("small.txt", SandboxOutputKind::Text).with_size_limit(16),
+            SandboxOutputSpec::new("large.bin", SandboxOutputKind::Binary).with_size_limit(16),
+        ];
+        let collected = collect_outputs(workspace.path(), &specs);
+
+        assert_eq!(collected.outputs.len(), 2);
+        assert_eq!(collected.outputs[0].bytes, b"ok");
+        assert!(collected.outputs[1].bytes.is_empty());
+        assert_eq!(collected.overflow_warnings.len(), 1);
+        assert!(collected.overflow_warnings[0].contains("large.bin"));
+        assert!(collected.partial_output_errors.is_empty());
+    }
+
+    #[test]
+    fn missing_outputs_are_reported_as_partial_errors() {
+        let workspace = tempfile::TempDir::new().expect("temp dir");
+        std::fs::write(workspace.path().join("present.txt"), "ok").expect("write");
+
+        let specs = vec![
+            SandboxOutputSpec::new("present.txt", SandboxOutputKind::Text),
+            SandboxOutputSpec::new("plots/missing.png", SandboxOutputKind::Binary),
+        ];
+        let collected = collect_outputs(workspace.path(), &specs);
+
+        assert_eq!(collected.outputs.len(), 1);
+        assert_eq!(collected.partial_output_errors.len(), 1);
+        let (path, error) = &collected.partial_output_errors[0];
+        assert_eq!(path, "plots/missing.png");
+        assert!(!error.is_empty());
+    }
+
+    #[test]
+    fn max_concurrency_defaults_for_missing_or_invalid_values() {
+        assert_eq!(parse_max_concurrency(None), DEFAULT_MAX_SANDBOX_CONCURRENCY);
+        assert_eq!(
+            parse_max_concurrency(Some("0")),
+            DEFAULT_MAX_SANDBOX_CONCURRENCY
+        );
+        assert_eq!(
+            parse_max_concurrency(Some("many")),
+            DEFAULT_MAX_SANDBOX_CONCURRENCY
+        );
+        assert_eq!(parse_max_concurrency(Some(" 6 ")), 6);
+    }
+
+    #[test]
+    fn volume_mounts_follow_the_workspace_mount() {
+        let config = DockerSandboxConfig {
+            volume_mounts: vec![SandboxVolumeMount::read_only("/srv/models", "/data/models")],
+            ..DockerSandboxConfig::default()
+        };
+        let workspace = PathBuf::from("/tmp/workspace");
+        let request = SandboxRequest::new("script.py", "print('hello')");
+        let args = build_docker_args(&config, &workspace, &request, None, None);
+
+        let workspace_mount = args
+            .iter()
+            .position(|a| a == "/tmp/workspace:/workspace:rw")
+            .expect("workspace mount");
+        assert_eq!(args[workspace_mount + 2], "/srv/models:/data/models:ro");
+    }
+
+    #[test]
+    fn volume_mounts_reject_relative_and_reserved_paths() {
+        SandboxVolumeMount::read_only("/srv/corpora", "/data/corpora")
+            .validate()
+            .expect("valid mount");
+
+        let relative = SandboxVolumeMount::read_only("srv/corpora", "/data/corpora");
+        assert!(relative.validate().is_err());
+
+        let traversal = SandboxVolumeMount::read_only("/srv/../etc", "/data/etc");
+        assert!(traversal.validate().is_err());
+
+        for target in ["/workspace/data", "/tmp", "/"] {
+            let mount = SandboxVolumeMount::read_only("/srv/corpora", target);
+            assert!(mount.validate().is_err(), "{target} should be rejected");
+        }
+    }
+
+    #[test]
+    fn reproducible_hash_ignores_file_and_output_order() {
+        let mut first = SandboxRequest::new("plot.py", "print('hi')");
+        first.files = vec![
+            SandboxFile::new("a.csv", "1,2"),
+            SandboxFile::new("b.csv", "3,4"),
+        ];
+        first.expected_outputs = vec![
+            SandboxOutputSpec::new("out.png", SandboxOutputKind::Binary),
+            SandboxOutputSpec::new("out.txt", SandboxOutputKind::Text),
+        ];
+
+        let mut second = first.clone();
+        second.files.reverse();
+        second.expected_outputs.reverse();
+        assert_eq!(first.to_reproducible_hash(), second.to_reproducible_hash());
+        assert_eq!(first.to_reproducible_hash().len(), 64);
+
+        let mut changed = first.clone();
+        changed.args.push("--verbose".to_string());
+        assert_ne!(first.to_reproducible_hash(), changed.to_reproducible_hash());
+
+        let mut slower = first.clone();
+        slower.timeout = Duration::from_secs(120);
+        assert_ne!(first.to_reproducible_hash(), slower.to_reproducible_hash());
+
+        let seeded = first
+            .clone()
+            .with_env("SEED", "1")
+            .with_env("DATASET", "a.csv");
+        let reseeded = first
+            .clone()
+            .with_env("DATASET", "a.csv")
+            .with_env("SEED", "1");
+        assert_ne!(first.to_reproducible_hash(), seeded.to_reproducible_hash());
+        assert_eq!(
+            seeded.to_reproducible_hash(),
+            reseeded.to_reproducible_hash()
+        );
+
+        let last_one = first.clone().with_env("A", "2").with_env("A", "1");
+        let last_two = first.clone().with_env("A", "1").with_env("A", "2");
+        assert_ne!(
+            last_one.to_reproducible_hash(),
+            last_two.to_reproducible_hash()
+        );
+        assert_eq!(
+            last_two.to_reproducible_hash(),
+            first.clone().with_env("A", "2").to_reproducible_hash()
+        );
+    }
+
+    #[test]
+    fn runtime_selects_interpreter_and_changes_hash() {
+        let config = DockerSandboxConfig {
+            image: "test-image:latest".to_string(),
+            ..DockerSandboxConfig::default()
+        };
+        let workspace = PathBuf::from("/tmp/workspace");
+
+        let node =
+            SandboxRequest::new("model.js", "console.log(1)").with_runtime(SandboxRuntime::Node);
+        let args = build_docker_args(&config, &workspace, &node, None, None);
+        let image = args
+            .iter()
+            .position(|a| a == "test-image:latest")
+            .expect("image");
+        assert_eq!(args[image + 1], "node");
+        assert_eq!(args[image + 2], "/workspace/model.js");
+
+        let custom = SandboxRequest::new("calc.jl", "println(1)")
+            .with_runtime(SandboxRuntime::Custom("julia".to_string()));
+        let args = build_docker_args(&config, &workspace, &custom, None, None);
+        assert_eq!(args[image + 1], "julia");
+        assert_ne!(
+            custom.to_reproducible_hash(),
+            SandboxRequest::new("calc.jl", "println(1)").to_reproducible_hash()
+        );
+    }
+
+    #[test]
+    fn request_resource_limits_are_capped_by_config() {
+        let config = DockerSandboxConfig {
+            memory_limit: Some("1g".to_string()),
+            cpus: Some("2".to_string()),
+            ..DockerSandboxConfig::default()
+        };
+        let workspace = PathBuf::from("/tmp/workspace");
+        let flag_value = |args: &[String], flag: &str| {
+            let index = args.iter().position(|a| a == flag).expect("flag present");
+            args[index + 1].clone()
+        };
+
+        let request = SandboxRequest::new("script.py", "print(1)");
+        let args = build_docker_args(&config, &workspace, &request, None, None);
+        assert_eq!(flag_value(&args, "--memory"), "1g");
+        assert_eq!(flag_value(&args, "--cpus"), "2");
+
+        let light = request.with_cpu_limit("0.5").with_memory_limit("256m");
+        light.validate().expect("valid limits");
+        let args = build_docker_args(&config, &workspace, &light, None, None);
+        assert_eq!(flag_value(&args, "--memory"), "256m");
+        assert_eq!(flag_value(&args, "--cpus"), "0.5");
+
+        let greedy = SandboxRequest::new("script.py", "print(1)")
+            .with_cpu_limit("8")
+            .with_memory_limit("4096m");
+        let args = build_docker_args(&config, &workspace, &greedy, None, None);
+        assert_eq!(flag_value(&args, "--memory"), "1g");
+        assert_eq!(flag_value(&args, "--cpus"), "2");
+
+        let unlimited = DockerSandboxConfig {
+            memory_limit: None,
+            cpus: None,
+            ..DockerSandboxConfig::default()
+        };
+        let uncapped = build_docker_args(&unlimited, &workspace, &greedy, None, None);
+        assert_eq!(flag_value(&uncapped, "--memory"), "4096m");
+        assert_eq!(flag_value(&uncapped, "--cpus"), "8");
+
+        let base = SandboxRequest::new("script.py", "print(1)");
+        for (cpus, memory) in [("fast", "256m"), ("-1", "256m"), ("1", "256mb"), ("1", "m")] {
+            assert!(
+                base.clone()
+                    .with_cpu_limit(cpus)
+                    .with_memory_limit(memory)
+                    .validate()
+                    .is_err(),
+                "accepted cpus={cpus} memory={memory}"
+            );
+        }
+    }
+
+    #[test]
+    fn request_env_keys_are_validated() {
+        let base = SandboxRequest::new("script.py", "print(1)");
+        base.clone()
+            .with_env("RANDOM_SEED", "42")
+            .validate()
+            .expect("plain keys are allowed");
+
+        for key in ["DEEPRESEARCH_MAX_SANDBOX_CONCURRENCY", "A=B", ""] {
+            assert!(
+                base.clone().with_env(key, "x").validate().is_err(),
+                "accepted env key {key:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn request_env_is_passed_after_config_env() {
+        let config = DockerSandboxConfig::default();
+        let workspace = PathBuf::from("/tmp/workspace");
+        let request = SandboxRequest::new("script.py", "print(1)").with_env("MPLBACKEND", "svg");
+        let args = build_docker_args(&config, &workspace, &request, None, None);
+
+        let config_env = args
+            .iter()
+            .position(|a| a == "MPLBACKEND=Agg")
+            .expect("config env");
+        let request_env = args
+            .iter()
+            .position(|a| a == "MPLBACKEND=svg")
+            .expect("request env");
+        assert!(
+            config_env < request_env,
+            "request env must override config env"
+        );
+    }
 }
@@ -0,0 +1,328 @@
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+use wasmtime::{Config, Engine, Linker, Module, Store, UpdateDeadline};
+use wasmtime_wasi::pipe::{MemoryInputPipe, MemoryOutputPipe};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{DirPerms, FilePerms, I32Exit, WasiCtxBuilder};
+
+use super::{
+    CollectedOutputs, SandboxExecutor, SandboxRequest, SandboxResult, SandboxRuntime,
+    WorkspaceGuard, acquire_sandbox_permit, collect_outputs, write_file,
+};
+
+const GUEST_WORKSPACE: &str = "/workspace";
+const GUEST_STDLIB: &str = "/usr/local/lib";
+const PIPE_CAPACITY: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct WasmSandboxConfig {
+    /// Pre-compiled WASI build of CPython (e.g. `python-3.12.0.wasm`).
+    pub python_module: PathBuf,
+    /// Host directory holding the Python stdlib, mounted read-only at `/usr/local/lib`.
+    /// Leave unset for runtimes that embed their stdlib.
+    pub stdlib_dir: Option<PathBuf>,
+    pub workspace_root: PathBuf,
+    pub env: Vec<(String, String)>,
+}
+
+impl WasmSandboxConfig {
+    pub fn new(python_module: impl Into<PathBuf>) -> Self {
+        Self {
+            python_module: python_module.into(),
+            stdlib_dir: None,
+            workspace_root: std::env::temp_dir().join("deepresearch_wasm_sandbox"),
+            env: vec![("MPLBACKEND".to_string(), "Agg".to_string())],
+        }
+    }
+
+    pub fn with_stdlib_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.stdlib_dir = Some(dir.into());
+        self
+    }
+}
+
+/// Runs sandbox scripts in a WASI CPython module via wasmtime, for hosts without a
+/// container runtime. Only the per-run workspace (and optional stdlib) is visible to
+/// the guest, and WASI exposes no network sockets.
+pub struct WasmSandboxRunner {
+    config: WasmSandboxConfig,
+    engine: Engine,
+    module: Module,
+}
+
+impl std::fmt::Debug for WasmSandboxRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmSandboxRunner")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WasmSandboxRunner {
+    pub fn new(config: WasmSandboxConfig) -> Result<Self> {
+        if let Some(dir) = &config.stdlib_dir
+            && !dir.is_dir()
+        {
+            return Err(anyhow!("stdlib directory {} does not exist", dir.display()));
+        }
+        std::fs::create_dir_all(&config.workspace_root).with_context(|| {
+            format!(
+                "failed to create workspace root {}",
+                config.workspace_root.display()
+            )
+        })?;
+
+        let mut engine_config = Config::new();
+        engine_config.epoch_interruption(true);
+        let engine = Engine::new(&engine_config).map_err(|err| anyhow!("{err:#}"))?;
+        let module = Module::from_file(&engine, &config.python_module).map_err(|err| {
+            anyhow!(
+                "failed to load WASI python module {}: {err:#}",
+                config.python_module.display()
+            )
+        })?;
+
+        Ok(Self {
+            config,
+            engine,
+            module,
+        })
+    }
+
+    #[tracing::instrument(skip(self, request), fields(script = %request.script_name))]
+    async fn execute_internal(&self, request: SandboxRequest) -> Result<SandboxResult> {
+        request.validate()?;
+        if request.runtime != SandboxRuntime::Python {
+            return Err(anyhow!(
+                "wasm sandbox only runs python scripts (requested {:?})",
+                request.runtime
+            ));
+        }
+
+        let workspace_dir = self.config.workspace_root.join(Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&workspace_dir).with_context(|| {
+            format!(
+                "failed to create sandbox workspace {}",
+                workspace_dir.display()
+            )
+        })?;
+        let guard = WorkspaceGuard::new(workspace_dir.clone());
+
+        if !request.reads_script_from_stdin() {
+            write_file(
+                &workspace_dir,
+                &request.script_name,
+                request.script_contents.as_bytes(),
+            )?;
+        }
+        for file in &request.files {
+            write_file(&workspace_dir, &file.path, &file.contents)?;
+        }
+
+        let mut argv = vec!["python".to_string()];
+        if request.reads_script_from_stdin() {
+            argv.push("-".to_string());
+        } else {
+            argv.push(format!("{GUEST_WORKSPACE}/{}", request.script_name));
+        }
+        argv.extend(request.args.iter().cloned());
+        debug!(?argv, "prepared wasm invocation");
+
+        let stdout = MemoryOutputPipe::new(PIPE_CAPACITY);
+        let stderr = MemoryOutputPipe::new(PIPE_CAPACITY);
+        let mut builder = WasiCtxBuilder::new();
+        builder
+            .args(&argv)
+            .envs(&self.config.env)
+            .envs(&request.env)
+            .stdin(MemoryInputPipe::new(
+                request.stdin.clone().unwrap_or_default(),
+            ))
+            .stdout(stdout.clone())
+            .stderr(stderr.clone())
+            .preopened_dir(
+                &workspace_dir,
+                GUEST_WORKSPACE,
+                DirPerms::all(),
+                FilePerms::all(),
+            )
+            .map_err(|err| anyhow!("failed to map sandbox workspace: {err:#}"))?;
+        if let Some(stdlib) = &self.config.stdlib_dir {
+            builder
+                .env("PYTHONHOME", "/usr/local")
+                .preopened_dir(stdlib, GUEST_STDLIB, DirPerms::READ, FilePerms::READ)
+                .map_err(|err| anyhow!("failed to map python stdlib: {err:#}"))?;
+        }
+        let wasi = builder.build_p1();
+
+        let engine = self.engine.clone();
+        let module = self.module.clone();
+        let start = Instant::now();
+        info!(
+            module = %self.config.python_module.display(),
+            workspace = %workspace_dir.display(),
+            "starting wasm sandbox execution"
+        );
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let guest_cancelled = cancelled.clone();
+        let mut run = tokio::task::spawn_blocking(move || {
+            run_module(&engine, &module, wasi, guest_cancelled)
+        });
+        let (timed_out, exit_code) = match tokio::time::timeout(request.timeout, &mut run).await {
+            Ok(joined) => (false, joined.context("wasm sandbox task panicked")??),
+            Err(_elapsed) => {
+                warn!("wasm sandbox execution timed out; interrupting guest");
+                // Bumping the shared epoch wakes every running guest; only this one sees its flag set.
+                cancelled.store(true, Ordering::SeqCst);
+                self.engine.increment_epoch();
+                let _ = run.await.context("wasm sandbox task panicked")?;
+                (true, None)
+            }
+        };
+        let duration = start.elapsed();
+
+        let CollectedOutputs {
+            outputs: collected_outputs,
+            overflow_warnings,
+            partial_output_errors,
+        } = collect_outputs(&workspace_dir, &request.expected_outputs);
+        drop(guard);
+
+        let status_label = if timed_out {
+            "timeout"
+        } else if exit_code == Some(0) {
+            "success"
+        } else {
+            "failure"
+        };
+        info!(
+            target: "telemetry.sandbox",
+            runtime = "wasm",
+            status = status_label,
+            exit_code,
+            timed_out,
+            duration_ms = duration.as_millis() as u64,
+            outputs = collected_outputs.len(),
+            "sandbox execution finished"
+        );
+        crate::record_sandbox_metrics(
+            status_label,
+            duration.as_millis() as u64,
+            collected_outputs.len(),
+            0,
+        );
+
+        Ok(SandboxResult {
+            exit_code,
+            stdout: String::from_utf8_lossy(&stdout.contents()).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr.contents()).into_owned(),
+            outputs: collected_outputs,
+            timed_out,
+            duration,
+            stderr_overflow_warning: if overflow_warnings.is_empty() {
+                None
+            } else {
+                Some(overflow_warnings.join("; "))
+            },
+            // WASI guests have no container to sample.
+            resource_usage: None,
+            partial_output_errors,
+        })
+    }
+
+    pub async fn execute(&self, request: SandboxRequest) -> Result<SandboxResult> {
+        let _permit = acquire_sandbox_permit().await?;
+        self.execute_internal(request).await
+    }
+}
+
+#[async_trait]
+impl SandboxExecutor for WasmSandboxRunner {
+    async fn execute(&self, request: SandboxRequest) -> Result<SandboxResult> {
+        WasmSandboxRunner::execute(self, request).await
+    }
+}
+
+/// Instantiate the module and run `_start`, mapping `proc_exit` to an exit code.
+/// Returns `Ok(None)` when the guest was interrupted after `cancelled` was set.
+fn run_module(
+    engine: &Engine,
+    module: &Module,
+    wasi: WasiP1Ctx,
+    cancelled: Arc<AtomicBool>,
+) -> Result<Option<i32>> {
+    let mut linker: Linker<WasiP1Ctx> = Linker::new(engine);
+    preview1::add_to_linker_sync(&mut linker, |ctx| ctx)
+        .map_err(|err| anyhow!("failed to link WASI imports: {err:#}"))?;
+
+    let mut store = Store::new(engine, wasi);
+    store.set_epoch_deadline(1);
+    store.epoch_deadline_callback(move |_| {
+        if cancelled.load(Ordering::SeqCst) {
+            Err(wasmtime::Trap::Interrupt.into())
+        } else {
+            Ok(UpdateDeadline::Continue(1))
+        }
+    });
+
+    let instance = linker
+        .instantiate(&mut store, module)
+        .map_err(|err| anyhow!("failed to instantiate WASI python module: {err:#}"))?;
+    let start = instance
+        .get_typed_func::<(), ()>(&mut store, "_start")
+        .map_err(|err| anyhow!("WASI python module has no _start export: {err:#}"))?;
+
+    match start.call(&mut store, ()) {
+        Ok(()) => Ok(Some(0)),
+        Err(err) => {
+            if let Some(exit) = err.downcast_ref::<I32Exit>() {
+                Ok(Some(exit.0))
+            } else if matches!(
+                err.downcast_ref::<wasmtime::Trap>(),
+                Some(wasmtime::Trap::Interrupt)
+            ) {
+                Ok(None)
+            } else {
+                warn!(error = %format!("{err:#}"), "wasm guest trapped");
+                Ok(Some(1))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_python_module_is_rejected() {
+        let temp = tempfile::TempDir::new().expect("temp dir");
+        let mut config = WasmSandboxConfig::new(temp.path().join("missing-python.wasm"));
+        config.workspace_root = temp.path().join("workspaces");
+
+        let err = WasmSandboxRunner::new(config).expect_err("module must exist");
+        assert!(err.to_string().contains("missing-python.wasm"));
+    }
+
+    #[tokio::test]
+    async fn runs_python_when_module_is_available() {
+        // Point DEEPRESEARCH_WASM_PYTHON at a WASI CPython build to exercise the runtime.
+        let Ok(module) = std::env::var("DEEPRESEARCH_WASM_PYTHON") else {
+            return;
+        };
+        let runner = WasmSandboxRunner::new(WasmSandboxConfig::new(module)).expect("runner");
+        let result = runner
+            .execute(SandboxRequest::new("hello.py", "print(6 * 7)"))
+            .await
+            .expect("execution");
+        assert_eq!(result.exit_code, Some(0));
+        assert_eq!(result.stdout.trim(), "42");
+    }
+}
@@ -1,6 +1,10 @@
 use async_trait::async_trait;
 use graph_flow::{Context, NextAction, Task, TaskResult};
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use tokio::time::{Duration, sleep};
 use tracing::{debug, info, instrument, warn};
@@ -8,15 +12,20 @@ use tracing::{debug, info, instrument, warn};
 use crate::memory::{DynRetriever, RetrievedDocument};
 use crate::sandbox::{
     SandboxExecutor, SandboxFile, SandboxOutputKind, SandboxOutputSpec, SandboxRequest,
-    SandboxResult,
+    SandboxResult, SandboxRuntime,
+};
+use crate::trace::{
+    TRACE_TAG_CONFIDENCE_SCORE, TRACE_TAG_SANDBOX_EXIT_CODE, TRACE_TAG_VERDICT, TraceCollector,
+    TraceEvent,
 };
-use crate::trace::TraceCollector;
 
 #[derive(Debug, Clone)]
 pub struct FactCheckSettings {
     pub min_confidence: f32,
     pub verification_count: usize,
     pub timeout_ms: u64,
+    /// Hostnames (lowercase, without `www.`) whose sources are never counted as verified.
+    pub source_blocklist: HashSet<String>,
 }
 
 impl Default for FactCheckSettings {
@@ -25,8 +34,75 @@ impl Default for FactCheckSettings {
             min_confidence: 0.6,
             verification_count: 3,
             timeout_ms: 120,
+            source_blocklist: HashSet::new(),
+        }
+    }
+}
+
+const FC_MIN_CONFIDENCE_ENV: &str = "DEEPRESEARCH_FC_MIN_CONFIDENCE";
+const FC_VERIFICATION_COUNT_ENV: &str = "DEEPRESEARCH_FC_VERIFICATION_COUNT";
+const FC_TIMEOUT_MS_ENV: &str = "DEEPRESEARCH_FC_TIMEOUT_MS";
+const FC_BLOCKLIST_ENV: &str = "DEEPRESEARCH_FC_BLOCKLIST";
+
+impl FactCheckSettings {
+    /// Defaults overridden by `DEEPRESEARCH_FC_*` variables; unset or unparsable values keep the default.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Like [`FactCheckSettings::from_env`], but reads overrides through `lookup`.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        Self {
+            min_confidence: parse_override(&lookup, FC_MIN_CONFIDENCE_ENV, defaults.min_confidence),
+            verification_count: parse_override(
+                &lookup,
+                FC_VERIFICATION_COUNT_ENV,
+                defaults.verification_count,
+            ),
+            timeout_ms: parse_override(&lookup, FC_TIMEOUT_MS_ENV, defaults.timeout_ms),
+            source_blocklist: lookup(FC_BLOCKLIST_ENV)
+                .map(|raw| raw.split(',').filter_map(normalize_host).collect())
+                .unwrap_or_default(),
         }
     }
+
+    /// Whether `source`'s hostname is on the blocklist.
+    pub fn is_blocked(&self, source: &str) -> bool {
+        !self.source_blocklist.is_empty()
+            && normalize_host(source_host(source))
+                .is_some_and(|host| self.source_blocklist.contains(&host))
+    }
+}
+
+fn normalize_host(host: &str) -> Option<String> {
+    let host = host.trim().to_lowercase();
+    let host = host.trim_start_matches("www.");
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+/// Hostname of a source URL (without scheme, credentials, port, or path).
+fn source_host(source: &str) -> &str {
+    let trimmed = source.trim();
+    let rest = trimmed.split_once("://").map_or(trimmed, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    host.split(':').next().unwrap_or_default()
+}
+
+fn parse_override<T>(lookup: &impl Fn(&str) -> Option<String>, key: &str, default: T) -> T
+where
+    T: std::str::FromStr,
+{
+    match lookup(key) {
+        Some(raw) => raw.trim().parse().unwrap_or_else(|_| {
+            warn!(key, value = %raw, "ignoring unparsable fact-check override");
+            default
+        }),
+        None => default,
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -43,6 +119,53 @@ pub struct MathToolRequest {
     pub expected_outputs: Vec<SandboxOutputSpec>,
     #[serde(default)]
     pub timeout_ms: Option<u64>,
+    /// Piped to the script's stdin; base64-encoded in JSON.
+    #[serde(
+        default,
+        with = "base64_bytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub stdin_bytes: Option<Vec<u8>>,
+    /// Per-run Docker CPU/memory overrides for this script.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_limits: Option<MathResourceLimits>,
+    /// Interpreter for `script` (`"python"`, `"bash"`, `"node"`, `"r"`, or `{"custom": "<binary>"}`).
+    #[serde(default)]
+    pub runtime: SandboxRuntime,
+}
+
+/// Request-level overrides of the sandbox's `--cpus` / `--memory` limits.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct MathResourceLimits {
+    /// Fractional CPU count, e.g. `"0.5"`.
+    #[serde(default)]
+    pub cpu_limit: Option<String>,
+    /// Docker memory size, e.g. `"256m"`.
+    #[serde(default)]
+    pub memory_limit: Option<String>,
+}
+
+mod base64_bytes {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(bytes) => serializer.serialize_some(&STANDARD.encode(bytes)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|encoded| STANDARD.decode(encoded.trim()).map_err(D::Error::custom))
+            .transpose()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +207,9 @@ pub struct MathToolResult {
     pub stdout: String,
     pub stderr: String,
     pub outputs: Vec<MathToolOutput>,
+    /// `(path, error)` for expected outputs the sandbox could not collect.
+    #[serde(default)]
+    pub outputs_missing: Vec<(String, String)>,
 }
 
 impl Default for MathToolResult {
@@ -96,6 +222,7 @@ impl Default for MathToolResult {
             stdout: String::new(),
             stderr: String::new(),
             outputs: Vec::new(),
+            outputs_missing: Vec::new(),
         }
     }
 }
@@ -127,8 +254,13 @@ impl MathToolResult {
             timed_out: result.timed_out,
             duration_ms,
             stdout: result.stdout,
-            stderr: result.stderr,
+            stderr: match result.stderr_overflow_warning {
+                Some(warning) if result.stderr.is_empty() => warning,
+                Some(warning) => format!("{}\n{warning}", result.stderr),
+                None => result.stderr,
+            },
             outputs,
+            outputs_missing: result.partial_output_errors,
         }
     }
 }
@@ -146,6 +278,9 @@ async fn persist_math_result(
     context.set("math.timed_out", result.timed_out).await;
     context.set("math.duration_ms", result.duration_ms).await;
     context.set("math.outputs", &result.outputs).await;
+    context
+        .set("math.partial_output_errors", &result.outputs_missing)
+        .await;
     if let Some(name) = script_name {
         context.set("math.script_name", name.to_string()).await;
     }
@@ -173,13 +308,55 @@ async fn persist_math_result(
         .await;
 }
 
-async fn record_trace(context: &Context, task_id: &str, message: impl Into<String>) {
+pub(crate) async fn record_trace(context: &Context, task_id: &str, message: impl Into<String>) {
+    push_trace(context, task_id, message.into(), None, HashMap::new()).await;
+}
+
+/// Like [`record_trace`], but stamps the event with the task's measured wall-clock time.
+pub(crate) async fn record_timed_trace(
+    context: &Context,
+    task_id: &str,
+    message: impl Into<String>,
+    elapsed: std::time::Duration,
+) {
+    push_trace(
+        context,
+        task_id,
+        message.into(),
+        Some(elapsed),
+        HashMap::new(),
+    )
+    .await;
+}
+
+/// Like [`record_timed_trace`], but attaches standard tags (see `TRACE_TAG_*`) to the event.
+pub(crate) async fn record_tagged_trace(
+    context: &Context,
+    task_id: &str,
+    message: impl Into<String>,
+    elapsed: std::time::Duration,
+    tags: HashMap<String, Value>,
+) {
+    push_trace(context, task_id, message.into(), Some(elapsed), tags).await;
+}
+
+async fn push_trace(
+    context: &Context,
+    task_id: &str,
+    message: String,
+    elapsed: Option<std::time::Duration>,
+    tags: HashMap<String, Value>,
+) {
     if !context.get::<bool>("trace.enabled").await.unwrap_or(false) {
         return;
     }
 
     let mut collector: TraceCollector = context.get("trace.collector").await.unwrap_or_default();
-    collector.record(task_id, message);
+    let event = TraceEvent::new(task_id, message).with_tags(tags);
+    collector.extend([match elapsed {
+        Some(elapsed) => event.with_duration(elapsed),
+        None => event,
+    }]);
     context.set("trace.collector", &collector).await;
 }
 
@@ -191,40 +368,199 @@ fn default_sources() -> Vec<String> {
     ]
 }
 
+static MARKDOWN_LINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\[([^\]]+)\]\((https?://[^\s)]+)\)").expect("invalid markdown link regex")
+});
+
+static INLINE_URL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"https?://[^\s<>()\[\]]+").expect("invalid inline url regex"));
+
+/// Structured citation extracted from research output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceReference {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Retrieval score of the document the source came from, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+}
+
+/// Extract citations from markdown links (`[text](url)`) and bare URLs, deduplicated by URL.
+pub fn extract_citations(text: &str) -> Vec<SourceReference> {
+    let mut citations: Vec<SourceReference> = Vec::new();
+    let mut link_spans = Vec::new();
+
+    for captures in MARKDOWN_LINK.captures_iter(text) {
+        if let Some(span) = captures.get(0) {
+            link_spans.push(span.range());
+        }
+        push_citation(
+            &mut citations,
+            &captures[2],
+            Some(captures[1].trim().to_string()),
+        );
+    }
+
+    for found in INLINE_URL.find_iter(text) {
+        if link_spans.iter().any(|span| span.contains(&found.start())) {
+            continue;
+        }
+        let url = found
+            .as_str()
+            .trim_end_matches(['.', ',', ';', ':', '!', '?', '"', '\'']);
+        push_citation(&mut citations, url, None);
+    }
+
+    citations
+}
+
+fn push_citation(citations: &mut Vec<SourceReference>, url: &str, title: Option<String>) {
+    if url.is_empty() || citations.iter().any(|existing| existing.url == url) {
+        return;
+    }
+    citations.push(SourceReference {
+        url: url.to_string(),
+        title: title.filter(|title| !title.is_empty()),
+        score: None,
+    });
+}
+
+/// Retry policy applied to retriever calls before falling back to placeholder findings.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    pub max_attempts: usize,
+    pub initial_backoff_ms: u64,
+    pub backoff_factor: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 100,
+            backoff_factor: 2.0,
+        }
+    }
+}
+
+impl RetryConfig {
+    fn backoff_for(&self, retry: usize) -> Duration {
+        let factor = self.backoff_factor.max(1.0).powi(retry as i32);
+        Duration::from_millis((self.initial_backoff_ms as f64 * factor).round() as u64)
+    }
+}
+
+struct RetrievalOutcome {
+    documents: Vec<RetrievedDocument>,
+    retry_count: usize,
+    degraded: bool,
+}
+
+const RESEARCHER_ID: &str = "researcher";
+
+/// Re-ranks (or filters) retrieved documents before the researcher persists them.
+pub type ResultScorer = Arc<dyn Fn(Vec<RetrievedDocument>) -> Vec<RetrievedDocument> + Send + Sync>;
+
 pub struct ResearchTask {
+    id: String,
     retriever: DynRetriever,
+    retry: RetryConfig,
+    merge_existing: bool,
+    scorer: Option<ResultScorer>,
+    max_tokens_per_finding: Option<usize>,
 }
 
 impl ResearchTask {
     pub fn new(retriever: DynRetriever) -> Self {
-        Self { retriever }
+        Self {
+            id: RESEARCHER_ID.to_string(),
+            retriever,
+            retry: RetryConfig::default(),
+            merge_existing: false,
+            scorer: None,
+            max_tokens_per_finding: None,
+        }
     }
 
-    async fn run_retrieval(&self, session_id: &str, query: &str) -> Vec<RetrievedDocument> {
-        match self.retriever.retrieve(session_id, query, 5).await {
-            Ok(results) => {
-                if results
-                    .iter()
-                    .all(|doc| doc.score <= 0.0 || doc.text.trim().is_empty())
-                {
-                    vec![RetrievedDocument {
-                        text:
-                            "Automated placeholder insight. Additional manual review recommended."
+    /// An additional researcher (registered under `id`) that appends its findings and
+    /// sources to those already gathered instead of replacing them.
+    pub fn supplementary(id: impl Into<String>, retriever: DynRetriever) -> Self {
+        Self {
+            id: id.into(),
+            merge_existing: true,
+            ..Self::new(retriever)
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Reorder retrieved documents with `scorer` before findings and sources are stored.
+    pub fn with_result_scorer(mut self, scorer: ResultScorer) -> Self {
+        self.scorer = Some(scorer);
+        self
+    }
+
+    /// Cut each retrieved finding to at most `limit` whitespace-separated words.
+    pub fn with_max_tokens_per_finding(mut self, limit: usize) -> Self {
+        self.max_tokens_per_finding = Some(limit);
+        self
+    }
+
+    async fn run_retrieval(&self, session_id: &str, query: &str) -> RetrievalOutcome {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            match self.retriever.retrieve(session_id, query, 5).await {
+                Ok(results) => {
+                    let documents = if results
+                        .iter()
+                        .all(|doc| doc.score <= 0.0 || doc.text.trim().is_empty())
+                    {
+                        vec![RetrievedDocument {
+                            text: "Automated placeholder insight. Additional manual review recommended."
                                 .to_string(),
-                        score: 0.1,
-                        source: Some("stub://memory".to_string()),
-                    }]
-                } else {
-                    results
+                            score: 0.1,
+                            source: Some("stub://memory".to_string()),
+                        }]
+                    } else {
+                        results
+                    };
+                    return RetrievalOutcome {
+                        documents,
+                        retry_count: attempt - 1,
+                        degraded: false,
+                    };
+                }
+                Err(err) if attempt < max_attempts => {
+                    let backoff = self.retry.backoff_for(attempt - 1);
+                    warn!(
+                        %session_id,
+                        attempt,
+                        max_attempts,
+                        backoff_ms = backoff.as_millis() as u64,
+                        error = %err,
+                        "retriever failed; retrying"
+                    );
+                    sleep(backoff).await;
+                }
+                Err(err) => {
+                    warn!(%session_id, %query, attempt, error = %err, "retriever failed; using placeholder");
+                    return RetrievalOutcome {
+                        documents: vec![RetrievedDocument {
+                            text: format!("Unable to query memory for '{query}'"),
+                            score: 0.0,
+                            source: Some("stub://error".to_string()),
+                        }],
+                        retry_count: attempt - 1,
+                        degraded: true,
+                    };
                 }
-            }
-            Err(err) => {
-                warn!(%session_id, %query, error = %err, "retriever failed; using placeholder");
-                vec![RetrievedDocument {
-                    text: format!("Unable to query memory for '{query}'"),
-                    score: 0.0,
-                    source: Some("stub://error".to_string()),
-                }]
             }
         }
     }
@@ -233,11 +569,12 @@ impl ResearchTask {
 #[async_trait]
 impl Task for ResearchTask {
     fn id(&self) -> &str {
-        "researcher"
+        &self.id
     }
 
-    #[instrument(name = "task.research", skip(self, context))]
+    #[instrument(name = "task.researcher", skip(self, context))]
     async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
+        let started = std::time::Instant::now();
         let query: String = context
             .get("query")
             .await
@@ -252,33 +589,130 @@ impl Task for ResearchTask {
         // Simulate latency when external systems are slow
         sleep(Duration::from_millis(150)).await;
 
-        let documents = self.run_retrieval(&session_id, &query).await;
+        let RetrievalOutcome {
+            mut documents,
+            retry_count,
+            degraded,
+        } = self.run_retrieval(&session_id, &query).await;
+        if let Some(scorer) = &self.scorer {
+            documents = scorer(documents);
+        }
+        context.set("research.retry_count", retry_count).await;
+        context.set("research.retrieval_degraded", degraded).await;
+        context
+            .set("research.scorer_applied", self.scorer.is_some())
+            .await;
 
-        let findings: Vec<String> = documents.iter().map(|doc| doc.text.clone()).collect();
-        let sources: Vec<String> = documents
-            .iter()
-            .filter_map(|doc| doc.source.clone())
-            .collect();
+        let mut findings: Vec<String> = Vec::new();
+        let mut sources: Vec<String> = Vec::new();
+        let mut source_references: Vec<SourceReference> = Vec::new();
+        let mut finding_sources: Vec<Option<usize>> = Vec::new();
+        if self.merge_existing {
+            findings = context.get("research.findings").await.unwrap_or_default();
+            sources = context.get("research.sources").await.unwrap_or_default();
+            source_references = context
+                .get("research.source_references")
+                .await
+                .unwrap_or_default();
+            finding_sources = context
+                .get("research.finding_sources")
+                .await
+                .unwrap_or_default();
+            finding_sources.resize(findings.len(), None);
+        }
+        let mut truncated = false;
+        findings.extend(documents.iter().map(|doc| {
+            match self
+                .max_tokens_per_finding
+                .and_then(|limit| truncate_words(&doc.text, limit))
+            {
+                Some(cut) => {
+                    truncated = true;
+                    cut.to_string()
+                }
+                None => doc.text.clone(),
+            }
+        }));
+        if self.merge_existing {
+            truncated |= context
+                .get::<bool>("research.findings_truncated")
+                .await
+                .unwrap_or(false);
+        }
+        context.set("research.findings_truncated", truncated).await;
+        // Each finding remembers which entry of `sources` its document came from.
+        for doc in &documents {
+            let Some(source) = &doc.source else {
+                finding_sources.push(None);
+                continue;
+            };
+            let known = self
+                .merge_existing
+                .then(|| sources.iter().position(|existing| existing == source))
+                .flatten();
+            let index = known.unwrap_or_else(|| {
+                sources.push(source.clone());
+                sources.len() - 1
+            });
+            finding_sources.push(Some(index));
+        }
+        for doc in &documents {
+            let Some(source) = &doc.source else {
+                continue;
+            };
+            // Markdown-link sources carry a title; plain URLs are kept verbatim.
+            let (url, title) = match extract_citations(source).into_iter().next() {
+                Some(citation) if source.starts_with('[') => (citation.url, citation.title),
+                _ => (source.clone(), None),
+            };
+            if source_references.iter().all(|existing| existing.url != url) {
+                source_references.push(SourceReference {
+                    url,
+                    title,
+                    score: Some(doc.score),
+                });
+            }
+        }
 
         context.set("research.findings", &findings).await;
         context.set("research.sources", &sources).await;
+        context
+            .set("research.finding_sources", &finding_sources)
+            .await;
+        context
+            .set("research.source_references", &source_references)
+            .await;
+
+        let mut citations = Vec::new();
+        for text in findings.iter().chain(sources.iter()) {
+            for citation in extract_citations(text) {
+                push_citation(&mut citations, &citation.url, citation.title);
+            }
+        }
+        context.set("research.citations", &citations).await;
 
         debug!(
             findings_count = findings.len(),
             sources_count = sources.len(),
+            citations_count = citations.len(),
             "research task populated context"
         );
 
-        record_trace(
-            &context,
-            self.id(),
-            format!(
-                "captured {} findings ({} sources)",
-                findings.len(),
-                sources.len()
-            ),
-        )
-        .await;
+        let mut trace_message = format!(
+            "captured {} findings ({} sources)",
+            findings.len(),
+            sources.len()
+        );
+        if retry_count > 0 {
+            trace_message.push_str(&format!(" after {retry_count} retries"));
+        }
+        if degraded {
+            trace_message.push_str("; retrieval degraded");
+        }
+        if context.get::<bool>("research.preseeded").await == Some(true) {
+            trace_message.push_str("; retrieval bypassed (preseeded)");
+        }
+        record_timed_trace(&context, self.id(), trace_message, started.elapsed()).await;
 
         Ok(TaskResult::new(
             Some(format!("Research completed for \"{}\"", query)),
@@ -291,6 +725,50 @@ pub struct FactCheckTask {
     settings: FactCheckSettings,
 }
 
+/// `text` cut after its `limit`-th word, or `None` when it has no more words than that.
+fn truncate_words(text: &str, limit: usize) -> Option<&str> {
+    let mut words = 0;
+    let mut in_word = false;
+    for (idx, ch) in text.char_indices() {
+        if ch.is_whitespace() {
+            in_word = false;
+        } else if !in_word {
+            in_word = true;
+            words += 1;
+            if words > limit {
+                return Some(text[..idx].trim_end());
+            }
+        }
+    }
+    None
+}
+
+/// Drop sources that point at the same document, keeping the first occurrence.
+/// URLs compare by host and path (case-insensitive host, query string, fragment and
+/// trailing slash ignored); other entries compare trimmed and case-insensitively.
+pub fn deduplicate_sources(sources: &[String]) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    sources
+        .iter()
+        .filter(|source| seen.insert(source_key(source)))
+        .cloned()
+        .collect()
+}
+
+fn source_key(source: &str) -> String {
+    let trimmed = source.trim();
+    let Some((_, rest)) = trimmed.split_once("://") else {
+        return trimmed.to_lowercase();
+    };
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    format!(
+        "{}/{}",
+        host.to_lowercase().trim_start_matches("www."),
+        path.trim_end_matches('/')
+    )
+}
+
 impl FactCheckTask {
     pub fn new(settings: FactCheckSettings) -> Self {
         Self { settings }
@@ -305,18 +783,29 @@ impl Task for FactCheckTask {
 
     #[instrument(name = "task.fact_check", skip(self, context))]
     async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
+        let started = std::time::Instant::now();
         let analysis: AnalystOutput = context
             .get("analysis.output")
             .await
             .unwrap_or_else(AnalystOutput::default);
-        let sources = analysis.sources.clone();
+        let sources = deduplicate_sources(&analysis.sources);
+        context
+            .set("factcheck.deduped_sources_count", sources.len())
+            .await;
 
         if self.settings.timeout_ms > 0 {
             sleep(Duration::from_millis(self.settings.timeout_ms.min(500))).await;
         }
 
-        let verified_sources: Vec<String> = sources
+        let (blocked, candidates): (Vec<&String>, Vec<&String>) = sources
             .iter()
+            .partition(|source| self.settings.is_blocked(source));
+        context
+            .set("factcheck.blocked_sources_count", blocked.len())
+            .await;
+
+        let verified_sources: Vec<String> = candidates
+            .into_iter()
             .take(self.settings.verification_count)
             .cloned()
             .collect();
@@ -352,7 +841,7 @@ impl Task for FactCheckTask {
             "fact-check task completed"
         );
 
-        record_trace(
+        record_tagged_trace(
             &context,
             self.id(),
             format!(
@@ -360,6 +849,11 @@ impl Task for FactCheckTask {
                 confidence,
                 verified_sources.len()
             ),
+            started.elapsed(),
+            HashMap::from([(
+                TRACE_TAG_CONFIDENCE_SCORE.to_string(),
+                Value::from(confidence),
+            )]),
         )
         .await;
 
@@ -370,16 +864,212 @@ impl Task for FactCheckTask {
     }
 }
 
+/// Formats the analyst summary from `(findings, sources)`.
+pub type SummaryTemplate = Arc<dyn Fn(&[String], &[String]) -> String + Send + Sync>;
+
+/// How the analyst cites `research.sources` in `analysis.output.summary_with_citations`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CitationStyle {
+    /// Sources stay a plain list next to the summary; no cited summary is produced.
+    #[default]
+    Inline,
+    /// `[n]` after each finding plus a `References:` block of numbered URLs.
+    Footnote,
+    /// `[n]` after each finding plus a `Notes:` block of Chicago-style notes
+    /// (`"Title," URL.` when the source has a title).
+    Chicago,
+}
+
 #[derive(Default)]
-pub struct AnalystTask;
+pub struct AnalystTask {
+    template: Option<SummaryTemplate>,
+    citation_style: CitationStyle,
+}
+
+impl AnalystTask {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the generic "Top insights" summary with a domain-specific format.
+    pub fn with_summary_template(mut self, template: SummaryTemplate) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    /// Also render a cited copy of the summary; the plain `summary` is left unchanged.
+    pub fn with_citation_style(mut self, style: CitationStyle) -> Self {
+        self.citation_style = style;
+        self
+    }
+
+    fn summarize(&self, findings: &[String], sources: &[String]) -> String {
+        if findings.is_empty() {
+            "No findings available; analyst requires additional research input".to_string()
+        } else if let Some(template) = &self.template {
+            template(findings, sources)
+        } else {
+            format!(
+                "Top insights: {}. Confidence supported by {} sources.",
+                findings.join("; "),
+                sources.len()
+            )
+        }
+    }
+
+    /// Summary with `[n]` markers and a reference block; `None` for [`CitationStyle::Inline`].
+    ///
+    /// `finding_sources[i]` is the index into `sources` cited by finding `i`; findings
+    /// without one are left unmarked.
+    fn cite(
+        &self,
+        findings: &[String],
+        finding_sources: &[Option<usize>],
+        sources: &[String],
+        references: &[SourceReference],
+    ) -> Option<String> {
+        let heading = match self.citation_style {
+            CitationStyle::Inline => return None,
+            CitationStyle::Footnote => "References",
+            CitationStyle::Chicago => "Notes",
+        };
+        let marked: Vec<String> = findings
+            .iter()
+            .enumerate()
+            .map(|(idx, finding)| {
+                match finding_sources
+                    .get(idx)
+                    .copied()
+                    .flatten()
+                    .filter(|source| *source < sources.len())
+                {
+                    Some(source) => format!("{finding} [{}]", source + 1),
+                    None => finding.clone(),
+                }
+            })
+            .collect();
+        let mut cited = self.summarize(&marked, sources);
+        if sources.is_empty() {
+            return Some(cited);
+        }
+
+        cited.push_str(&format!("\n\n{heading}:"));
+        for (idx, source) in sources.iter().enumerate() {
+            let entry = match self.citation_style {
+                CitationStyle::Chicago => {
+                    let reference = references.iter().find(|reference| {
+                        reference.url == *source || source.contains(&reference.url)
+                    });
+                    match reference {
+                        Some(SourceReference {
+                            url,
+                            title: Some(title),
+                            ..
+                        }) => format!("\"{title},\" {url}."),
+                        Some(reference) => format!("{}.", reference.url),
+                        None => format!("{source}."),
+                    }
+                }
+                _ => source.clone(),
+            };
+            cited.push_str(&format!("\n{}. {entry}", idx + 1));
+        }
+        Some(cited)
+    }
+}
+
+/// Hook invoked with the previous script and the 1-based retry number; returns the script to run next.
+pub type ScriptMutator = Arc<dyn Fn(&str, usize) -> String + Send + Sync>;
+
+/// Retry budget applied when the sandbox reports `MathToolStatus::Failure`.
+#[derive(Clone, Default)]
+pub struct MathRetryPolicy {
+    pub max_retries: usize,
+    pub mutate_script: Option<ScriptMutator>,
+}
+
+impl MathRetryPolicy {
+    pub fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            mutate_script: None,
+        }
+    }
+
+    pub fn with_mutator<F>(mut self, mutate: F) -> Self
+    where
+        F: Fn(&str, usize) -> String + Send + Sync + 'static,
+    {
+        self.mutate_script = Some(Arc::new(mutate));
+        self
+    }
+
+    fn next_script(&self, script: &str, retry: usize) -> String {
+        match &self.mutate_script {
+            Some(mutate) => mutate(script, retry),
+            None => script.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Debug for MathRetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MathRetryPolicy")
+            .field("max_retries", &self.max_retries)
+            .field("mutate_script", &self.mutate_script.is_some())
+            .finish()
+    }
+}
 
 pub struct MathToolTask {
     runner: Arc<dyn SandboxExecutor>,
+    retry: MathRetryPolicy,
 }
 
 impl MathToolTask {
     pub fn new(runner: Arc<dyn SandboxExecutor>) -> Self {
-        Self { runner }
+        Self {
+            runner,
+            retry: MathRetryPolicy::default(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: MathRetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    async fn execute_script(
+        &self,
+        request: &MathToolRequest,
+        script_name: &str,
+        script: &str,
+    ) -> MathToolResult {
+        let mut sandbox_request = SandboxRequest::new(script_name.to_string(), script.to_string());
+        sandbox_request.args = request.args.clone();
+        sandbox_request.files = request.files.clone();
+        sandbox_request.expected_outputs = request.expected_outputs.clone();
+        sandbox_request.stdin = request.stdin_bytes.clone();
+        sandbox_request.runtime = request.runtime.clone();
+        if let Some(limits) = &request.resource_limits {
+            sandbox_request.cpu_limit = limits.cpu_limit.clone();
+            sandbox_request.memory_limit = limits.memory_limit.clone();
+        }
+        if let Some(timeout_ms) = request.timeout_ms {
+            sandbox_request.timeout = Duration::from_millis(timeout_ms);
+        }
+
+        match self.runner.execute(sandbox_request).await {
+            Ok(sandbox_result) => MathToolResult::from_sandbox(sandbox_result),
+            Err(err) => {
+                warn!(error = %err, "math sandbox execution failed");
+                MathToolResult {
+                    status: MathToolStatus::Failure,
+                    stderr: err.to_string(),
+                    ..MathToolResult::default()
+                }
+            }
+        }
     }
 }
 
@@ -391,12 +1081,19 @@ impl Task for MathToolTask {
 
     #[instrument(name = "task.math_tool", skip(self, context))]
     async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
+        let started = std::time::Instant::now();
         let mut result = MathToolResult::default();
         let request = context.get::<MathToolRequest>("math.request").await;
 
         let Some(request) = request else {
             persist_math_result(&context, &result, None).await;
-            record_trace(&context, self.id(), "skipped (no request)").await;
+            record_timed_trace(
+                &context,
+                self.id(),
+                "skipped (no request)",
+                started.elapsed(),
+            )
+            .await;
             return Ok(TaskResult::new(
                 Some("Math tool skipped (no request)".to_string()),
                 NextAction::ContinueAndExecute,
@@ -405,7 +1102,13 @@ impl Task for MathToolTask {
 
         if request.script.trim().is_empty() {
             persist_math_result(&context, &result, request.script_name.as_deref()).await;
-            record_trace(&context, self.id(), "skipped (empty script)").await;
+            record_timed_trace(
+                &context,
+                self.id(),
+                "skipped (empty script)",
+                started.elapsed(),
+            )
+            .await;
             return Ok(TaskResult::new(
                 Some("Math tool skipped (empty script)".to_string()),
                 NextAction::ContinueAndExecute,
@@ -417,35 +1120,38 @@ impl Task for MathToolTask {
             .clone()
             .unwrap_or_else(|| "math_tool.py".to_string());
 
-        let mut sandbox_request = SandboxRequest::new(script_name.clone(), request.script.clone());
-        sandbox_request.args = request.args.clone();
-        sandbox_request.files = request.files.clone();
-        sandbox_request.expected_outputs = request.expected_outputs.clone();
-        if let Some(timeout_ms) = request.timeout_ms {
-            sandbox_request.timeout = Duration::from_millis(timeout_ms);
+        let mut script = request.script.clone();
+        let mut retry_attempts = 0;
+        result = self.execute_script(&request, &script_name, &script).await;
+        while result.status == MathToolStatus::Failure && retry_attempts < self.retry.max_retries {
+            retry_attempts += 1;
+            script = self.retry.next_script(&script, retry_attempts);
+            debug!(
+                retry = retry_attempts,
+                "retrying math sandbox after failure"
+            );
+            result = self.execute_script(&request, &script_name, &script).await;
         }
 
-        result = match self.runner.execute(sandbox_request).await {
-            Ok(sandbox_result) => MathToolResult::from_sandbox(sandbox_result),
-            Err(err) => {
-                warn!(error = %err, "math sandbox execution failed");
-                MathToolResult {
-                    status: MathToolStatus::Failure,
-                    stderr: err.to_string(),
-                    ..MathToolResult::default()
-                }
-            }
-        };
-
         persist_math_result(&context, &result, Some(&script_name)).await;
+        context.set("math.retry_attempts", retry_attempts).await;
+        context.set("math.final_script", script).await;
 
         let trace_message = format!(
-            "{} (outputs {}, exit {:?})",
+            "{} (outputs {}, exit {:?}, retries {})",
             result.status,
             result.outputs.len(),
-            result.exit_code
+            result.exit_code,
+            retry_attempts
         );
-        record_trace(&context, self.id(), trace_message).await;
+        let mut tags = HashMap::new();
+        if let Some(exit_code) = result.exit_code {
+            tags.insert(
+                TRACE_TAG_SANDBOX_EXIT_CODE.to_string(),
+                Value::from(exit_code),
+            );
+        }
+        record_tagged_trace(&context, self.id(), trace_message, started.elapsed(), tags).await;
 
         let message = match result.status {
             MathToolStatus::Success => "Math tool completed successfully",
@@ -469,11 +1175,26 @@ impl Task for AnalystTask {
 
     #[instrument(name = "task.analyst", skip(self, context))]
     async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
+        let started = std::time::Instant::now();
         let findings: Vec<String> = context.get("research.findings").await.unwrap_or_default();
         let sources: Vec<String> = context
             .get("research.sources")
             .await
             .unwrap_or_else(default_sources);
+        let source_references: Vec<SourceReference> = context
+            .get("research.source_references")
+            .await
+            .unwrap_or_default();
+        // Sessions recorded before `research.finding_sources` existed paired findings and
+        // sources by position.
+        let finding_sources: Vec<Option<usize>> = context
+            .get("research.finding_sources")
+            .await
+            .unwrap_or_else(|| {
+                (0..findings.len())
+                    .map(|idx| (idx < sources.len()).then_some(idx))
+                    .collect()
+            });
         let degradation_note: String = context
             .get("math.degradation_note")
             .await
@@ -488,24 +1209,29 @@ impl Task for AnalystTask {
             "analyst synthesizing results"
         );
 
-        let mut summary = if findings.is_empty() {
-            "No findings available; analyst requires additional research input".to_string()
-        } else {
-            format!(
-                "Top insights: {}. Confidence supported by {} sources.",
-                findings.join("; "),
-                sources.len()
-            )
-        };
+        let mut summary = self.summarize(&findings, &sources);
+        let mut summary_with_citations =
+            self.cite(&findings, &finding_sources, &sources, &source_references);
 
         if !degradation_note.trim().is_empty() {
             summary.push_str(&format!("\nNote: {}", degradation_note));
+            if let Some(cited) = summary_with_citations.as_mut() {
+                cited.push_str(&format!("\nNote: {}", degradation_note));
+            }
         }
 
-        let structured = AnalystOutput {
-            summary: summary.clone(),
+        let metadata: HashMap<String, Value> = context
+            .get("analysis.metadata_seed")
+            .await
+            .unwrap_or_default();
+
+        let structured = AnalystOutput {
+            summary: summary.clone(),
+            summary_with_citations,
             highlight: findings.first().cloned().unwrap_or_default(),
             sources,
+            source_references,
+            metadata,
         };
 
         context.set("analysis.output", &structured).await;
@@ -528,10 +1254,11 @@ impl Task for AnalystTask {
             "analyst produced structured summary"
         );
 
-        record_trace(
+        record_timed_trace(
             &context,
             self.id(),
             format!("highlight: {}", structured.highlight),
+            started.elapsed(),
         )
         .await;
 
@@ -542,8 +1269,78 @@ impl Task for AnalystTask {
     }
 }
 
-#[derive(Default)]
-pub struct CriticTask;
+/// Evidence handed to a critic scorer.
+#[derive(Debug, Clone)]
+pub struct CriticInput {
+    pub analysis: AnalystOutput,
+    pub fact_confidence: f32,
+    pub fact_passed: bool,
+    pub verified_sources: Vec<String>,
+}
+
+/// Outcome of critic scoring; `explanation` becomes `critique.verdict`.
+#[derive(Debug, Clone)]
+pub struct CriticVerdict {
+    pub passes: bool,
+    pub explanation: String,
+}
+
+pub type CriticScorer = Arc<dyn Fn(&CriticInput) -> CriticVerdict + Send + Sync>;
+
+pub struct CriticTask {
+    scorer: Option<CriticScorer>,
+    minimum_source_count: usize,
+}
+
+impl Default for CriticTask {
+    fn default() -> Self {
+        Self {
+            scorer: None,
+            minimum_source_count: 1,
+        }
+    }
+}
+
+impl CriticTask {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the built-in verdict heuristic with a domain-specific scorer.
+    pub fn with_custom_scoring(mut self, scorer: CriticScorer) -> Self {
+        self.scorer = Some(scorer);
+        self
+    }
+
+    /// Require at least `count` analysis sources before the built-in heuristic passes
+    /// (default 1). Custom scorers see the outcome via `critique.source_count_met`.
+    pub fn with_minimum_source_count(mut self, count: usize) -> Self {
+        self.minimum_source_count = count;
+        self
+    }
+
+    fn default_verdict(&self, input: &CriticInput) -> CriticVerdict {
+        let passes = input.fact_passed
+            && input.analysis.summary.split('.').count() >= 2
+            && input.analysis.sources.len() >= self.minimum_source_count;
+        let explanation = if passes {
+            "Analysis passes automated checks"
+        } else {
+            "Insufficient evidence; requires manual review"
+        };
+        CriticVerdict {
+            passes,
+            explanation: explanation.to_string(),
+        }
+    }
+
+    /// Fact-check notes that report zero verified coverage contradict an analysis that
+    /// still cites sources.
+    fn detect_contradiction(fact_notes: &str, analysis: &AnalystOutput) -> bool {
+        !analysis.sources.is_empty()
+            && (fact_notes.contains("coverage 0%") || fact_notes.contains("verified 0 sources"))
+    }
+}
 
 #[async_trait]
 impl Task for CriticTask {
@@ -553,6 +1350,7 @@ impl Task for CriticTask {
 
     #[instrument(name = "task.critic", skip(self, context))]
     async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
+        let started = std::time::Instant::now();
         let analysis: AnalystOutput = context
             .get("analysis.output")
             .await
@@ -563,37 +1361,71 @@ impl Task for CriticTask {
             .get("factcheck.verified_sources")
             .await
             .unwrap_or_default();
+        let fact_notes: String = context.get("factcheck.notes").await.unwrap_or_default();
 
-        let passes_confidence =
-            fact_passed && analysis.summary.split('.').count() >= 2 && !analysis.sources.is_empty();
+        let input = CriticInput {
+            analysis,
+            fact_confidence,
+            fact_passed,
+            verified_sources,
+        };
+        let mut verdict = match &self.scorer {
+            Some(scorer) => scorer(&input),
+            None => self.default_verdict(&input),
+        };
+        let source_count_met = input.analysis.sources.len() >= self.minimum_source_count;
+        let contradiction = Self::detect_contradiction(&fact_notes, &input.analysis);
+        if contradiction {
+            verdict.explanation.push_str(&format!(
+                " (conflict: fact-check reported \"{fact_notes}\" but the analysis cites {} source(s))",
+                input.analysis.sources.len()
+            ));
+        }
+        let CriticInput {
+            analysis,
+            verified_sources,
+            ..
+        } = input;
+        let passes_confidence = verdict.passes;
 
         context.set_sync("critique.confident", passes_confidence);
-        let verdict = if passes_confidence {
-            "Analysis passes automated checks"
-        } else {
-            "Insufficient evidence; requires manual review"
-        };
-        context.set_sync("critique.verdict", verdict);
+        context.set_sync("critique.verdict", verdict.explanation);
+        context.set_sync("critique.contradiction", contradiction);
+        context.set_sync(
+            "critique.minimum_source_count_required",
+            self.minimum_source_count,
+        );
+        context.set_sync("critique.source_count_met", source_count_met);
 
         info!(
             confident = passes_confidence,
+            contradiction,
             sources = analysis.sources.len(),
+            minimum_sources = self.minimum_source_count,
             fact_confidence = fact_confidence,
             "critic evaluated analysis"
         );
 
-        record_trace(
+        let verdict_label = if passes_confidence {
+            "auto-approved"
+        } else {
+            "manual review"
+        };
+        record_tagged_trace(
             &context,
             self.id(),
             format!(
-                "verdict: {} (fact {:.2})",
-                if passes_confidence {
-                    "auto-approved"
+                "verdict: {} (fact {:.2}{})",
+                verdict_label,
+                fact_confidence,
+                if self.scorer.is_some() {
+                    ", custom scoring"
                 } else {
-                    "manual review"
-                },
-                fact_confidence
+                    ""
+                }
             ),
+            started.elapsed(),
+            HashMap::from([(TRACE_TAG_VERDICT.to_string(), Value::from(verdict_label))]),
         )
         .await;
 
@@ -627,8 +1459,182 @@ impl Task for CriticTask {
     }
 }
 
+/// Rendering of `final.summary` chosen with [`FinalizeTask::with_output_format`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinalizeOutputFormat {
+    #[default]
+    PlainText,
+    /// Each section under a `## ` heading.
+    Markdown,
+    /// Plain-text summary plus a JSON object in `final.structured_output`.
+    StructuredJson,
+}
+
+/// Everything the finalizer reports, gathered once from the context.
+struct FinalReport {
+    verdict: String,
+    analysis: AnalystOutput,
+    confident: bool,
+    fact_confidence: f32,
+    verified_sources: Vec<String>,
+    contradiction: bool,
+}
+
+impl FinalReport {
+    const CONTRADICTION_NOTE: &'static str =
+        "fact-check verified none of the cited sources; treat the summary as unsupported.";
+
+    async fn from_context(context: &Context) -> Self {
+        Self {
+            verdict: context
+                .get::<String>("critique.verdict")
+                .await
+                .unwrap_or_else(|| "No verdict recorded".to_string()),
+            analysis: context
+                .get("analysis.output")
+                .await
+                .unwrap_or_else(AnalystOutput::default),
+            confident: context
+                .get::<bool>("critique.confident")
+                .await
+                .unwrap_or(false),
+            fact_confidence: context
+                .get::<f32>("factcheck.confidence")
+                .await
+                .unwrap_or(0.0),
+            verified_sources: context
+                .get("factcheck.verified_sources")
+                .await
+                .unwrap_or_default(),
+            contradiction: context
+                .get::<bool>("critique.contradiction")
+                .await
+                .unwrap_or(false),
+        }
+    }
+
+    fn confidence_label(&self) -> &'static str {
+        if self.confident {
+            "High"
+        } else {
+            "Review suggested"
+        }
+    }
+
+    fn numbered(items: &[String], empty: &str) -> String {
+        if items.is_empty() {
+            return format!("  {empty}");
+        }
+        items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| format!("  {}. {}", idx + 1, item))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Metadata as pretty JSON with sorted keys so the rendered block is stable across runs.
+    fn metadata_block(&self) -> Option<String> {
+        if self.analysis.metadata.is_empty() {
+            return None;
+        }
+        let ordered: BTreeMap<&String, &Value> = self.analysis.metadata.iter().collect();
+        Some(serde_json::to_string_pretty(&ordered).unwrap_or_default())
+    }
+
+    fn render_plain(&self) -> String {
+        let mut summary = format!(
+            "{}\n\nSummary:\n{}\n\nKey Insight: {}\nConfidence: {}\nSources:\n{}\n\nFact-Check Confidence: {:.2}\nVerified Sources:\n{}",
+            self.verdict,
+            self.analysis.summary,
+            self.analysis.highlight,
+            self.confidence_label(),
+            Self::numbered(&self.analysis.sources, "(none recorded)"),
+            self.fact_confidence,
+            Self::numbered(&self.verified_sources, "(none verified)"),
+        );
+        if self.contradiction {
+            summary.push_str(&format!("\n\nContradiction: {}", Self::CONTRADICTION_NOTE));
+        }
+        if let Some(metadata) = self.metadata_block() {
+            summary.push_str(&format!("\n\nMetadata:\n{metadata}"));
+        }
+        summary
+    }
+
+    fn render_markdown(&self) -> String {
+        let list = |items: &[String], empty: &str| {
+            if items.is_empty() {
+                format!("_{empty}_")
+            } else {
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, item)| format!("{}. {}", idx + 1, item))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        };
+        let mut sections = vec![
+            format!("## Verdict\n\n{}", self.verdict),
+            format!("## Summary\n\n{}", self.analysis.summary),
+            format!("## Key Insight\n\n{}", self.analysis.highlight),
+            format!("## Confidence\n\n{}", self.confidence_label()),
+            format!(
+                "## Sources\n\n{}",
+                list(&self.analysis.sources, "None recorded")
+            ),
+            format!(
+                "## Fact-Check\n\nConfidence: {:.2}\n\n{}",
+                self.fact_confidence,
+                list(&self.verified_sources, "None verified")
+            ),
+        ];
+        if self.contradiction {
+            sections.push(format!("## Contradiction\n\n{}", Self::CONTRADICTION_NOTE));
+        }
+        if let Some(metadata) = self.metadata_block() {
+            sections.push(format!("## Metadata\n\n```json\n{metadata}\n```"));
+        }
+        sections.join("\n\n")
+    }
+
+    fn structured(&self) -> Value {
+        serde_json::json!({
+            "verdict": self.verdict,
+            "summary": self.analysis.summary,
+            "highlight": self.analysis.highlight,
+            "confident": self.confident,
+            "sources": self.analysis.sources,
+            "source_references": self.analysis.source_references,
+            "fact_check": {
+                "confidence": self.fact_confidence,
+                "verified_sources": self.verified_sources,
+            },
+            "contradiction": self.contradiction,
+            "metadata": self.analysis.metadata,
+        })
+    }
+}
+
 #[derive(Default)]
-pub struct FinalizeTask;
+pub struct FinalizeTask {
+    output_format: FinalizeOutputFormat,
+}
+
+impl FinalizeTask {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Choose how `final.summary` is rendered; `StructuredJson` also writes
+    /// `final.structured_output`.
+    pub fn with_output_format(mut self, format: FinalizeOutputFormat) -> Self {
+        self.output_format = format;
+        self
+    }
+}
 
 #[async_trait]
 impl Task for FinalizeTask {
@@ -638,78 +1644,112 @@ impl Task for FinalizeTask {
 
     #[instrument(name = "task.finalize", skip(self, context))]
     async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
-        let analysis: AnalystOutput = context
-            .get("analysis.output")
-            .await
-            .unwrap_or_else(AnalystOutput::default);
+        let started = std::time::Instant::now();
+        let report = FinalReport::from_context(&context).await;
 
-        let verdict = context
-            .get::<String>("critique.verdict")
-            .await
-            .unwrap_or_else(|| "No verdict recorded".to_string());
-
-        let confident = context
-            .get::<bool>("critique.confident")
-            .await
-            .unwrap_or(false);
-        let fact_confidence = context
-            .get::<f32>("factcheck.confidence")
-            .await
-            .unwrap_or(0.0);
-        let verified_sources: Vec<String> = context
-            .get("factcheck.verified_sources")
-            .await
-            .unwrap_or_default();
-
-        let sources_block = if analysis.sources.is_empty() {
-            "  (none recorded)".to_string()
-        } else {
-            analysis
-                .sources
-                .iter()
-                .enumerate()
-                .map(|(idx, src)| format!("  {}. {}", idx + 1, src))
-                .collect::<Vec<_>>()
-                .join("\n")
-        };
-        let verified_block = if verified_sources.is_empty() {
-            "  (none verified)".to_string()
-        } else {
-            verified_sources
-                .iter()
-                .enumerate()
-                .map(|(idx, src)| format!("  {}. {}", idx + 1, src))
-                .collect::<Vec<_>>()
-                .join("\n")
+        let summary = match self.output_format {
+            FinalizeOutputFormat::Markdown => report.render_markdown(),
+            FinalizeOutputFormat::PlainText | FinalizeOutputFormat::StructuredJson => {
+                report.render_plain()
+            }
         };
-
-        let summary = format!(
-            "{verdict}\n\nSummary:\n{}\n\nKey Insight: {}\nConfidence: {}\nSources:\n{}\n\nFact-Check Confidence: {:.2}\nVerified Sources:\n{}",
-            analysis.summary,
-            analysis.highlight,
-            if confident {
-                "High"
-            } else {
-                "Review suggested"
-            },
-            sources_block,
-            fact_confidence,
-            verified_block,
-        );
+        if self.output_format == FinalizeOutputFormat::StructuredJson {
+            context
+                .set("final.structured_output", report.structured())
+                .await;
+        }
 
         context.set("final.summary", summary.clone()).await;
+        context.set("final.output_format", self.output_format).await;
         context.set("final.requires_manual", false).await;
+        context
+            .set(
+                "final.source_references",
+                &report.analysis.source_references,
+            )
+            .await;
 
-        info!(confident, "finalize task completed");
+        info!(confident = report.confident, "finalize task completed");
 
-        record_trace(&context, self.id(), "final summary emitted").await;
+        record_timed_trace(
+            &context,
+            self.id(),
+            "final summary emitted",
+            started.elapsed(),
+        )
+        .await;
 
         Ok(TaskResult::new(Some(summary), NextAction::End))
     }
 }
 
+/// HTTP endpoint notified whenever a session is routed to manual review.
+#[derive(Debug, Clone)]
+pub struct ManualReviewWebhook {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
 #[derive(Default)]
-pub struct ManualReviewTask;
+pub struct ManualReviewTask {
+    webhook: Option<ManualReviewWebhook>,
+    #[cfg(feature = "webhook")]
+    client: reqwest::Client,
+}
+
+impl ManualReviewTask {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// POST `{session_id, summary, timestamp}` to `url` when manual review is requested.
+    /// Delivery runs in the background; failures are logged and never fail the task.
+    /// Requires the `webhook` feature; without it the call is logged and skipped.
+    pub fn with_webhook(mut self, url: String, headers: Vec<(String, String)>) -> Self {
+        self.webhook = Some(ManualReviewWebhook { url, headers });
+        self
+    }
+
+    #[cfg(feature = "webhook")]
+    fn notify(&self, session_id: String, summary: &str) {
+        let Some(webhook) = self.webhook.clone() else {
+            return;
+        };
+        let mut request = self.client.post(&webhook.url).json(&serde_json::json!({
+            "session_id": session_id,
+            "summary": summary,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        }));
+        for (name, value) in &webhook.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        tokio::spawn(async move {
+            match request
+                .send()
+                .await
+                .and_then(|resp| resp.error_for_status())
+            {
+                Ok(_) => debug!(url = %webhook.url, "manual review webhook delivered"),
+                Err(err) => warn!(
+                    url = %webhook.url,
+                    error = %err,
+                    "manual review webhook failed"
+                ),
+            }
+        });
+    }
+
+    #[cfg(not(feature = "webhook"))]
+    fn notify(&self, session_id: String, _summary: &str) {
+        if let Some(webhook) = &self.webhook {
+            warn!(
+                %session_id,
+                url = %webhook.url,
+                "manual review webhook configured but deepresearch-core was built without `webhook`"
+            );
+        }
+    }
+}
 
 #[async_trait]
 impl Task for ManualReviewTask {
@@ -719,6 +1759,7 @@ impl Task for ManualReviewTask {
 
     #[instrument(name = "task.manual_review", skip(self, context))]
     async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
+        let started = std::time::Instant::now();
         let summary = String::from(
             "Automated checks flagged low confidence. Please perform manual verification.",
         );
@@ -727,8 +1768,16 @@ impl Task for ManualReviewTask {
         context.set("final.requires_manual", true).await;
 
         info!("manual review required");
+        let session_id: String = context.get("session_id").await.unwrap_or_default();
+        self.notify(session_id, &summary);
 
-        record_trace(&context, self.id(), "manual review requested").await;
+        record_timed_trace(
+            &context,
+            self.id(),
+            "manual review requested",
+            started.elapsed(),
+        )
+        .await;
 
         Ok(TaskResult::new(Some(summary), NextAction::End))
     }
@@ -737,6 +1786,859 @@ impl Task for ManualReviewTask {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AnalystOutput {
     pub summary: String,
+    /// `summary` with citation markers and a reference block, set when the analyst runs
+    /// with a non-inline [`CitationStyle`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_with_citations: Option<String>,
     pub highlight: String,
     pub sources: Vec<String>,
+    /// Structured provenance (URL, title, retrieval score) for `sources`.
+    #[serde(default)]
+    pub source_references: Vec<SourceReference>,
+    /// Deployment-specific structured data (e.g. `sentiment`, `topic_tags`), seeded from
+    /// `analysis.metadata_seed`.
+    #[serde(default)]
+    pub metadata: HashMap<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{IngestDocument, Retriever};
+    use crate::sandbox::MockSandboxExecutor;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FlakyRetriever {
+        failures: usize,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Retriever for FlakyRetriever {
+        async fn retrieve(
+            &self,
+            _session_id: &str,
+            _query: &str,
+            _limit: usize,
+        ) -> anyhow::Result<Vec<RetrievedDocument>> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call < self.failures {
+                anyhow::bail!("transient network error");
+            }
+            Ok(vec![RetrievedDocument {
+                text: "Grid storage demand doubled".to_string(),
+                score: 0.9,
+                source: Some("https://example.com/grid".to_string()),
+            }])
+        }
+
+        async fn ingest(
+            &self,
+            _session_id: &str,
+            _docs: Vec<IngestDocument>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn fast_retry(max_attempts: usize) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            initial_backoff_ms: 1,
+            backoff_factor: 2.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn retrieval_retries_transient_failures() {
+        let retriever = Arc::new(FlakyRetriever {
+            failures: 2,
+            calls: AtomicUsize::new(0),
+        });
+        let task = ResearchTask::new(retriever.clone()).with_retry(fast_retry(3));
+
+        let outcome = task.run_retrieval("s-1", "grid storage").await;
+
+        assert_eq!(outcome.retry_count, 2);
+        assert!(!outcome.degraded);
+        assert_eq!(outcome.documents[0].text, "Grid storage demand doubled");
+        assert_eq!(retriever.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retrieval_degrades_after_exhausting_attempts() {
+        let retriever = Arc::new(FlakyRetriever {
+            failures: usize::MAX,
+            calls: AtomicUsize::new(0),
+        });
+        let task = ResearchTask::new(retriever.clone()).with_retry(fast_retry(2));
+
+        let outcome = task.run_retrieval("s-1", "grid storage").await;
+
+        assert_eq!(outcome.retry_count, 1);
+        assert!(outcome.degraded);
+        assert_eq!(outcome.documents[0].source.as_deref(), Some("stub://error"));
+    }
+
+    #[tokio::test]
+    async fn result_scorer_reorders_persisted_findings() {
+        let doc = |text: &str, score: f32| RetrievedDocument {
+            text: text.to_string(),
+            score,
+            source: Some(format!("https://example.com/{score}")),
+        };
+        let retriever = Arc::new(crate::memory::SeedRetriever::new(vec![
+            doc("older report", 0.9),
+            doc("recent report", 0.4),
+        ]));
+        let task = ResearchTask::new(retriever).with_result_scorer(Arc::new(|mut docs| {
+            docs.reverse();
+            docs
+        }));
+        let context = Context::new();
+        context.set("session_id", "s-1".to_string()).await;
+
+        task.run(context.clone()).await.expect("research task runs");
+
+        let findings: Vec<String> = context.get("research.findings").await.unwrap();
+        assert_eq!(findings, vec!["recent report", "older report"]);
+        assert_eq!(
+            context.get::<bool>("research.scorer_applied").await,
+            Some(true)
+        );
+    }
+
+    #[tokio::test]
+    async fn researcher_truncates_long_findings_at_word_boundaries() {
+        let doc = |text: &str| RetrievedDocument {
+            text: text.to_string(),
+            score: 0.8,
+            source: None,
+        };
+        let retriever = Arc::new(crate::memory::SeedRetriever::new(vec![
+            doc("alpha  beta\ngamma delta epsilon"),
+            doc("short note"),
+        ]));
+        let context = Context::new();
+        context.set("session_id", "s-trunc".to_string()).await;
+
+        ResearchTask::new(retriever.clone())
+            .with_max_tokens_per_finding(3)
+            .run(context.clone())
+            .await
+            .expect("research task runs");
+
+        let findings: Vec<String> = context.get("research.findings").await.unwrap();
+        assert_eq!(findings, vec!["alpha  beta\ngamma", "short note"]);
+        assert_eq!(
+            context.get::<bool>("research.findings_truncated").await,
+            Some(true)
+        );
+
+        ResearchTask::new(retriever)
+            .with_max_tokens_per_finding(5)
+            .run(context.clone())
+            .await
+            .expect("research task runs");
+        assert_eq!(
+            context.get::<bool>("research.findings_truncated").await,
+            Some(false)
+        );
+        assert_eq!(truncate_words("one two ", 2), None);
+        assert_eq!(truncate_words("one two", 0), Some(""));
+    }
+
+    #[tokio::test]
+    async fn researcher_records_structured_source_references() {
+        let retriever = Arc::new(crate::memory::SeedRetriever::new(vec![
+            RetrievedDocument {
+                text: "grid report".to_string(),
+                score: 0.8,
+                source: Some("[Grid outlook](https://example.com/grid)".to_string()),
+            },
+            RetrievedDocument {
+                text: "solar report".to_string(),
+                score: 0.5,
+                source: Some("https://example.com/solar".to_string()),
+            },
+        ]));
+        let context = Context::new();
+        context.set("session_id", "s-refs".to_string()).await;
+
+        ResearchTask::new(retriever)
+            .run(context.clone())
+            .await
+            .expect("research task runs");
+        AnalystTask::new()
+            .run(context.clone())
+            .await
+            .expect("analyst task runs");
+
+        let references: Vec<SourceReference> = context
+            .get("research.source_references")
+            .await
+            .expect("references recorded");
+        assert_eq!(
+            references,
+            vec![
+                SourceReference {
+                    url: "https://example.com/grid".to_string(),
+                    title: Some("Grid outlook".to_string()),
+                    score: Some(0.8),
+                },
+                SourceReference {
+                    url: "https://example.com/solar".to_string(),
+                    title: None,
+                    score: Some(0.5),
+                },
+            ]
+        );
+        let analysis: AnalystOutput = context.get("analysis.output").await.unwrap();
+        assert_eq!(analysis.source_references, references);
+    }
+
+    struct FailingUntilFixed;
+
+    #[async_trait]
+    impl SandboxExecutor for FailingUntilFixed {
+        async fn execute(&self, request: SandboxRequest) -> anyhow::Result<SandboxResult> {
+            let fixed = request.script_contents.contains("# fixed");
+            Ok(SandboxResult {
+                exit_code: Some(if fixed { 0 } else { 1 }),
+                stdout: String::new(),
+                stderr: String::new(),
+                outputs: Vec::new(),
+                timed_out: false,
+                duration: Duration::from_millis(1),
+                stderr_overflow_warning: None,
+                resource_usage: None,
+                partial_output_errors: Vec::new(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn math_tool_retries_with_mutated_script() {
+        let policy = MathRetryPolicy::new(3).with_mutator(|script, retry| {
+            if retry == 2 {
+                format!("{script}\n# fixed")
+            } else {
+                script.to_string()
+            }
+        });
+        let task = MathToolTask::new(Arc::new(FailingUntilFixed)).with_retry_policy(policy);
+        let context = Context::new();
+        context
+            .set(
+                "math.request",
+                MathToolRequest {
+                    script: "print(1 / 0)".to_string(),
+                    ..MathToolRequest::default()
+                },
+            )
+            .await;
+
+        task.run(context.clone()).await.expect("math task");
+
+        assert_eq!(
+            context.get::<String>("math.status").await.as_deref(),
+            Some("success")
+        );
+        assert_eq!(context.get::<usize>("math.retry_attempts").await, Some(2));
+        assert_eq!(
+            context.get::<String>("math.final_script").await.as_deref(),
+            Some("print(1 / 0)\n# fixed")
+        );
+    }
+
+    #[tokio::test]
+    async fn math_tool_surfaces_missing_outputs_separately_from_failure() {
+        let mut result = MockSandboxExecutor::success("done");
+        result.partial_output_errors = vec![(
+            "plot.png".to_string(),
+            "No such file or directory (os error 2)".to_string(),
+        )];
+        let task = MathToolTask::new(Arc::new(MockSandboxExecutor::new().with_response(result)));
+        let context = Context::new();
+        context
+            .set(
+                "math.request",
+                MathToolRequest {
+                    script: "print(1)".to_string(),
+                    ..MathToolRequest::default()
+                },
+            )
+            .await;
+
+        task.run(context.clone()).await.expect("math task");
+
+        assert_eq!(
+            context.get::<String>("math.status").await.as_deref(),
+            Some("success")
+        );
+        let missing: Vec<(String, String)> = context
+            .get("math.partial_output_errors")
+            .await
+            .expect("partial output errors");
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].0, "plot.png");
+        let stored: MathToolResult = context.get("math.result").await.expect("math result");
+        assert_eq!(stored.outputs_missing, missing);
+    }
+
+    #[tokio::test]
+    async fn math_tool_without_policy_does_not_retry() {
+        let task = MathToolTask::new(Arc::new(FailingUntilFixed));
+        let context = Context::new();
+        context
+            .set(
+                "math.request",
+                MathToolRequest {
+                    script: "print(1 / 0)".to_string(),
+                    ..MathToolRequest::default()
+                },
+            )
+            .await;
+
+        task.run(context.clone()).await.expect("math task");
+
+        assert_eq!(
+            context.get::<String>("math.status").await.as_deref(),
+            Some("failure")
+        );
+        assert_eq!(context.get::<usize>("math.retry_attempts").await, Some(0));
+    }
+
+    #[tokio::test]
+    async fn analyst_metadata_seed_reaches_final_summary() {
+        let context = Context::new();
+        context
+            .set("research.findings", vec!["Rates stabilised".to_string()])
+            .await;
+        context
+            .set(
+                "analysis.metadata_seed",
+                serde_json::json!({"domain": "finance", "topic_tags": ["rates"]}),
+            )
+            .await;
+
+        AnalystTask::new()
+            .run(context.clone())
+            .await
+            .expect("analyst runs");
+        let output: AnalystOutput = context.get("analysis.output").await.unwrap();
+        assert_eq!(output.metadata["domain"], "finance");
+
+        FinalizeTask::new()
+            .run(context.clone())
+            .await
+            .expect("finalize runs");
+        let summary: String = context.get("final.summary").await.unwrap();
+        assert!(summary.contains("Metadata:\n{\n  \"domain\": \"finance\""));
+        assert!(summary.contains("\"topic_tags\""));
+    }
+
+    #[tokio::test]
+    async fn finalize_renders_markdown_and_structured_json() {
+        let context = Context::new();
+        context
+            .set(
+                "analysis.output",
+                AnalystOutput {
+                    summary: "Rates held steady.".to_string(),
+                    highlight: "Stability".to_string(),
+                    sources: vec!["https://example.com/rates".to_string()],
+                    ..AnalystOutput::default()
+                },
+            )
+            .await;
+        context.set("critique.verdict", "Looks good").await;
+
+        FinalizeTask::new()
+            .with_output_format(FinalizeOutputFormat::Markdown)
+            .run(context.clone())
+            .await
+            .expect("markdown finalize");
+        let markdown: String = context.get("final.summary").await.unwrap();
+        assert!(markdown.starts_with("## Verdict\n\nLooks good"));
+        assert!(markdown.contains("## Sources\n\n1. https://example.com/rates"));
+        assert_eq!(
+            context
+                .get::<String>("final.output_format")
+                .await
+                .as_deref(),
+            Some("markdown")
+        );
+        assert!(
+            context
+                .get::<Value>("final.structured_output")
+                .await
+                .is_none()
+        );
+
+        FinalizeTask::new()
+            .with_output_format(FinalizeOutputFormat::StructuredJson)
+            .run(context.clone())
+            .await
+            .expect("structured finalize");
+        let plain: String = context.get("final.summary").await.unwrap();
+        assert!(plain.starts_with("Looks good\n\nSummary:\nRates held steady."));
+        let structured: Value = context.get("final.structured_output").await.unwrap();
+        assert_eq!(structured["summary"], "Rates held steady.");
+        assert_eq!(structured["sources"][0], "https://example.com/rates");
+        assert_eq!(
+            context
+                .get::<FinalizeOutputFormat>("final.output_format")
+                .await,
+            Some(FinalizeOutputFormat::StructuredJson)
+        );
+    }
+
+    #[tokio::test]
+    async fn critic_uses_custom_scorer_when_configured() {
+        let context = Context::new();
+        context
+            .set(
+                "analysis.output",
+                AnalystOutput {
+                    summary: "One sentence only".to_string(),
+                    highlight: String::new(),
+                    sources: Vec::new(),
+                    ..AnalystOutput::default()
+                },
+            )
+            .await;
+        context.set("factcheck.confidence", 0.9_f32).await;
+
+        let scorer: CriticScorer = Arc::new(|input: &CriticInput| CriticVerdict {
+            passes: input.fact_confidence >= 0.8,
+            explanation: format!("Domain rule: confidence {:.1}", input.fact_confidence),
+        });
+        CriticTask::new()
+            .with_custom_scoring(scorer)
+            .run(context.clone())
+            .await
+            .expect("critic task");
+
+        assert_eq!(context.get::<bool>("critique.confident").await, Some(true));
+        assert_eq!(
+            context.get::<String>("critique.verdict").await.as_deref(),
+            Some("Domain rule: confidence 0.9")
+        );
+
+        CriticTask::new()
+            .run(context.clone())
+            .await
+            .expect("critic task");
+        assert_eq!(context.get::<bool>("critique.confident").await, Some(false));
+    }
+
+    #[tokio::test]
+    async fn critic_enforces_minimum_source_count() {
+        let context = Context::new();
+        context
+            .set(
+                "analysis.output",
+                AnalystOutput {
+                    summary: "First finding. Second finding.".to_string(),
+                    highlight: String::new(),
+                    sources: vec![
+                        "https://example.com/a".to_string(),
+                        "https://example.com/b".to_string(),
+                    ],
+                    ..AnalystOutput::default()
+                },
+            )
+            .await;
+
+        CriticTask::new()
+            .run(context.clone())
+            .await
+            .expect("critic task");
+        assert_eq!(context.get::<bool>("critique.confident").await, Some(true));
+        assert_eq!(
+            context
+                .get::<usize>("critique.minimum_source_count_required")
+                .await,
+            Some(1)
+        );
+
+        CriticTask::new()
+            .with_minimum_source_count(3)
+            .run(context.clone())
+            .await
+            .expect("critic task");
+        assert_eq!(context.get::<bool>("critique.confident").await, Some(false));
+        assert_eq!(
+            context.get::<bool>("critique.source_count_met").await,
+            Some(false)
+        );
+        assert_eq!(
+            context
+                .get::<usize>("critique.minimum_source_count_required")
+                .await,
+            Some(3)
+        );
+    }
+
+    #[tokio::test]
+    async fn critic_flags_fact_check_contradiction() {
+        let context = Context::new();
+        context
+            .set(
+                "analysis.output",
+                AnalystOutput {
+                    summary: "First finding. Second finding.".to_string(),
+                    highlight: String::new(),
+                    sources: vec!["https://example.com/report".to_string()],
+                    ..AnalystOutput::default()
+                },
+            )
+            .await;
+        context.set("factcheck.passed", true).await;
+        context
+            .set("factcheck.notes", "verified 0 sources (coverage 0%)")
+            .await;
+
+        CriticTask::new()
+            .run(context.clone())
+            .await
+            .expect("critic task");
+
+        assert_eq!(
+            context.get::<bool>("critique.contradiction").await,
+            Some(true)
+        );
+        let verdict = context
+            .get::<String>("critique.verdict")
+            .await
+            .expect("verdict");
+        assert!(verdict.contains("conflict"), "verdict: {verdict}");
+
+        let summary = FinalizeTask::new()
+            .run(context.clone())
+            .await
+            .expect("finalize task")
+            .response
+            .expect("summary");
+        assert!(summary.contains("Contradiction:"), "summary: {summary}");
+
+        context
+            .set("factcheck.notes", "verified 1 sources (coverage 100%)")
+            .await;
+        CriticTask::new()
+            .run(context.clone())
+            .await
+            .expect("critic task");
+        assert_eq!(
+            context.get::<bool>("critique.contradiction").await,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn fact_check_settings_read_env_overrides() {
+        let vars = HashMap::from([
+            (FC_MIN_CONFIDENCE_ENV, "0.85"),
+            (FC_VERIFICATION_COUNT_ENV, "5"),
+            (FC_TIMEOUT_MS_ENV, "not-a-number"),
+            (FC_BLOCKLIST_ENV, "WWW.Spam.example, ,junk.example"),
+        ]);
+        let settings =
+            FactCheckSettings::from_lookup(|key| vars.get(key).map(|value| value.to_string()));
+
+        assert!((settings.min_confidence - 0.85).abs() < f32::EPSILON);
+        assert_eq!(settings.verification_count, 5);
+        assert_eq!(settings.timeout_ms, FactCheckSettings::default().timeout_ms);
+        assert_eq!(
+            settings.source_blocklist,
+            HashSet::from(["spam.example".to_string(), "junk.example".to_string()])
+        );
+        assert!(settings.is_blocked("https://spam.example/post"));
+    }
+
+    #[test]
+    fn math_request_stdin_round_trips_as_base64() {
+        let request: MathToolRequest = serde_json::from_value(serde_json::json!({
+            "script": "import sys; print(sys.stdin.read())",
+            "stdin_bytes": "YSxiCjEsMgo="
+        }))
+        .expect("request should deserialize");
+        assert_eq!(request.stdin_bytes.as_deref(), Some(&b"a,b\n1,2\n"[..]));
+
+        let encoded = serde_json::to_value(&request).expect("request should serialize");
+        assert_eq!(encoded["stdin_bytes"], "YSxiCjEsMgo=");
+
+        let legacy: MathToolRequest =
+            serde_json::from_value(serde_json::json!({ "script": "print(1)" })).unwrap();
+        assert!(legacy.stdin_bytes.is_none());
+    }
+
+    #[test]
+    fn extracts_markdown_links_and_inline_urls() {
+        let text = "See [IEA outlook](https://iea.org/report) and https://example.com/data. \
+                    Repeat: https://iea.org/report";
+
+        let citations = extract_citations(text);
+
+        assert_eq!(
+            citations,
+            vec![
+                SourceReference {
+                    url: "https://iea.org/report".to_string(),
+                    title: Some("IEA outlook".to_string()),
+                    score: None,
+                },
+                SourceReference {
+                    url: "https://example.com/data".to_string(),
+                    title: None,
+                    score: None,
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "webhook")]
+    #[tokio::test]
+    async fn manual_review_posts_webhook() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/review", listener.local_addr().unwrap());
+        let task = ManualReviewTask::new()
+            .with_webhook(url, vec![("x-review-token".into(), "abc".into())]);
+        let context = Context::new();
+        context.set("session_id", "session-42".to_string()).await;
+
+        task.run(context.clone()).await.expect("manual review runs");
+        assert_eq!(
+            context.get::<bool>("final.requires_manual").await,
+            Some(true)
+        );
+
+        let (mut socket, _) = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+            .await
+            .expect("webhook was not sent")
+            .unwrap();
+        let mut received = Vec::new();
+        let mut buf = [0u8; 4096];
+        while !String::from_utf8_lossy(&received).contains("\"timestamp\"") {
+            let read = socket.read(&mut buf).await.unwrap();
+            assert!(read > 0, "connection closed before body arrived");
+            received.extend_from_slice(&buf[..read]);
+        }
+        socket
+            .write_all(b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n")
+            .await
+            .unwrap();
+
+        let request = String::from_utf8_lossy(&received).to_lowercase();
+        assert!(request.starts_with("post /review"));
+        assert!(request.contains("x-review-token: abc"));
+        assert!(request.contains("\"session_id\":\"session-42\""));
+    }
+
+    #[test]
+    fn deduplicates_sources_by_host_and_path() {
+        let sources = vec![
+            "https://example.com/report?utm_source=feed".to_string(),
+            "https://EXAMPLE.com/report#summary".to_string(),
+            "http://www.example.com/report/".to_string(),
+            "https://example.com/other".to_string(),
+            "Internal memo".to_string(),
+            " internal memo ".to_string(),
+        ];
+
+        assert_eq!(
+            deduplicate_sources(&sources),
+            vec![
+                "https://example.com/report?utm_source=feed".to_string(),
+                "https://example.com/other".to_string(),
+                "Internal memo".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn analyst_renders_footnote_and_chicago_citations() {
+        let context = Context::new();
+        context
+            .set(
+                "research.findings",
+                vec!["Prices fell".to_string(), "Demand rose".to_string()],
+            )
+            .await;
+        context
+            .set(
+                "research.sources",
+                vec![
+                    "https://example.com/prices".to_string(),
+                    "https://example.com/demand".to_string(),
+                ],
+            )
+            .await;
+        context
+            .set(
+                "research.source_references",
+                vec![SourceReference {
+                    url: "https://example.com/prices".to_string(),
+                    title: Some("Price Index".to_string()),
+                    score: None,
+                }],
+            )
+            .await;
+
+        AnalystTask::new()
+            .run(context.clone())
+            .await
+            .expect("analyst task");
+        let plain: AnalystOutput = context.get("analysis.output").await.unwrap();
+        assert!(plain.summary_with_citations.is_none());
+
+        AnalystTask::new()
+            .with_citation_style(CitationStyle::Footnote)
+            .run(context.clone())
+            .await
+            .expect("analyst task");
+        let footnoted: AnalystOutput = context.get("analysis.output").await.unwrap();
+        assert_eq!(footnoted.summary, plain.summary);
+        assert_eq!(
+            footnoted.summary_with_citations.as_deref(),
+            Some(
+                "Top insights: Prices fell [1]; Demand rose [2]. Confidence supported by 2 sources.\n\nReferences:\n1. https://example.com/prices\n2. https://example.com/demand"
+            )
+        );
+
+        AnalystTask::new()
+            .with_citation_style(CitationStyle::Chicago)
+            .run(context.clone())
+            .await
+            .expect("analyst task");
+        let chicago: AnalystOutput = context.get("analysis.output").await.unwrap();
+        let cited = chicago.summary_with_citations.expect("cited summary");
+        assert!(cited.ends_with(
+            "Notes:\n1. \"Price Index,\" https://example.com/prices.\n2. https://example.com/demand."
+        ));
+    }
+
+    #[tokio::test]
+    async fn footnotes_follow_each_finding_source_when_some_are_missing() {
+        let retriever = Arc::new(crate::memory::SeedRetriever::new(vec![
+            RetrievedDocument {
+                text: "Unsourced note".to_string(),
+                score: 0.9,
+                source: None,
+            },
+            RetrievedDocument {
+                text: "Prices fell".to_string(),
+                score: 0.8,
+                source: Some("https://example.com/prices".to_string()),
+            },
+        ]));
+        let context = Context::new();
+        context.set("session_id", "s-cite".to_string()).await;
+
+        ResearchTask::new(retriever)
+            .run(context.clone())
+            .await
+            .expect("research task runs");
+        assert_eq!(
+            context
+                .get::<Vec<Option<usize>>>("research.finding_sources")
+                .await,
+            Some(vec![None, Some(0)])
+        );
+
+        AnalystTask::new()
+            .with_citation_style(CitationStyle::Footnote)
+            .run(context.clone())
+            .await
+            .expect("analyst task runs");
+        let output: AnalystOutput = context.get("analysis.output").await.unwrap();
+        assert_eq!(
+            output.summary_with_citations.as_deref(),
+            Some(
+                "Top insights: Unsourced note; Prices fell [1]. Confidence supported by 1 sources.\n\nReferences:\n1. https://example.com/prices"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn fact_check_counts_unique_sources() {
+        let context = Context::new();
+        let analysis = AnalystOutput {
+            summary: "Summary.".to_string(),
+            highlight: String::new(),
+            sources: vec![
+                "https://example.com/a?page=1".to_string(),
+                "https://example.com/a?page=2".to_string(),
+                "https://example.com/b".to_string(),
+            ],
+            ..AnalystOutput::default()
+        };
+        context.set("analysis.output", analysis).await;
+        let task = FactCheckTask::new(FactCheckSettings {
+            min_confidence: 0.6,
+            verification_count: 2,
+            timeout_ms: 0,
+            ..FactCheckSettings::default()
+        });
+
+        task.run(context.clone()).await.expect("fact check runs");
+
+        assert_eq!(
+            context
+                .get::<usize>("factcheck.deduped_sources_count")
+                .await,
+            Some(2)
+        );
+        let verified: Vec<String> = context.get("factcheck.verified_sources").await.unwrap();
+        assert_eq!(
+            verified,
+            vec![
+                "https://example.com/a?page=1".to_string(),
+                "https://example.com/b".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fact_check_skips_blocklisted_domains() {
+        let context = Context::new();
+        let analysis = AnalystOutput {
+            sources: vec![
+                "https://www.content-farm.example/rates".to_string(),
+                "https://example.com/a".to_string(),
+                "http://user@content-farm.example:8080/b".to_string(),
+                "https://example.org/c".to_string(),
+            ],
+            ..AnalystOutput::default()
+        };
+        context.set("analysis.output", analysis).await;
+        let task = FactCheckTask::new(FactCheckSettings {
+            verification_count: 2,
+            timeout_ms: 0,
+            source_blocklist: HashSet::from(["content-farm.example".to_string()]),
+            ..FactCheckSettings::default()
+        });
+
+        task.run(context.clone()).await.expect("fact check runs");
+
+        assert_eq!(
+            context
+                .get::<usize>("factcheck.blocked_sources_count")
+                .await,
+            Some(2)
+        );
+        let verified: Vec<String> = context.get("factcheck.verified_sources").await.unwrap();
+        assert_eq!(
+            verified,
+            vec![
+                "https://example.com/a".to_string(),
+                "https://example.org/c".to_string(),
+            ]
+        );
+        let notes: String = context.get("factcheck.notes").await.unwrap();
+        assert_eq!(notes, "verified 2 sources (coverage 50%)");
+    }
 }
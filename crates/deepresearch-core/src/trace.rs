@@ -1,17 +1,38 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write as _;
 use std::fs::{File, create_dir_all};
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::Stream;
+use tokio_stream::wrappers::ReceiverStream;
+
+const TRACE_STREAM_CAPACITY: usize = 128;
+
+/// Standard tag: exit code reported by the math sandbox.
+pub const TRACE_TAG_SANDBOX_EXIT_CODE: &str = "sandbox_exit_code";
+/// Standard tag: fact-check confidence score in `[0, 1]`.
+pub const TRACE_TAG_CONFIDENCE_SCORE: &str = "confidence_score";
+/// Standard tag: critic verdict (`auto-approved` or `manual review`).
+pub const TRACE_TAG_VERDICT: &str = "verdict";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraceEvent {
     pub task_id: String,
     pub message: String,
     pub timestamp_ms: u128,
+    /// Wall-clock time the task spent before emitting this event, when measured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Cross-cutting annotations (e.g. `confidence_score`) for filtering in the GUI.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, Value>,
 }
 
 impl TraceEvent {
@@ -24,8 +45,25 @@ impl TraceEvent {
             task_id: task_id.into(),
             message: message.into(),
             timestamp_ms,
+            duration_ms: None,
+            tags: HashMap::new(),
         }
     }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration_ms = Some(duration.as_millis().min(u64::MAX as u128) as u64);
+        self
+    }
+
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_tags(mut self, tags: HashMap<String, Value>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -46,6 +84,26 @@ impl TraceCollector {
         self.events.push(TraceEvent::new(task_id, message));
     }
 
+    pub fn record_tagged(
+        &mut self,
+        task_id: impl Into<String>,
+        message: impl Into<String>,
+        tags: HashMap<String, Value>,
+    ) {
+        self.events
+            .push(TraceEvent::new(task_id, message).with_tags(tags));
+    }
+
+    pub fn record_with_duration(
+        &mut self,
+        task_id: impl Into<String>,
+        message: impl Into<String>,
+        duration: Duration,
+    ) {
+        self.events
+            .push(TraceEvent::new(task_id, message).with_duration(duration));
+    }
+
     pub fn extend<I>(&mut self, events: I)
     where
         I: IntoIterator<Item = TraceEvent>,
@@ -68,6 +126,110 @@ impl TraceCollector {
     pub fn summary(&self) -> TraceSummary {
         TraceSummary::from_events(&self.events)
     }
+
+    /// Sub-trace containing only the events emitted by the given tasks.
+    pub fn filter_by_task(&self, task_ids: &[&str]) -> TraceCollector {
+        let events = self
+            .events
+            .iter()
+            .filter(|event| task_ids.contains(&event.task_id.as_str()))
+            .cloned()
+            .collect();
+        Self { events }
+    }
+
+    /// Unique task ids in the order they first appear.
+    pub fn task_ids(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.events
+            .iter()
+            .filter(|event| seen.insert(event.task_id.as_str()))
+            .map(|event| event.task_id.clone())
+            .collect()
+    }
+
+    /// Combine two collectors into one timeline ordered by `timestamp_ms`, dropping events
+    /// that share the same task, message, and timestamp.
+    pub fn merge(self, other: TraceCollector) -> TraceCollector {
+        let mut events = self.events;
+        events.extend(other.events);
+        events.sort_by_key(|event| event.timestamp_ms);
+
+        let mut seen = HashSet::new();
+        events.retain(|event| {
+            seen.insert((
+                event.task_id.clone(),
+                event.message.clone(),
+                event.timestamp_ms,
+            ))
+        });
+        Self { events }
+    }
+
+    /// Re-emit `events` in order, sleeping for the original gap between consecutive
+    /// `timestamp_ms` values divided by `speed` (`2.0` plays twice as fast). A speed that is
+    /// not a positive finite number replays without delays.
+    pub fn replay(events: Vec<TraceEvent>, speed: f64) -> impl Stream<Item = TraceEvent> {
+        let (sender, receiver) = mpsc::channel(1);
+        tokio::spawn(async move {
+            let mut previous: Option<u128> = None;
+            for event in events {
+                if let Some(previous) = previous {
+                    let delay = replay_delay(event.timestamp_ms.saturating_sub(previous), speed);
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+                previous = Some(event.timestamp_ms);
+                if sender.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+        ReceiverStream::new(receiver)
+    }
+}
+
+fn replay_delay(gap_ms: u128, speed: f64) -> Duration {
+    if !speed.is_finite() || speed <= 0.0 {
+        return Duration::ZERO;
+    }
+    Duration::from_secs_f64(gap_ms as f64 / 1000.0 / speed)
+}
+
+/// Live fan-out of a session's trace events that replays history to late subscribers.
+#[derive(Clone)]
+pub struct TraceStream {
+    sender: broadcast::Sender<TraceEvent>,
+    history: Arc<Mutex<Vec<TraceEvent>>>,
+}
+
+impl TraceStream {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(TRACE_STREAM_CAPACITY);
+        Self {
+            sender,
+            history: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn publish(&self, event: TraceEvent) {
+        let mut history = self.history.lock().unwrap_or_else(|err| err.into_inner());
+        history.push(event.clone());
+        let _ = self.sender.send(event);
+    }
+
+    /// Events recorded so far plus a receiver for everything published afterwards.
+    pub fn subscribe(&self) -> (Vec<TraceEvent>, broadcast::Receiver<TraceEvent>) {
+        let history = self.history.lock().unwrap_or_else(|err| err.into_inner());
+        (history.clone(), self.sender.subscribe())
+    }
+}
+
+impl Default for TraceStream {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,6 +237,14 @@ pub struct TraceStep {
     pub index: usize,
     pub task_id: String,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// `TraceEvent::timestamp_ms` of the event this step was built from.
+    #[serde(default)]
+    pub timestamp_ms: u128,
+    /// Originating sub-session when the summary was built from merged traces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub_session: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -91,22 +261,72 @@ impl TraceSummary {
                 index: idx + 1,
                 task_id: event.task_id.clone(),
                 message: event.message.clone(),
+                duration_ms: event.duration_ms.or_else(|| {
+                    events
+                        .get(idx + 1)
+                        .filter(|next| next.timestamp_ms >= event.timestamp_ms)
+                        .map(|next| {
+                            (next.timestamp_ms - event.timestamp_ms).min(u64::MAX as u128) as u64
+                        })
+                }),
+                timestamp_ms: event.timestamp_ms,
+                sub_session: None,
             })
             .collect();
         Self { steps }
     }
 
+    /// Interleave events from labelled sub-sessions (same ordering and de-duplication as
+    /// [`TraceCollector::merge`]) and tag each step with the sub-session it came from.
+    pub fn from_merged_events<'a, I>(sub_sessions: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a [TraceEvent])>,
+    {
+        let mut labelled: Vec<(&str, &TraceEvent)> = sub_sessions
+            .into_iter()
+            .flat_map(|(label, events)| events.iter().map(move |event| (label, event)))
+            .collect();
+        labelled.sort_by_key(|(_, event)| event.timestamp_ms);
+
+        let mut seen = HashSet::new();
+        labelled.retain(|(_, event)| {
+            seen.insert((
+                event.task_id.as_str(),
+                event.message.as_str(),
+                event.timestamp_ms,
+            ))
+        });
+
+        let events: Vec<TraceEvent> = labelled.iter().map(|(_, event)| (*event).clone()).collect();
+        let mut summary = Self::from_events(&events);
+        for (step, (label, _)) in summary.steps.iter_mut().zip(&labelled) {
+            step.sub_session = Some(label.to_string());
+        }
+        summary
+    }
+
     pub fn render_markdown(&self) -> String {
         if self.steps.is_empty() {
             return "No trace events recorded.".to_string();
         }
         let mut output = String::from("### Trace Summary\n");
         for step in &self.steps {
-            let _ = writeln!(
-                output,
-                "{}. {} → {}",
-                step.index, step.task_id, step.message
-            );
+            match &step.sub_session {
+                Some(label) => {
+                    let _ = writeln!(
+                        output,
+                        "{}. [{label}] {} → {}",
+                        step.index, step.task_id, step.message
+                    );
+                }
+                None => {
+                    let _ = writeln!(
+                        output,
+                        "{}. {} → {}",
+                        step.index, step.task_id, step.message
+                    );
+                }
+            }
         }
         output
     }
@@ -155,6 +375,241 @@ impl TraceSummary {
         output.push_str("}\n");
         output
     }
+
+    /// Render a self-contained HTML page with one swimlane per task and a band per step,
+    /// positioned by its offset from the first event and sized by its duration.
+    pub fn render_html(&self, title: &str) -> String {
+        let title = escape_html(title);
+        let mut lanes: Vec<&str> = Vec::new();
+        for step in &self.steps {
+            if !lanes.contains(&step.task_id.as_str()) {
+                lanes.push(&step.task_id);
+            }
+        }
+
+        let start_ms = self
+            .steps
+            .iter()
+            .map(|step| step.timestamp_ms)
+            .min()
+            .unwrap_or(0);
+        let offset_of =
+            |step: &TraceStep| (step.timestamp_ms - start_ms).min(u64::MAX as u128) as u64;
+        let total_ms = self
+            .steps
+            .iter()
+            .map(|step| offset_of(step).saturating_add(step.duration_ms.unwrap_or(0)))
+            .max()
+            .unwrap_or(0)
+            .max(1) as f64;
+
+        let mut output = String::new();
+        let _ = writeln!(
+            output,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>"
+        );
+        output.push_str(HTML_STYLE);
+        let _ = writeln!(output, "</head>\n<body>\n<h1>{title}</h1>");
+
+        if self.steps.is_empty() {
+            output.push_str("<p>No trace events recorded.</p>\n</body>\n</html>\n");
+            return output;
+        }
+
+        output.push_str("<div class=\"timeline\">\n");
+        for (lane_idx, lane) in lanes.iter().enumerate() {
+            let hue = (lane_idx * 67) % 360;
+            let _ = writeln!(
+                output,
+                "<div class=\"lane\"><div class=\"label\">{}</div><div class=\"track\">",
+                escape_html(lane)
+            );
+            for step in self.steps.iter().filter(|step| step.task_id == *lane) {
+                let offset_ms = offset_of(step);
+                let width = (step.duration_ms.unwrap_or(0) as f64 / total_ms * 100.0)
+                    .max(MIN_BAND_WIDTH_PCT);
+                let left = (offset_ms as f64 / total_ms * 100.0).min(100.0 - width);
+                let duration = step
+                    .duration_ms
+                    .map(|ms| format!("{ms} ms"))
+                    .unwrap_or_else(|| "final step".to_string());
+                let _ = writeln!(
+                    output,
+                    "<div class=\"band\" style=\"left:{left:.2}%;width:{width:.2}%;background:hsl({hue},65%,55%)\" \
+                     data-tip=\"#{} {} ({duration}, +{offset_ms} ms): {}\"></div>",
+                    step.index,
+                    escape_html(&step.task_id),
+                    escape_html(&step.message)
+                );
+            }
+            output.push_str("</div></div>\n");
+        }
+        output.push_str("</div>\n<div id=\"tip\"></div>\n");
+        output.push_str(HTML_SCRIPT);
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+
+    /// Render the steps as CSV (`step_index,task_id,message,duration_ms`) for spreadsheet tools.
+    pub fn to_csv(&self) -> String {
+        let mut output = String::from("step_index,task_id,message,duration_ms\n");
+        for step in &self.steps {
+            let duration = step
+                .duration_ms
+                .map(|value| value.to_string())
+                .unwrap_or_default();
+            let _ = writeln!(
+                output,
+                "{},{},{},{}",
+                step.index,
+                escape_csv(&step.task_id),
+                escape_csv(&step.message),
+                duration
+            );
+        }
+        output
+    }
+
+    /// Compare two traces by a longest-common-subsequence over `(task_id, message)`.
+    /// Steps present in both traces but outside the common subsequence are reported as
+    /// reordered rather than as an add/remove pair.
+    pub fn diff(&self, other: &TraceSummary) -> TraceDiff {
+        let key = |step: &TraceStep| (step.task_id.clone(), step.message.clone());
+        let ours: Vec<_> = self.steps.iter().map(key).collect();
+        let theirs: Vec<_> = other.steps.iter().map(key).collect();
+
+        // lcs[i][j] = LCS length of ours[i..] and theirs[j..].
+        let mut lcs = vec![vec![0usize; theirs.len() + 1]; ours.len() + 1];
+        for i in (0..ours.len()).rev() {
+            for j in (0..theirs.len()).rev() {
+                lcs[i][j] = if ours[i] == theirs[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut removed = Vec::new();
+        let mut added = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < ours.len() && j < theirs.len() {
+            if ours[i] == theirs[j] {
+                i += 1;
+                j += 1;
+            } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                removed.push(self.steps[i].clone());
+                i += 1;
+            } else {
+                added.push(other.steps[j].clone());
+                j += 1;
+            }
+        }
+        removed.extend(self.steps[i..].iter().cloned());
+        added.extend(other.steps[j..].iter().cloned());
+
+        let mut reordered = Vec::new();
+        removed.retain(|step: &TraceStep| {
+            let position = added.iter().position(|candidate| {
+                candidate.task_id == step.task_id && candidate.message == step.message
+            });
+            match position {
+                Some(position) => {
+                    let moved = added.remove(position);
+                    reordered.push((step.index, moved.index, step.task_id.clone()));
+                    false
+                }
+                None => true,
+            }
+        });
+
+        TraceDiff {
+            added,
+            removed,
+            reordered,
+        }
+    }
+}
+
+/// Step-level differences between two traces; indices refer to `TraceStep::index`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TraceDiff {
+    /// Steps only present in the other trace.
+    pub added: Vec<TraceStep>,
+    /// Steps only present in this trace.
+    pub removed: Vec<TraceStep>,
+    /// `(index in this trace, index in the other trace, task_id)` for steps that moved.
+    pub reordered: Vec<(usize, usize, String)>,
+}
+
+impl TraceDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.reordered.is_empty()
+    }
+
+    pub fn render_markdown(&self) -> String {
+        if self.is_empty() {
+            return "No differences between traces.".to_string();
+        }
+        let mut output = String::from("### Trace Diff\n");
+        for (title, steps, marker) in [("Removed", &self.removed, '-'), ("Added", &self.added, '+')]
+        {
+            if steps.is_empty() {
+                continue;
+            }
+            let _ = writeln!(output, "\n#### {title}");
+            for step in steps {
+                let _ = writeln!(
+                    output,
+                    "{marker} {}. {} → {}",
+                    step.index, step.task_id, step.message
+                );
+            }
+        }
+        if !self.reordered.is_empty() {
+            output.push_str("\n#### Reordered\n");
+            for (from, to, task_id) in &self.reordered {
+                let _ = writeln!(output, "~ {task_id}: step {from} → step {to}");
+            }
+        }
+        output
+    }
+}
+
+const MIN_BAND_WIDTH_PCT: f64 = 1.0;
+
+const HTML_STYLE: &str = "<style>
+body { font-family: system-ui, sans-serif; margin: 2rem; color: #222; }
+.timeline { border-left: 1px solid #ccc; }
+.lane { display: flex; align-items: center; height: 2.25rem; border-bottom: 1px solid #eee; }
+.label { width: 10rem; padding-right: 0.75rem; text-align: right; font-size: 0.85rem; }
+.track { position: relative; flex: 1; height: 1.25rem; }
+.band { position: absolute; top: 0; height: 100%; border-radius: 3px; cursor: pointer; }
+#tip { position: fixed; display: none; max-width: 28rem; padding: 0.4rem 0.6rem; background: #222; \
+color: #fff; font-size: 0.8rem; border-radius: 4px; pointer-events: none; }
+</style>
+";
+
+const HTML_SCRIPT: &str = "<script>
+const tip = document.getElementById('tip');
+document.querySelectorAll('.band').forEach((band) => {
+  band.addEventListener('mousemove', (event) => {
+    tip.textContent = band.dataset.tip;
+    tip.style.display = 'block';
+    tip.style.left = event.clientX + 12 + 'px';
+    tip.style.top = event.clientY + 12 + 'px';
+  });
+  band.addEventListener('mouseleave', () => { tip.style.display = 'none'; });
+});
+</script>
+";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
 }
 
 fn sanitize_mermaid(text: &str) -> String {
@@ -169,19 +624,172 @@ fn escape_graphviz(text: &str) -> String {
     text.replace('"', "\\\"").replace('\n', " ")
 }
 
+fn escape_csv(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Destination for a session's persisted trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceOutput {
+    /// Append to `<dir>/<session_id>.json` on the local filesystem.
+    LocalFile(PathBuf),
+    /// Upload to `<key_prefix>/<session_id>.json` in an S3-compatible bucket (requires the
+    /// `s3-traces` feature). `endpoint` targets non-AWS stores such as MinIO.
+    S3 {
+        bucket: String,
+        key_prefix: String,
+        endpoint: Option<String>,
+    },
+}
+
+/// Object key for a session's trace under `key_prefix`.
+pub fn trace_object_key(key_prefix: &str, session_id: &str) -> String {
+    let prefix = key_prefix.trim_matches('/');
+    if prefix.is_empty() {
+        format!("{session_id}.json")
+    } else {
+        format!("{prefix}/{session_id}.json")
+    }
+}
+
+/// Upload `events` to `s3://<bucket>/<key_prefix>/<session_id>.json`, replacing any previous
+/// object, and return the object URI. Credentials and region come from the standard AWS
+/// environment/profile chain.
+#[cfg(feature = "s3-traces")]
+pub async fn persist_trace_s3(
+    bucket: &str,
+    key_prefix: &str,
+    endpoint: Option<&str>,
+    session_id: &str,
+    events: &[TraceEvent],
+) -> Result<String> {
+    let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+    if let Some(endpoint) = endpoint {
+        loader = loader.endpoint_url(endpoint);
+    }
+    let shared = loader.load().await;
+    // Custom endpoints (MinIO, Ceph) rarely support virtual-hosted bucket addressing.
+    let config = aws_sdk_s3::config::Builder::from(&shared)
+        .force_path_style(endpoint.is_some())
+        .build();
+    let client = aws_sdk_s3::Client::from_conf(config);
+
+    let key = trace_object_key(key_prefix, session_id);
+    let body = serde_json::to_vec_pretty(events).context("failed to serialize trace")?;
+    client
+        .put_object()
+        .bucket(bucket)
+        .key(&key)
+        .content_type("application/json")
+        .body(aws_sdk_s3::primitives::ByteStream::from(body))
+        .send()
+        .await
+        .with_context(|| format!("failed to upload trace to s3://{bucket}/{key}"))?;
+    Ok(format!("s3://{bucket}/{key}"))
+}
+
+/// How [`persist_trace_with_options`] folds new events into an existing trace file.
+#[derive(Debug, Clone, Copy)]
+pub struct AppendTraceOptions {
+    /// Drop events whose `(task_id, message, timestamp_ms)` already appears in the file,
+    /// the same key [`TraceCollector::merge`] uses.
+    pub deduplicate: bool,
+}
+
+impl Default for AppendTraceOptions {
+    fn default() -> Self {
+        Self { deduplicate: true }
+    }
+}
+
+/// Append `events` to `<dir>/<session_id>.json`, keeping events recorded by earlier runs.
 pub fn persist_trace<P: AsRef<Path>>(
     dir: P,
     session_id: &str,
     events: &[TraceEvent],
+) -> Result<PathBuf> {
+    persist_trace_with_options(dir, session_id, events, AppendTraceOptions::default())
+}
+
+pub fn persist_trace_with_options<P: AsRef<Path>>(
+    dir: P,
+    session_id: &str,
+    events: &[TraceEvent],
+    options: AppendTraceOptions,
 ) -> Result<PathBuf> {
     let dir = dir.as_ref();
+    let path = dir.join(format!("{session_id}.json"));
+
+    let mut merged: Vec<TraceEvent> = if path.exists() {
+        let existing = std::fs::read(&path)
+            .with_context(|| format!("failed to read trace file {}", path.display()))?;
+        serde_json::from_slice(&existing)
+            .with_context(|| format!("failed to parse trace file {}", path.display()))?
+    } else {
+        Vec::new()
+    };
+    merged.extend(events.iter().cloned());
+    merged.sort_by_key(|event| event.timestamp_ms);
+    if options.deduplicate {
+        let mut seen = HashSet::new();
+        merged.retain(|event| {
+            seen.insert((
+                event.task_id.clone(),
+                event.message.clone(),
+                event.timestamp_ms,
+            ))
+        });
+    }
+
+    write_trace_atomically(dir, &path, &merged)?;
+    Ok(path)
+}
+
+/// Overwrite `<dir>/<session_id>.json` with exactly `events`, discarding any previous trace.
+pub fn persist_trace_replace<P: AsRef<Path>>(
+    dir: P,
+    session_id: &str,
+    events: &[TraceEvent],
+) -> Result<PathBuf> {
+    let dir = dir.as_ref();
+    let path = dir.join(format!("{session_id}.json"));
+    write_trace_atomically(dir, &path, events)?;
+    Ok(path)
+}
+
+fn write_trace_atomically(dir: &Path, path: &Path, events: &[TraceEvent]) -> Result<()> {
     create_dir_all(dir)
         .with_context(|| format!("failed to create trace directory {}", dir.display()))?;
-    let path = dir.join(format!("{session_id}.json"));
     let payload = serde_json::to_vec_pretty(events)?;
+    let tmp_path = path.with_extension("json.tmp");
+    let mut file = File::create(&tmp_path)
+        .with_context(|| format!("failed to create trace file {}", tmp_path.display()))?;
+    file.write_all(&payload)
+        .with_context(|| format!("failed to write trace file {}", tmp_path.display()))?;
+    file.sync_all()
+        .with_context(|| format!("failed to flush trace file {}", tmp_path.display()))?;
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("failed to move trace file into {}", path.display()))?;
+    Ok(())
+}
+
+/// Persist the CSV rendering of a trace summary as `<session_id>.csv`.
+pub fn persist_trace_csv<P: AsRef<Path>>(
+    dir: P,
+    session_id: &str,
+    summary: &TraceSummary,
+) -> Result<PathBuf> {
+    let dir = dir.as_ref();
+    create_dir_all(dir)
+        .with_context(|| format!("failed to create trace directory {}", dir.display()))?;
+    let path = dir.join(format!("{session_id}.csv"));
     let mut file = File::create(&path)
         .with_context(|| format!("failed to create trace file {}", path.display()))?;
-    file.write_all(&payload)
+    file.write_all(summary.to_csv().as_bytes())
         .with_context(|| format!("failed to write trace file {}", path.display()))?;
     Ok(path)
 }
@@ -190,6 +798,52 @@ pub fn persist_trace<P: AsRef<Path>>(
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn trace_stream_replays_history_then_forwards_live_events() {
+        let stream = TraceStream::new();
+        stream.publish(TraceEvent::new("researcher", "captured findings"));
+
+        let (history, mut receiver) = stream.subscribe();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].task_id, "researcher");
+
+        stream.publish(TraceEvent::new("analyst", "synthesised"));
+        let live = receiver.recv().await.expect("live event");
+        assert_eq!(live.task_id, "analyst");
+    }
+
+    #[tokio::test]
+    async fn replay_preserves_order_and_scales_gaps() {
+        use tokio_stream::StreamExt;
+
+        let mut first = TraceEvent::new("researcher", "captured findings");
+        first.timestamp_ms = 1_000;
+        let mut second = TraceEvent::new("analyst", "synthesised");
+        second.timestamp_ms = 2_000;
+
+        let start = std::time::Instant::now();
+        let replayed: Vec<TraceEvent> = TraceCollector::replay(vec![first, second], 50.0)
+            .collect()
+            .await;
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        let tasks: Vec<&str> = replayed.iter().map(|e| e.task_id.as_str()).collect();
+        assert_eq!(tasks, vec!["researcher", "analyst"]);
+
+        assert_eq!(replay_delay(1_000, 2.0), Duration::from_millis(500));
+        assert_eq!(replay_delay(1_000, 0.0), Duration::ZERO);
+        assert_eq!(replay_delay(1_000, f64::NAN), Duration::ZERO);
+    }
+
+    #[test]
+    fn trace_object_key_joins_prefix_and_session() {
+        assert_eq!(trace_object_key("", "s1"), "s1.json");
+        assert_eq!(trace_object_key("traces/", "s1"), "traces/s1.json");
+        assert_eq!(
+            trace_object_key("/prod/traces", "s1"),
+            "prod/traces/s1.json"
+        );
+    }
+
     #[test]
     fn markdown_renders_steps() {
         let mut collector = TraceCollector::new();
@@ -220,4 +874,249 @@ mod tests {
         assert!(graphviz.contains("digraph Trace"));
         assert!(graphviz.contains("step1"));
     }
+
+    #[test]
+    fn html_renders_swimlanes_with_escaped_tooltips() {
+        let mut first = TraceEvent::new("researcher", "captured <2> findings");
+        first.timestamp_ms = 1_000;
+        let mut second = TraceEvent::new("analyst", "synthesised");
+        second.timestamp_ms = 1_300;
+        let mut third = TraceEvent::new("researcher", "follow-up");
+        third.timestamp_ms = 1_400;
+        let summary = TraceSummary::from_events(&[first, second, third]);
+
+        let html = summary.render_html("Session <demo>");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Session &lt;demo&gt;</title>"));
+        assert_eq!(html.matches("class=\"lane\"").count(), 2);
+        assert_eq!(html.matches("class=\"band\"").count(), 3);
+        assert!(html.contains("left:0.00%;width:75.00%"));
+        assert!(html.contains("left:75.00%;width:25.00%"));
+        assert!(html.contains("captured &lt;2&gt; findings"));
+        assert!(!html.contains("http"));
+    }
+
+    #[test]
+    fn html_positions_bands_by_event_timestamp() {
+        let mut first = TraceEvent::new("researcher", "collected");
+        first.timestamp_ms = 1_000;
+        first.duration_ms = Some(100);
+        let mut second = TraceEvent::new("analyst", "after a pause");
+        second.timestamp_ms = 1_500;
+        second.duration_ms = Some(100);
+        let mut third = TraceEvent::new("critic", "done");
+        third.timestamp_ms = 2_000;
+        let summary = TraceSummary::from_events(&[first, second, third]);
+
+        let html = summary.render_html("Gaps");
+        assert!(html.contains("left:0.00%;width:10.00%"));
+        assert!(html.contains("left:50.00%;width:10.00%"));
+        assert!(html.contains("+500 ms"));
+    }
+
+    #[test]
+    fn merge_orders_by_timestamp_and_drops_duplicates() {
+        let event = |task: &str, message: &str, ts: u128| TraceEvent {
+            task_id: task.to_string(),
+            message: message.to_string(),
+            timestamp_ms: ts,
+            duration_ms: None,
+            tags: HashMap::new(),
+        };
+        let left = TraceCollector::from_events(vec![
+            event("researcher", "source a", 10),
+            event("analyst", "shared", 30),
+        ]);
+        let right = TraceCollector::from_events(vec![
+            event("researcher", "source b", 20),
+            event("analyst", "shared", 30),
+        ]);
+
+        let merged = left.merge(right);
+        let messages: Vec<&str> = merged
+            .events()
+            .iter()
+            .map(|event| event.message.as_str())
+            .collect();
+        assert_eq!(messages, vec!["source a", "source b", "shared"]);
+
+        let first = [event("researcher", "source a", 10)];
+        let second = [event("researcher", "source b", 20)];
+        let summary =
+            TraceSummary::from_merged_events([("web", &first[..]), ("papers", &second[..])]);
+        assert_eq!(summary.steps[0].sub_session.as_deref(), Some("web"));
+        assert_eq!(summary.steps[1].sub_session.as_deref(), Some("papers"));
+        assert_eq!(summary.steps[0].duration_ms, Some(10));
+        assert!(summary.render_markdown().contains("1. [web] researcher"));
+    }
+
+    #[test]
+    fn tagged_events_round_trip_and_default_to_empty() {
+        let mut collector = TraceCollector::new();
+        collector.record_tagged(
+            "critic",
+            "verdict: manual review",
+            HashMap::from([(TRACE_TAG_VERDICT.to_string(), Value::from("manual review"))]),
+        );
+        let json = serde_json::to_string(collector.events()).unwrap();
+        let restored: Vec<TraceEvent> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored[0].tags[TRACE_TAG_VERDICT], "manual review");
+
+        let legacy: TraceEvent =
+            serde_json::from_str(r#"{"task_id":"analyst","message":"m","timestamp_ms":1}"#)
+                .unwrap();
+        assert!(legacy.tags.is_empty());
+        assert!(!serde_json::to_string(&legacy).unwrap().contains("tags"));
+    }
+
+    #[test]
+    fn filter_by_task_keeps_matching_events() {
+        let mut collector = TraceCollector::new();
+        collector.record("researcher", "source a");
+        collector.record("analyst", "highlight");
+        collector.record("researcher", "source b");
+        collector.record("critic", "verdict");
+
+        assert_eq!(
+            collector.task_ids(),
+            vec!["researcher", "analyst", "critic"]
+        );
+
+        let filtered = collector.filter_by_task(&["researcher", "critic"]);
+        let messages: Vec<&str> = filtered
+            .events()
+            .iter()
+            .map(|event| event.message.as_str())
+            .collect();
+        assert_eq!(messages, vec!["source a", "source b", "verdict"]);
+        assert!(collector.filter_by_task(&["missing"]).is_empty());
+    }
+
+    #[test]
+    fn summary_prefers_recorded_durations() {
+        let mut collector = TraceCollector::new();
+        collector.record_with_duration(
+            "researcher",
+            "captured findings",
+            Duration::from_millis(150),
+        );
+        collector.record_with_duration("researcher", "follow-up", Duration::from_millis(40));
+        collector.record("analyst", "highlight");
+
+        let events = collector.events();
+        assert_eq!(events[0].duration_ms, Some(150));
+
+        let summary = collector.summary();
+        assert_eq!(summary.steps[0].duration_ms, Some(150));
+        assert_eq!(summary.steps[1].duration_ms, Some(40));
+
+        let json = serde_json::to_string(&events[2]).unwrap();
+        assert!(!json.contains("duration_ms"));
+    }
+
+    #[test]
+    fn persist_trace_appends_and_deduplicates() {
+        let dir = tempfile::TempDir::new().expect("temp dir");
+        let event = |task: &str, ts: u128| TraceEvent {
+            task_id: task.to_string(),
+            message: format!("{task} at {ts}"),
+            timestamp_ms: ts,
+            duration_ms: None,
+            tags: HashMap::new(),
+        };
+
+        persist_trace(
+            dir.path(),
+            "s1",
+            &[event("researcher", 10), event("analyst", 20)],
+        )
+        .expect("initial trace");
+        let path = persist_trace(
+            dir.path(),
+            "s1",
+            &[event("analyst", 20), event("critic", 30)],
+        )
+        .expect("appended trace");
+
+        let stored: Vec<TraceEvent> =
+            serde_json::from_slice(&std::fs::read(&path).expect("read")).expect("parse");
+        let tasks: Vec<&str> = stored.iter().map(|e| e.task_id.as_str()).collect();
+        assert_eq!(tasks, vec!["researcher", "analyst", "critic"]);
+        assert!(!path.with_extension("json.tmp").exists());
+
+        let mut same_millisecond = event("critic", 30);
+        same_millisecond.message = "second verdict".to_string();
+        persist_trace(dir.path(), "s1", &[same_millisecond]).expect("same-ms trace");
+        let stored: Vec<TraceEvent> =
+            serde_json::from_slice(&std::fs::read(&path).expect("read")).expect("parse");
+        assert_eq!(stored.len(), 4);
+
+        persist_trace_replace(dir.path(), "s1", &[event("finalize", 40)]).expect("replace");
+        let stored: Vec<TraceEvent> =
+            serde_json::from_slice(&std::fs::read(&path).expect("read")).expect("parse");
+        assert_eq!(stored.len(), 1);
+    }
+
+    #[test]
+    fn csv_quotes_fields_and_reports_durations() {
+        let mut first = TraceEvent::new("researcher", "captured 2 findings, 1 source");
+        first.timestamp_ms = 1_000;
+        let mut second = TraceEvent::new("critic", "verdict: \"auto\"");
+        second.timestamp_ms = 1_250;
+        let summary = TraceSummary::from_events(&[first, second]);
+
+        let csv = summary.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "step_index,task_id,message,duration_ms");
+        assert_eq!(
+            lines[1],
+            "1,researcher,\"captured 2 findings, 1 source\",250"
+        );
+        assert_eq!(lines[2], "2,critic,\"verdict: \"\"auto\"\"\",");
+    }
+
+    fn summary(steps: &[(&str, &str)]) -> TraceSummary {
+        let events: Vec<TraceEvent> = steps
+            .iter()
+            .map(|(task, message)| TraceEvent::new(*task, *message))
+            .collect();
+        TraceSummary::from_events(&events)
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_reordered_steps() {
+        let baseline = summary(&[
+            ("researcher", "captured findings"),
+            ("math", "sandbox ok"),
+            ("analyst", "synthesised"),
+            ("critic", "verdict: auto"),
+        ]);
+        let candidate = summary(&[
+            ("researcher", "captured findings"),
+            ("analyst", "synthesised"),
+            ("math", "sandbox ok"),
+            ("critic", "verdict: manual"),
+            ("manual_review", "manual review requested"),
+        ]);
+
+        let diff = baseline.diff(&candidate);
+        let removed: Vec<_> = diff.removed.iter().map(|s| s.message.as_str()).collect();
+        let added: Vec<_> = diff.added.iter().map(|s| s.message.as_str()).collect();
+        assert_eq!(removed, vec!["verdict: auto"]);
+        assert_eq!(added, vec!["verdict: manual", "manual review requested"]);
+        assert_eq!(diff.reordered, vec![(2, 3, "math".to_string())]);
+
+        let markdown = diff.render_markdown();
+        assert!(markdown.contains("- 4. critic → verdict: auto"));
+        assert!(markdown.contains("+ 5. manual_review → manual review requested"));
+        assert!(markdown.contains("~ math: step 2 → step 3"));
+    }
+
+    #[test]
+    fn diff_of_identical_traces_is_empty() {
+        let trace = summary(&[("researcher", "captured findings"), ("critic", "ok")]);
+        let diff = trace.diff(&trace.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.render_markdown(), "No differences between traces.");
+    }
 }
@@ -1,29 +1,44 @@
+use crate::error::DeepResearchError;
 use crate::logging::{SessionLogInput, log_session_completion};
+#[cfg(feature = "sqlite-retriever")]
+use crate::memory::SqliteRetriever;
 #[cfg(feature = "qdrant-retriever")]
 use crate::memory::qdrant::{HybridRetriever, QdrantConfig};
-use crate::memory::{DynRetriever, IngestDocument, StubRetriever};
+use crate::memory::{
+    CollectionInfo, DynRetriever, IngestDocument, RetrievedDocument, SeedRetriever, StubRetriever,
+};
 use crate::pipeline;
-use crate::sandbox::SandboxExecutor;
+use crate::sandbox::{SandboxChoice, SandboxExecutor};
 use crate::tasks::{
-    AnalystOutput, AnalystTask, CriticTask, FactCheckSettings, FactCheckTask, FinalizeTask,
-    ManualReviewTask, MathToolTask, ResearchTask,
+    AnalystOutput, AnalystTask, CriticScorer, CriticTask, FactCheckSettings, FactCheckTask,
+    FinalizeTask, ManualReviewTask, ManualReviewWebhook, MathToolTask, ResearchTask, ResultScorer,
+    SourceReference, SummaryTemplate, record_trace,
+};
+use crate::trace::{
+    TraceCollector, TraceEvent, TraceOutput, TraceStream, TraceSummary, persist_trace,
 };
-use crate::trace::{TraceCollector, TraceEvent, TraceSummary, persist_trace};
 use anyhow::{Result, anyhow};
+use async_trait::async_trait;
 use graph_flow::{
-    ExecutionStatus, FlowRunner, GraphBuilder, InMemorySessionStorage, Session, SessionStorage,
-    Task,
+    Context, ExecutionStatus, FlowRunner, GraphBuilder, InMemorySessionStorage, NextAction,
+    Session, SessionStorage, Task, TaskResult,
 };
 use serde_json::Value;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tracing::warn;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, info, warn};
 use uuid::Uuid;
 
 #[cfg(feature = "postgres-session")]
 use graph_flow::storage_postgres::PostgresSessionStorage;
 
 const DEFAULT_TRACE_DIR: &str = "data/traces";
+const CONTEXT7_PREFIX: &str = "use context7";
+const DISABLE_CONTEXT7_ENV: &str = "DEEPRESEARCH_DISABLE_CONTEXT7_PREFIX";
+const SESSION_CREATED_AT_KEY: &str = "session.created_at";
 
 /// Bundle of the default tasks used in the DeepResearch workflow.
 #[derive(Clone)]
@@ -37,20 +52,54 @@ pub struct BaseGraphTasks {
     pub manual_review: Arc<ManualReviewTask>,
 }
 
+/// Per-task customisations applied when assembling the base graph.
+#[derive(Clone, Default)]
+struct TaskOverrides {
+    critic_scorer: Option<CriticScorer>,
+    analyst_template: Option<SummaryTemplate>,
+    manual_review_webhook: Option<ManualReviewWebhook>,
+    result_scorer: Option<ResultScorer>,
+    max_tokens_per_finding: Option<usize>,
+    critic_minimum_sources: Option<usize>,
+}
+
 impl BaseGraphTasks {
     fn new(
         retriever: DynRetriever,
         fact_settings: FactCheckSettings,
         math: Option<Arc<MathToolTask>>,
+        overrides: TaskOverrides,
     ) -> Self {
+        let mut critic = match overrides.critic_scorer {
+            Some(scorer) => CriticTask::new().with_custom_scoring(scorer),
+            None => CriticTask::new(),
+        };
+        if let Some(count) = overrides.critic_minimum_sources {
+            critic = critic.with_minimum_source_count(count);
+        }
+        let analyst = match overrides.analyst_template {
+            Some(template) => AnalystTask::new().with_summary_template(template),
+            None => AnalystTask::new(),
+        };
+        let manual_review = match overrides.manual_review_webhook {
+            Some(webhook) => ManualReviewTask::new().with_webhook(webhook.url, webhook.headers),
+            None => ManualReviewTask::new(),
+        };
+        let mut research = match overrides.result_scorer {
+            Some(scorer) => ResearchTask::new(retriever).with_result_scorer(scorer),
+            None => ResearchTask::new(retriever),
+        };
+        if let Some(limit) = overrides.max_tokens_per_finding {
+            research = research.with_max_tokens_per_finding(limit);
+        }
         Self {
-            research: Arc::new(ResearchTask::new(retriever)),
+            research: Arc::new(research),
             math,
-            analyst: Arc::new(AnalystTask),
+            analyst: Arc::new(analyst),
             fact_check: Arc::new(FactCheckTask::new(fact_settings)),
-            critic: Arc::new(CriticTask),
-            finalize: Arc::new(FinalizeTask),
-            manual_review: Arc::new(ManualReviewTask),
+            critic: Arc::new(critic),
+            finalize: Arc::new(FinalizeTask::new()),
+            manual_review: Arc::new(manual_review),
         }
     }
 }
@@ -67,6 +116,14 @@ pub struct SessionOutcome {
     pub factcheck_passed: Option<bool>,
     pub factcheck_verified_sources: Vec<String>,
     pub critic_confident: Option<bool>,
+    /// Unique sources cited by the analyst, in first-seen order.
+    pub sources: Vec<String>,
+    /// Structured provenance (URL, title, retrieval score) for the analyst's sources.
+    pub source_references: Vec<SourceReference>,
+    /// Set when the session was aborted through its cancellation token; `summary` is empty.
+    pub cancelled: bool,
+    /// Set once `final.summary` is recorded (by `FinalizeTask` or `ManualReviewTask`).
+    pub completed: bool,
 }
 
 impl SessionOutcome {
@@ -93,12 +150,66 @@ impl SessionOutcome {
             Some(self.trace_summary.render_graphviz())
         }
     }
+
+    pub fn explain_html(&self) -> Option<String> {
+        if self.trace_events.is_empty() {
+            None
+        } else {
+            Some(
+                self.trace_summary
+                    .render_html(&format!("Trace {}", self.session_id)),
+            )
+        }
+    }
+
+    /// Raw trace events as pretty-printed JSON, for dashboards that consume traces directly.
+    pub fn explain_json(&self) -> Option<String> {
+        if self.trace_events.is_empty() {
+            None
+        } else {
+            serde_json::to_string_pretty(&self.trace_events).ok()
+        }
+    }
+}
+
+/// Upload the trace to S3, returning its `s3://` URI; failures only log a warning.
+#[cfg(feature = "s3-traces")]
+async fn upload_trace(
+    bucket: &str,
+    key_prefix: &str,
+    endpoint: Option<&str>,
+    session_id: &str,
+    events: &[TraceEvent],
+) -> Option<String> {
+    match crate::trace::persist_trace_s3(bucket, key_prefix, endpoint, session_id, events).await {
+        Ok(uri) => Some(uri),
+        Err(err) => {
+            warn!(%session_id, error = %format!("{err:#}"), "failed to upload trace to S3");
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "s3-traces"))]
+async fn upload_trace(
+    bucket: &str,
+    _key_prefix: &str,
+    _endpoint: Option<&str>,
+    session_id: &str,
+    _events: &[TraceEvent],
+) -> Option<String> {
+    warn!(
+        %session_id,
+        bucket,
+        "S3 trace output requested but deepresearch-core was built without `s3-traces`"
+    );
+    None
 }
 
-fn build_outcome(
+async fn build_outcome(
     session: &Session,
     session_id: &str,
-    trace_output_dir: Option<&PathBuf>,
+    trace_output: Option<&TraceOutput>,
 ) -> Result<SessionOutcome> {
     let summary = extract_final_summary(session);
 
@@ -106,31 +217,53 @@ fn build_outcome(
         .context
         .get_sync::<bool>("trace.enabled")
         .unwrap_or(false);
+    // Sessions stored before `trace.persist` existed persisted whenever tracing was on.
+    let trace_persist = session
+        .context
+        .get_sync::<bool>("trace.persist")
+        .unwrap_or(trace_enabled);
 
-    let collector = session
+    let completed = session
         .context
-        .get_sync::<TraceCollector>("trace.collector")
-        .unwrap_or_else(|| {
-            let legacy: Vec<TraceEvent> =
-                session.context.get_sync("trace.events").unwrap_or_default();
-            TraceCollector::from_events(legacy)
-        });
+        .get_sync::<String>("final.summary")
+        .is_some();
 
-    let events = collector.into_events();
+    let events = stored_trace_events(session);
     let trace_summary = TraceSummary::from_events(&events);
 
+    // Only finished sessions are written out; polling a running one must stay read-only.
     let mut trace_path = None;
-    if trace_enabled && !events.is_empty() {
-        let dir = trace_output_dir
-            .cloned()
-            .unwrap_or_else(|| PathBuf::from(DEFAULT_TRACE_DIR));
-        match persist_trace(&dir, session_id, &events) {
-            Ok(path) => trace_path = Some(path),
-            Err(err) => warn!(%session_id, error = %err, "failed to persist trace to disk"),
+    let mut trace_uri = None;
+    if completed && trace_persist && !events.is_empty() {
+        match trace_output {
+            Some(TraceOutput::S3 {
+                bucket,
+                key_prefix,
+                endpoint,
+            }) => {
+                trace_uri =
+                    upload_trace(bucket, key_prefix, endpoint.as_deref(), session_id, &events)
+                        .await;
+            }
+            local => {
+                let dir = match local {
+                    Some(TraceOutput::LocalFile(dir)) => dir.clone(),
+                    _ => PathBuf::from(DEFAULT_TRACE_DIR),
+                };
+                match persist_trace(&dir, session_id, &events) {
+                    Ok(path) => trace_path = Some(path),
+                    Err(err) => {
+                        warn!(%session_id, error = %err, "failed to persist trace to disk")
+                    }
+                }
+            }
         }
     }
 
-    let trace_path_str = trace_path.as_ref().map(|path| path.display().to_string());
+    let trace_path_str = trace_path
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .or(trace_uri);
     let query = session.context.get_sync::<String>("query");
     let verdict = session.context.get_sync::<String>("critique.verdict");
     let requires_manual = session
@@ -144,21 +277,25 @@ fn build_outcome(
         .get_sync::<Vec<String>>("factcheck.verified_sources")
         .unwrap_or_default();
     let critic_confident = session.context.get_sync::<bool>("critique.confident");
-    let sources = session
+    let analysis = session
         .context
         .get_sync::<AnalystOutput>("analysis.output")
-        .unwrap_or_default()
-        .sources;
+        .unwrap_or_default();
+    let sources = dedup_sources(analysis.sources);
+    let source_references = analysis.source_references;
 
-    if let Err(err) = log_session_completion(SessionLogInput {
-        session_id: session_id.to_string(),
-        query,
-        summary: summary.clone(),
-        verdict,
-        requires_manual,
-        sources,
-        trace_path: trace_path_str.clone(),
-    }) {
+    // Loading a session that is still running (e.g. `explain --watch`) must not log it as done.
+    if completed
+        && let Err(err) = log_session_completion(SessionLogInput {
+            session_id: session_id.to_string(),
+            query,
+            summary: summary.clone(),
+            verdict,
+            requires_manual,
+            sources: sources.clone(),
+            trace_path: trace_path_str.clone(),
+        })
+    {
         warn!(%session_id, error = %err, "failed to record session log");
     }
 
@@ -173,17 +310,349 @@ fn build_outcome(
         factcheck_passed,
         factcheck_verified_sources,
         critic_confident,
+        sources,
+        source_references,
+        cancelled: false,
+        completed,
     };
 
-    pipeline::persist_session_record(session, &outcome);
+    if completed {
+        pipeline::persist_session_record(session, &outcome);
+    }
 
     Ok(outcome)
 }
 
+fn cancelled_outcome(session_id: &str) -> SessionOutcome {
+    info!(%session_id, "session cancelled");
+    SessionOutcome {
+        session_id: session_id.to_string(),
+        summary: String::new(),
+        trace_events: Vec::new(),
+        trace_summary: TraceSummary::default(),
+        trace_path: None,
+        requires_manual: false,
+        factcheck_confidence: None,
+        factcheck_passed: None,
+        factcheck_verified_sources: Vec::new(),
+        critic_confident: None,
+        sources: Vec::new(),
+        source_references: Vec::new(),
+        cancelled: true,
+        completed: false,
+    }
+}
+
+/// Walk the graph from its start task the way `FlowRunner` would, once per outcome of the
+/// critic's `critique.confident` branch, and return the visited task ids in topological order.
+fn validate_graph(
+    graph: &graph_flow::Graph,
+    tasks: &BaseGraphTasks,
+    context: &Context,
+) -> Result<Vec<String>> {
+    let start = graph
+        .start_task_id()
+        .ok_or_else(|| DeepResearchError::PlanningError("graph has no start task".into()))?;
+    if graph.get_task(&start).is_none() {
+        return Err(DeepResearchError::PlanningError(format!(
+            "start task '{start}' is not registered"
+        ))
+        .into());
+    }
+
+    let mut visited: Vec<String> = vec![start.clone()];
+    let mut edges: Vec<(String, String)> = Vec::new();
+    for confident in [true, false] {
+        // Probe a detached copy so branch evaluation never leaks into the real context.
+        let probe: Context = serde_json::from_value(serde_json::to_value(context)?)?;
+        probe.set_sync("critique.confident", confident);
+
+        let mut path = vec![start.clone()];
+        let mut current = start.clone();
+        while let Some(next) = graph.find_next_task(&current, &probe) {
+            if graph.get_task(&next).is_none() {
+                return Err(DeepResearchError::PlanningError(format!(
+                    "edge '{current}' -> '{next}' targets an unregistered task"
+                ))
+                .into());
+            }
+            if path.contains(&next) {
+                path.push(next);
+                return Err(DeepResearchError::PlanningError(format!(
+                    "cycle detected: {}",
+                    path.join(" -> ")
+                ))
+                .into());
+            }
+            if !edges.contains(&(current.clone(), next.clone())) {
+                edges.push((current.clone(), next.clone()));
+            }
+            if !visited.contains(&next) {
+                visited.push(next.clone());
+            }
+            path.push(next.clone());
+            current = next;
+        }
+    }
+
+    let mut expected = vec![
+        tasks.research.id(),
+        tasks.analyst.id(),
+        tasks.fact_check.id(),
+        tasks.critic.id(),
+        tasks.finalize.id(),
+        tasks.manual_review.id(),
+    ];
+    if let Some(math) = &tasks.math {
+        expected.push(math.id());
+    }
+    let unreachable: Vec<&str> = expected
+        .into_iter()
+        .filter(|id| !visited.iter().any(|seen| seen == id))
+        .collect();
+    if !unreachable.is_empty() {
+        return Err(DeepResearchError::PlanningError(format!(
+            "unreachable tasks: {}",
+            unreachable.join(", ")
+        ))
+        .into());
+    }
+
+    // Kahn's algorithm; ties keep discovery order so the result is deterministic.
+    let mut order = Vec::with_capacity(visited.len());
+    let mut remaining = visited;
+    while !remaining.is_empty() {
+        let position = remaining
+            .iter()
+            .position(|id| {
+                !edges
+                    .iter()
+                    .any(|(from, to)| to == id && remaining.contains(from))
+            })
+            .ok_or_else(|| DeepResearchError::PlanningError("graph contains a cycle".into()))?;
+        order.push(remaining.remove(position));
+    }
+    Ok(order)
+}
+
+fn dry_run_outcome(session_id: &str, order: Vec<String>) -> SessionOutcome {
+    info!(%session_id, tasks = order.len(), "dry run validated graph");
+    let trace_events: Vec<TraceEvent> = order
+        .into_iter()
+        .map(|task_id| TraceEvent::new(task_id, "dry-run: validated"))
+        .collect();
+    SessionOutcome {
+        session_id: session_id.to_string(),
+        summary: "dry-run: graph validated".to_string(),
+        trace_summary: TraceSummary::from_events(&trace_events),
+        trace_events,
+        trace_path: None,
+        requires_manual: false,
+        factcheck_confidence: None,
+        factcheck_passed: None,
+        factcheck_verified_sources: Vec::new(),
+        critic_confident: None,
+        sources: Vec::new(),
+        source_references: Vec::new(),
+        cancelled: false,
+        completed: false,
+    }
+}
+
+fn dedup_sources(sources: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    sources
+        .into_iter()
+        .filter(|source| seen.insert(source.clone()))
+        .collect()
+}
+
+/// Runs `inner` under a deadline; on expiry the task is marked `<id>.timed_out` and the
+/// graph continues with the next task instead of failing the session.
+struct TimeoutTask {
+    inner: Arc<dyn Task>,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl Task for TimeoutTask {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
+        match tokio::time::timeout(self.timeout, self.inner.run(context.clone())).await {
+            Ok(result) => result,
+            Err(_elapsed) => {
+                let task_id = self.id();
+                let timeout_ms = self.timeout.as_millis() as u64;
+                warn!(%task_id, timeout_ms, "task timed out; skipping to next task");
+                context.set(format!("{task_id}.timed_out"), true).await;
+                record_trace(
+                    &context,
+                    task_id,
+                    format!("timed out after {timeout_ms}ms; skipped"),
+                )
+                .await;
+                Ok(TaskResult::new(
+                    Some(format!("{task_id} timed out after {timeout_ms}ms")),
+                    NextAction::ContinueAndExecute,
+                ))
+            }
+        }
+    }
+}
+
+/// Runs `inner` inside a per-task span so each task shows up as a child span of the
+/// caller's trace (see `otel.trace_id` / `otel.span_id`).
+struct SpannedTask {
+    inner: Arc<dyn Task>,
+}
+
+#[async_trait]
+impl Task for SpannedTask {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
+        let span = crate::otel::task_span(&context, self.id()).await;
+        self.inner.run(context).instrument(span).await
+    }
+}
+
+/// Publishes the trace events `inner` recorded to the run's [`TraceStream`] once it returns.
+/// The stream belongs to this graph alone, so concurrent runs never see each other's events.
+struct LiveTraceTask {
+    inner: Arc<dyn Task>,
+    stream: TraceStream,
+}
+
+#[async_trait]
+impl Task for LiveTraceTask {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    async fn run(&self, context: Context) -> graph_flow::Result<TaskResult> {
+        let recorded = |collector: Option<TraceCollector>| collector.unwrap_or_default();
+        let before = recorded(context.get("trace.collector").await)
+            .events()
+            .len();
+        let result = self.inner.run(context.clone()).await;
+        let after = recorded(context.get("trace.collector").await);
+        for event in after.events().iter().skip(before) {
+            self.stream.publish(event.clone());
+        }
+        result
+    }
+}
+
+fn wrap_task(
+    task: Arc<dyn Task>,
+    timeouts: &HashMap<String, Duration>,
+    live_trace: Option<&TraceStream>,
+) -> Arc<dyn Task> {
+    let inner: Arc<dyn Task> = match timeouts.get(task.id()) {
+        Some(timeout) => Arc::new(TimeoutTask {
+            inner: task,
+            timeout: *timeout,
+        }),
+        None => task,
+    };
+    let inner: Arc<dyn Task> = match live_trace {
+        Some(stream) => Arc::new(LiveTraceTask {
+            inner,
+            stream: stream.clone(),
+        }),
+        None => inner,
+    };
+    Arc::new(SpannedTask { inner })
+}
+
+fn resolve_sandbox(
+    executor: Option<&Arc<dyn SandboxExecutor>>,
+    choice: Option<&SandboxChoice>,
+) -> Result<Option<Arc<dyn SandboxExecutor>>> {
+    match (executor, choice) {
+        (Some(executor), _) => Ok(Some(executor.clone())),
+        (None, Some(choice)) => choice
+            .build()
+            .map(Some)
+            .map_err(|err| DeepResearchError::SandboxError(format!("{err:#}")).into()),
+        (None, None) => Ok(None),
+    }
+}
+
 /// Hook for callers to mutate the graph before default wiring occurs.
 pub type GraphCustomizer = dyn Fn(GraphBuilder, &BaseGraphTasks) -> GraphBuilder + Send + Sync;
 
-#[derive(Clone, Default)]
+/// Builds a [`GraphCustomizer`] for common topologies without touching `GraphBuilder` directly:
+/// `researcher -> researcher_1.. -> math_tool -> analyst`, with each stage optional.
+#[derive(Default)]
+pub struct StandardPipelineBuilder {
+    math_executor: Option<Arc<dyn SandboxExecutor>>,
+    extra_researchers: Vec<DynRetriever>,
+}
+
+impl StandardPipelineBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `MathToolTask` after research and before analysis. Ignored when the session
+    /// already configures a sandbox executor, whose math task is reused instead.
+    pub fn with_math_tool(mut self, executor: Arc<dyn SandboxExecutor>) -> Self {
+        self.math_executor = Some(executor);
+        self
+    }
+
+    /// Add a researcher (`researcher_<n>`) whose findings are appended to the primary ones.
+    pub fn with_extra_researcher(mut self, retriever: DynRetriever) -> Self {
+        self.extra_researchers.push(retriever);
+        self
+    }
+
+    pub fn build(self) -> Box<GraphCustomizer> {
+        let StandardPipelineBuilder {
+            math_executor,
+            extra_researchers,
+        } = self;
+        Box::new(move |mut builder: GraphBuilder, tasks: &BaseGraphTasks| {
+            let mut chain: Vec<String> = vec![tasks.research.id().to_string()];
+            for (index, retriever) in extra_researchers.iter().enumerate() {
+                let researcher = Arc::new(ResearchTask::supplementary(
+                    format!("researcher_{}", index + 1),
+                    retriever.clone(),
+                ));
+                chain.push(researcher.id().to_string());
+                builder = builder.add_task(researcher);
+            }
+            match (&tasks.math, &math_executor) {
+                (Some(math), _) => chain.push(math.id().to_string()),
+                (None, Some(executor)) => {
+                    let math = Arc::new(MathToolTask::new(executor.clone()));
+                    chain.push(math.id().to_string());
+                    builder = builder.add_task(math);
+                }
+                (None, None) => {}
+            }
+            chain.push(tasks.analyst.id().to_string());
+
+            if chain.len() == 2 {
+                return builder;
+            }
+            // graph_flow follows the first unconditional edge out of a task, and customizer
+            // edges are registered before the default research -> analyst wiring.
+            for pair in chain.windows(2) {
+                builder = builder.add_edge(pair[0].as_str(), pair[1].as_str());
+            }
+            builder
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub enum RetrieverChoice {
     #[default]
     Stub,
@@ -192,6 +661,9 @@ pub enum RetrieverChoice {
         collection: String,
         concurrency_limit: usize,
     },
+    Sqlite {
+        db_path: PathBuf,
+    },
 }
 
 impl RetrieverChoice {
@@ -206,6 +678,12 @@ impl RetrieverChoice {
             concurrency_limit,
         }
     }
+
+    pub fn sqlite(db_path: impl Into<PathBuf>) -> Self {
+        Self::Sqlite {
+            db_path: db_path.into(),
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -235,24 +713,38 @@ fn build_graph(
     retriever: DynRetriever,
     fact_settings: FactCheckSettings,
     math_executor: Option<Arc<dyn SandboxExecutor>>,
+    overrides: TaskOverrides,
+    task_timeouts: &HashMap<String, Duration>,
+    live_trace: Option<&TraceStream>,
 ) -> (Arc<graph_flow::Graph>, BaseGraphTasks) {
     let math_task = math_executor.map(|executor| Arc::new(MathToolTask::new(executor)));
-    let tasks = BaseGraphTasks::new(retriever, fact_settings, math_task);
+    let tasks = BaseGraphTasks::new(retriever, fact_settings, math_task, overrides);
 
     let builder = GraphBuilder::new("deepresearch_workflow")
-        .add_task(tasks.research.clone())
-        .add_task(tasks.analyst.clone())
-        .add_task(tasks.fact_check.clone())
-        .add_task(tasks.critic.clone())
-        .add_task(tasks.finalize.clone())
-        .add_task(tasks.manual_review.clone());
+        .add_task(wrap_task(tasks.research.clone(), task_timeouts, live_trace))
+        .add_task(wrap_task(tasks.analyst.clone(), task_timeouts, live_trace))
+        .add_task(wrap_task(
+            tasks.fact_check.clone(),
+            task_timeouts,
+            live_trace,
+        ))
+        .add_task(wrap_task(tasks.critic.clone(), task_timeouts, live_trace))
+        .add_task(wrap_task(tasks.finalize.clone(), task_timeouts, live_trace))
+        .add_task(wrap_task(
+            tasks.manual_review.clone(),
+            task_timeouts,
+            live_trace,
+        ));
 
     let builder = if let Some(math) = &tasks.math {
-        builder.add_task(math.clone())
+        builder.add_task(wrap_task(math.clone(), task_timeouts, live_trace))
     } else {
         builder
     };
 
+    #[cfg(feature = "task-plugins")]
+    let builder = add_plugin_tasks(builder, &tasks, task_timeouts, live_trace);
+
     let builder = if let Some(customize) = customizer {
         customize(builder, &tasks)
     } else {
@@ -285,6 +777,50 @@ fn build_graph(
     (graph, tasks)
 }
 
+/// Add every [`TaskPlugin`](crate::plugin::TaskPlugin) as a graph node. Plugins whose task id
+/// collides with a built-in task are skipped so they cannot replace core behaviour.
+#[cfg(feature = "task-plugins")]
+fn add_plugin_tasks(
+    builder: GraphBuilder,
+    tasks: &BaseGraphTasks,
+    task_timeouts: &HashMap<String, Duration>,
+    live_trace: Option<&TraceStream>,
+) -> GraphBuilder {
+    let mut reserved = vec![
+        tasks.research.id(),
+        tasks.analyst.id(),
+        tasks.fact_check.id(),
+        tasks.critic.id(),
+        tasks.finalize.id(),
+        tasks.manual_review.id(),
+    ];
+    if let Some(math) = &tasks.math {
+        reserved.push(math.id());
+    }
+
+    crate::plugin::registered_task_plugins()
+        .into_iter()
+        .fold(builder, |builder, plugin| {
+            let task = (plugin.factory)();
+            if reserved.contains(&task.id()) {
+                warn!(
+                    plugin = plugin.id,
+                    task_id = task.id(),
+                    "skipping task plugin that shadows a built-in task"
+                );
+                return builder;
+            }
+            if task.id() != plugin.id {
+                warn!(
+                    plugin = plugin.id,
+                    task_id = task.id(),
+                    "task plugin id differs from the task it builds"
+                );
+            }
+            builder.add_task(wrap_task(task, task_timeouts, live_trace))
+        })
+}
+
 async fn init_storage(choice: &StorageChoice) -> Result<Arc<dyn SessionStorage>> {
     match choice {
         StorageChoice::InMemory => Ok(Arc::new(InMemorySessionStorage::new())),
@@ -292,7 +828,11 @@ async fn init_storage(choice: &StorageChoice) -> Result<Arc<dyn SessionStorage>>
         StorageChoice::Postgres { database_url } => {
             let storage = PostgresSessionStorage::connect(database_url)
                 .await
-                .map_err(|err| anyhow!("failed to connect Postgres session storage: {err}"))?;
+                .map_err(|err| {
+                    DeepResearchError::StorageError(format!(
+                        "failed to connect Postgres session storage: {err}"
+                    ))
+                })?;
             Ok(Arc::new(storage))
         }
         StorageChoice::Custom { storage } => Ok(storage.clone()),
@@ -303,7 +843,13 @@ fn new_session_id() -> String {
     Uuid::new_v4().to_string()
 }
 
-async fn build_retriever(choice: &RetrieverChoice) -> Result<DynRetriever> {
+pub(crate) async fn build_retriever(choice: &RetrieverChoice) -> Result<DynRetriever> {
+    connect_retriever(choice)
+        .await
+        .map_err(|err| DeepResearchError::RetrieverError(format!("{err:#}")).into())
+}
+
+async fn connect_retriever(choice: &RetrieverChoice) -> Result<DynRetriever> {
     match choice {
         RetrieverChoice::Stub => Ok(Arc::new(StubRetriever::new())),
         RetrieverChoice::Qdrant {
@@ -313,11 +859,11 @@ async fn build_retriever(choice: &RetrieverChoice) -> Result<DynRetriever> {
         } => {
             #[cfg(feature = "qdrant-retriever")]
             {
-                let retriever = HybridRetriever::new(QdrantConfig {
-                    url: url.clone(),
-                    collection: collection.clone(),
-                    concurrency_limit: *concurrency_limit,
-                })
+                let retriever = HybridRetriever::new(QdrantConfig::new(
+                    url.clone(),
+                    collection.clone(),
+                    *concurrency_limit,
+                ))
                 .await?;
                 Ok(Arc::new(retriever))
             }
@@ -329,6 +875,101 @@ async fn build_retriever(choice: &RetrieverChoice) -> Result<DynRetriever> {
                 ))
             }
         }
+        RetrieverChoice::Sqlite { db_path } => {
+            #[cfg(feature = "sqlite-retriever")]
+            {
+                let retriever = SqliteRetriever::new(db_path).await?;
+                Ok(Arc::new(retriever))
+            }
+            #[cfg(not(feature = "sqlite-retriever"))]
+            {
+                let _ = db_path;
+                Err(anyhow!(
+                    "sqlite retriever support not enabled; rebuild with `--features deepresearch-core/sqlite-retriever`"
+                ))
+            }
+        }
+    }
+}
+
+/// Expected JSON type of an initial-context value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextValueType {
+    String,
+    Bool,
+    Number,
+    /// Any JSON value (objects, arrays, null, ...).
+    Json,
+}
+
+impl ContextValueType {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            ContextValueType::String => value.is_string(),
+            ContextValueType::Bool => value.is_boolean(),
+            ContextValueType::Number => value.is_number(),
+            ContextValueType::Json => true,
+        }
+    }
+}
+
+/// Allowed `initial_context` keys and their value types; see [`SessionOptions::with_context_schema`].
+pub type ContextSchema = HashMap<String, ContextValueType>;
+
+/// Rewrites the caller's query (acronym expansion, entity linking, …) before the
+/// session starts; the untouched input is kept under `query.original`.
+pub type QueryPreprocessor = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(text) => Value::String(text),
+        toml::Value::Integer(number) => Value::from(number),
+        toml::Value::Float(number) => Value::from(number),
+        toml::Value::Boolean(flag) => Value::Bool(flag),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Reject initial-context entries whose key is not in `schema` (usually a typo) or whose
+/// value has the wrong type. All problems are reported together.
+fn validate_initial_context(schema: &ContextSchema, entries: &[(String, Value)]) -> Result<()> {
+    let problems: Vec<String> = entries
+        .iter()
+        .filter_map(|(key, value)| match schema.get(key) {
+            None => Some(format!("unknown context key '{key}'")),
+            Some(expected) if !expected.matches(value) => Some(format!(
+                "context key '{key}' expects {expected:?} but got {}",
+                json_type_name(value)
+            )),
+            Some(_) => None,
+        })
+        .collect();
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "initial context failed schema validation: {}",
+            problems.join("; ")
+        ))
+    }
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
     }
 }
 
@@ -340,10 +981,28 @@ pub struct SessionOptions<'a> {
     pub initial_context: Vec<(String, Value)>,
     pub storage: StorageChoice,
     pub retriever: RetrieverChoice,
+    pub preseeded_documents: Vec<RetrievedDocument>,
     pub fact_check_settings: FactCheckSettings,
     pub sandbox_executor: Option<Arc<dyn SandboxExecutor>>,
+    pub sandbox: Option<SandboxChoice>,
+    pub critic_scorer: Option<CriticScorer>,
+    pub analyst_template: Option<SummaryTemplate>,
+    pub manual_review_webhook: Option<ManualReviewWebhook>,
+    pub result_scorer: Option<ResultScorer>,
+    pub task_timeouts: HashMap<String, Duration>,
+    /// Word budget per retrieved finding; longer findings are cut at a word boundary.
+    pub max_tokens_per_finding: Option<usize>,
+    /// Minimum number of analysis sources the critic requires (default 1).
+    pub critic_minimum_sources: Option<usize>,
     pub trace_enabled: bool,
-    pub trace_output_dir: Option<PathBuf>,
+    pub trace_output: Option<TraceOutput>,
+    pub trace_stream: Option<TraceStream>,
+    pub context7_prefix: bool,
+    pub cancellation_token: Option<CancellationToken>,
+    pub context_schema: Option<ContextSchema>,
+    pub query_preprocessor: Option<QueryPreprocessor>,
+    /// Build and validate the graph and initial context without executing any task.
+    pub dry_run: bool,
 }
 
 impl<'a> SessionOptions<'a> {
@@ -355,10 +1014,25 @@ impl<'a> SessionOptions<'a> {
             initial_context: Vec::new(),
             storage: StorageChoice::InMemory,
             retriever: RetrieverChoice::default(),
-            fact_check_settings: FactCheckSettings::default(),
+            preseeded_documents: Vec::new(),
+            fact_check_settings: FactCheckSettings::from_env(),
             sandbox_executor: None,
+            sandbox: None,
+            critic_scorer: None,
+            analyst_template: None,
+            manual_review_webhook: None,
+            result_scorer: None,
+            task_timeouts: HashMap::new(),
+            max_tokens_per_finding: None,
+            critic_minimum_sources: None,
             trace_enabled: false,
-            trace_output_dir: None,
+            trace_output: None,
+            trace_stream: None,
+            context7_prefix: false,
+            cancellation_token: None,
+            context_schema: None,
+            query_preprocessor: None,
+            dry_run: false,
         }
     }
 
@@ -377,6 +1051,40 @@ impl<'a> SessionOptions<'a> {
         self
     }
 
+    /// Seed the initial context from a TOML file: each top-level key becomes one entry
+    /// (tables map to JSON objects, datetimes to RFC 3339 strings).
+    pub fn with_context_file(mut self, path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|err| anyhow!("failed to read context file {}: {err}", path.display()))?;
+        let table: toml::Table = raw
+            .parse()
+            .map_err(|err| anyhow!("failed to parse context file {}: {err}", path.display()))?;
+        for (key, value) in table {
+            self = self.with_initial_context(key, toml_to_json(value));
+        }
+        Ok(self)
+    }
+
+    /// Validate `initial_context` against `schema` before the session starts; unknown keys
+    /// and type mismatches fail the run instead of silently seeding the wrong key.
+    pub fn with_context_schema(mut self, schema: ContextSchema) -> Self {
+        self.context_schema = Some(schema);
+        self
+    }
+
+    /// Transform the query before it is stored; runs ahead of the `use context7` prefix.
+    pub fn with_query_preprocessor(mut self, preprocessor: QueryPreprocessor) -> Self {
+        self.query_preprocessor = Some(preprocessor);
+        self
+    }
+
+    /// Validate the graph (start task, cycles, unreachable tasks) and initial context, then
+    /// return without running tasks or persisting the session.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
     pub fn with_fact_check_settings(mut self, settings: FactCheckSettings) -> Self {
         self.fact_check_settings = settings;
         self
@@ -403,11 +1111,73 @@ impl<'a> SessionOptions<'a> {
         self
     }
 
+    /// Feed externally retrieved documents to the researcher, bypassing `retriever` entirely.
+    pub fn with_preseeded_documents(mut self, documents: Vec<RetrievedDocument>) -> Self {
+        self.preseeded_documents = documents;
+        self
+    }
+
     pub fn with_sandbox_executor(mut self, executor: Arc<dyn SandboxExecutor>) -> Self {
         self.sandbox_executor = Some(executor);
         self
     }
 
+    /// Build the math sandbox from `choice`; ignored when an executor is set explicitly.
+    pub fn with_sandbox(mut self, choice: SandboxChoice) -> Self {
+        self.sandbox = Some(choice);
+        self
+    }
+
+    /// Score the critic verdict with `scorer` instead of the built-in heuristic.
+    pub fn with_critic_scorer(mut self, scorer: CriticScorer) -> Self {
+        self.critic_scorer = Some(scorer);
+        self
+    }
+
+    /// Route the session to manual review unless the analysis cites at least `count` sources.
+    pub fn with_critic_minimum_sources(mut self, count: usize) -> Self {
+        self.critic_minimum_sources = Some(count);
+        self
+    }
+
+    /// Format the analyst summary with `template` instead of the generic default.
+    pub fn with_analyst_template(mut self, template: SummaryTemplate) -> Self {
+        self.analyst_template = Some(template);
+        self
+    }
+
+    /// Re-rank the researcher's retrieved documents with `scorer` before they are stored.
+    pub fn with_result_scorer(mut self, scorer: ResultScorer) -> Self {
+        self.result_scorer = Some(scorer);
+        self
+    }
+
+    /// Truncate each retrieved finding to at most `limit` words (whitespace-separated, a
+    /// rough token estimate) so long documents do not overflow downstream context windows.
+    pub fn with_max_tokens_per_finding(mut self, limit: usize) -> Self {
+        self.max_tokens_per_finding = Some(limit);
+        self
+    }
+
+    /// Notify `url` (with the extra `headers`) whenever the session needs manual review.
+    pub fn with_manual_review_webhook(
+        mut self,
+        url: impl Into<String>,
+        headers: Vec<(String, String)>,
+    ) -> Self {
+        self.manual_review_webhook = Some(ManualReviewWebhook {
+            url: url.into(),
+            headers,
+        });
+        self
+    }
+
+    /// Abandon `task_id` after `timeout` and continue with the next task in the graph.
+    pub fn with_task_timeout(mut self, task_id: impl Into<String>, timeout: Duration) -> Self {
+        self.task_timeouts.insert(task_id.into(), timeout);
+        self
+    }
+
     pub fn with_qdrant_retriever(
         mut self,
         url: impl Into<String>,
@@ -418,18 +1188,78 @@ impl<'a> SessionOptions<'a> {
         self
     }
 
+    pub fn with_sqlite_retriever(mut self, db_path: impl Into<PathBuf>) -> Self {
+        self.retriever = RetrieverChoice::sqlite(db_path);
+        self
+    }
+
     pub fn enable_trace(mut self) -> Self {
         self.trace_enabled = true;
         self
     }
 
-    pub fn with_trace_output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+    pub fn with_trace_output_dir(self, dir: impl Into<PathBuf>) -> Self {
+        self.with_trace_output(TraceOutput::LocalFile(dir.into()))
+    }
+
+    /// Persist the trace to `output` (local directory or S3 bucket); implies `enable_trace`.
+    pub fn with_trace_output(mut self, output: TraceOutput) -> Self {
         self.trace_enabled = true;
-        self.trace_output_dir = Some(dir.into());
+        self.trace_output = Some(output);
+        self
+    }
+
+    /// Publish trace events to `stream` as each task finishes. Events are collected for the
+    /// stream, but only persisted when `enable_trace` or `with_trace_output` is also set.
+    pub fn with_trace_stream(mut self, stream: TraceStream) -> Self {
+        self.trace_stream = Some(stream);
+        self
+    }
+
+    /// Prefix the query with `use context7` unless it already starts with it.
+    pub fn with_context7_prefix(mut self) -> Self {
+        self.context7_prefix = true;
+        self
+    }
+
+    /// Abort the run when `token` is cancelled; the outcome then has `cancelled == true`.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
         self
     }
 }
 
+fn context7_prefix_disabled_by_env() -> bool {
+    std::env::var(DISABLE_CONTEXT7_ENV)
+        .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn ensure_context7_prefix(query: &str) -> String {
+    let trimmed = query.trim_start();
+
+    if trimmed.to_ascii_lowercase().starts_with(CONTEXT7_PREFIX) {
+        query.to_string()
+    } else if trimmed.is_empty() {
+        CONTEXT7_PREFIX.to_string()
+    } else {
+        format!("{CONTEXT7_PREFIX} {query}")
+    }
+}
+
+/// Trace events recorded in the session context (legacy `trace.events` as fallback).
+fn stored_trace_events(session: &Session) -> Vec<TraceEvent> {
+    session
+        .context
+        .get_sync::<TraceCollector>("trace.collector")
+        .unwrap_or_else(|| {
+            let legacy: Vec<TraceEvent> =
+                session.context.get_sync("trace.events").unwrap_or_default();
+            TraceCollector::from_events(legacy)
+        })
+        .into_events()
+}
+
 fn extract_final_summary(session: &Session) -> String {
     session
         .context
@@ -440,83 +1270,156 @@ fn extract_final_summary(session: &Session) -> String {
 /// Run the research workflow end-to-end with a detailed outcome (summary + trace).
 pub async fn run_research_session_with_report(
     options: SessionOptions<'_>,
-) -> Result<SessionOutcome> {
-    let retriever = build_retriever(&options.retriever).await?;
+) -> Result<SessionOutcome, DeepResearchError> {
+    let started = std::time::Instant::now();
+    if let Some(schema) = &options.context_schema {
+        validate_initial_context(schema, &options.initial_context)?;
+    }
+    let preseeded = !options.preseeded_documents.is_empty();
+    let retriever: DynRetriever = if preseeded {
+        Arc::new(SeedRetriever::new(options.preseeded_documents.clone()))
+    } else {
+        build_retriever(&options.retriever).await?
+    };
     let (graph, tasks) = build_graph(
         options.customize_graph.as_deref(),
         retriever,
         options.fact_check_settings.clone(),
-        options.sandbox_executor.clone(),
+        resolve_sandbox(options.sandbox_executor.as_ref(), options.sandbox.as_ref())?,
+        TaskOverrides {
+            critic_scorer: options.critic_scorer.clone(),
+            analyst_template: options.analyst_template.clone(),
+            manual_review_webhook: options.manual_review_webhook.clone(),
+            result_scorer: options.result_scorer.clone(),
+            max_tokens_per_finding: options.max_tokens_per_finding,
+            critic_minimum_sources: options.critic_minimum_sources,
+        },
+        &options.task_timeouts,
+        options.trace_stream.as_ref(),
     );
     let storage = init_storage(&options.storage).await?;
-    let runner = FlowRunner::new(graph, storage.clone());
+    let runner = FlowRunner::new(graph.clone(), storage.clone());
 
     let session_id = options.session_id.clone().unwrap_or_else(new_session_id);
     let session = Session::new_from_task(session_id.clone(), tasks.research.id());
 
-    session
-        .context
-        .set("query", options.query.to_string())
+    let processed = match options.query_preprocessor.as_ref() {
+        Some(preprocess) => preprocess(options.query),
+        None => options.query.to_string(),
+    };
+    let query = if options.context7_prefix && !context7_prefix_disabled_by_env() {
+        ensure_context7_prefix(&processed)
+    } else {
+        processed
+    };
+    session
+        .context
+        .set("query.original", options.query.to_string())
         .await;
+    session.context.set("query", query).await;
     session.context.set("session_id", session_id.clone()).await;
+    session
+        .context
+        .set(SESSION_CREATED_AT_KEY, chrono::Utc::now().to_rfc3339())
+        .await;
     for (key, value) in options.initial_context.iter() {
         session.context.set(key, value.clone()).await;
     }
-    if options.trace_enabled {
+    if preseeded {
+        session.context.set("research.preseeded", true).await;
+    }
+    if options.trace_enabled || options.trace_stream.is_some() {
         session.context.set("trace.enabled", true).await;
+        session
+            .context
+            .set("trace.persist", options.trace_enabled)
+            .await;
         session
             .context
             .set("trace.collector", TraceCollector::new())
             .await;
     }
 
-    storage
-        .save(session)
-        .await
-        .map_err(|err| anyhow!("failed to persist session: {err}"))?;
+    if options.dry_run {
+        let order = validate_graph(&graph, &tasks, &session.context)?;
+        return Ok(dry_run_outcome(&session_id, order));
+    }
 
-    execute_until_complete(&runner, &session_id).await?;
+    storage.save(session).await.map_err(|err| {
+        DeepResearchError::StorageError(format!("failed to persist session: {err}"))
+    })?;
+
+    if execute_until_complete(&runner, &session_id, options.cancellation_token.as_ref()).await?
+        == RunEnd::Cancelled
+    {
+        return Ok(cancelled_outcome(&session_id));
+    }
 
     let session = load_session(&storage, &session_id).await?;
-    build_outcome(&session, &session_id, options.trace_output_dir.as_ref())
+    let outcome = build_outcome(&session, &session_id, options.trace_output.as_ref()).await?;
+    crate::record_session_metrics(
+        &session_id,
+        started.elapsed().as_millis() as u64,
+        outcome.requires_manual,
+    );
+    Ok(outcome)
 }
 
 /// Run the research workflow end-to-end for the provided query using default settings.
 pub async fn run_research_session(query: &str) -> Result<String> {
-    run_research_session_with_report(SessionOptions::new(query))
-        .await
-        .map(|outcome| outcome.summary)
+    let outcome = run_research_session_with_report(SessionOptions::new(query)).await?;
+    Ok(outcome.summary)
 }
 
 /// Run the research workflow with custom options (session ID, storage, graph customisation, seeded context).
 pub async fn run_research_session_with_options(options: SessionOptions<'_>) -> Result<String> {
-    run_research_session_with_report(options)
-        .await
-        .map(|outcome| outcome.summary)
+    let outcome = run_research_session_with_report(options).await?;
+    Ok(outcome.summary)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum RunEnd {
+    Completed,
+    Cancelled,
 }
 
-async fn execute_until_complete(runner: &FlowRunner, session_id: &str) -> Result<()> {
+async fn execute_until_complete(
+    runner: &FlowRunner,
+    session_id: &str,
+    cancel: Option<&CancellationToken>,
+) -> Result<RunEnd> {
     loop {
-        let result = runner
-            .run(session_id)
-            .await
-            .map_err(|err| anyhow!("graph execution failure: {err}"))?;
+        // A single `run` call may execute the whole task chain, so race it against the
+        // token rather than only checking between iterations.
+        let result = match cancel {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => return Ok(RunEnd::Cancelled),
+                result = runner.run(session_id) => result,
+            },
+            None => runner.run(session_id).await,
+        }
+        .map_err(|err| {
+            DeepResearchError::ExecutionError(format!("graph execution failure: {err}"))
+        })?;
 
         match result.status {
             ExecutionStatus::Completed => break,
             ExecutionStatus::WaitingForInput => continue,
-            ExecutionStatus::Error(message) => return Err(anyhow!(message)),
+            ExecutionStatus::Error(message) => {
+                return Err(DeepResearchError::ExecutionError(message).into());
+            }
         }
     }
-    Ok(())
+    Ok(RunEnd::Completed)
 }
 
 async fn load_session(storage: &Arc<dyn SessionStorage>, session_id: &str) -> Result<Session> {
     storage
         .get(session_id)
         .await
-        .map_err(|err| anyhow!("failed to load session: {err}"))?
-        .ok_or_else(|| anyhow!("session '{session_id}' not found"))
+        .map_err(|err| DeepResearchError::StorageError(format!("failed to load session: {err}")))?
+        .ok_or_else(|| DeepResearchError::SessionNotFound(session_id.to_string()).into())
 }
 
 /// Options for resuming an existing session.
@@ -527,8 +1430,17 @@ pub struct ResumeOptions {
     pub retriever: RetrieverChoice,
     pub fact_check_settings: FactCheckSettings,
     pub sandbox_executor: Option<Arc<dyn SandboxExecutor>>,
+    pub sandbox: Option<SandboxChoice>,
+    pub critic_scorer: Option<CriticScorer>,
+    pub critic_minimum_sources: Option<usize>,
+    pub max_tokens_per_finding: Option<usize>,
+    pub analyst_template: Option<SummaryTemplate>,
+    pub manual_review_webhook: Option<ManualReviewWebhook>,
+    pub result_scorer: Option<ResultScorer>,
+    pub task_timeouts: HashMap<String, Duration>,
     pub trace_enabled: bool,
     pub trace_output_dir: Option<PathBuf>,
+    pub cancellation_token: Option<CancellationToken>,
 }
 
 impl ResumeOptions {
@@ -538,10 +1450,19 @@ impl ResumeOptions {
             customize_graph: None,
             storage: StorageChoice::InMemory,
             retriever: RetrieverChoice::default(),
-            fact_check_settings: FactCheckSettings::default(),
+            fact_check_settings: FactCheckSettings::from_env(),
             sandbox_executor: None,
+            sandbox: None,
+            critic_scorer: None,
+            critic_minimum_sources: None,
+            max_tokens_per_finding: None,
+            analyst_template: None,
+            manual_review_webhook: None,
+            result_scorer: None,
+            task_timeouts: HashMap::new(),
             trace_enabled: false,
             trace_output_dir: None,
+            cancellation_token: None,
         }
     }
 
@@ -571,6 +1492,12 @@ impl ResumeOptions {
         self
     }
 
+    /// Abort the run when `token` is cancelled; the outcome then has `cancelled == true`.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
     pub fn with_fact_check_settings(mut self, settings: FactCheckSettings) -> Self {
         self.fact_check_settings = settings;
         self
@@ -581,6 +1508,62 @@ impl ResumeOptions {
         self
     }
 
+    /// Build the math sandbox from `choice`; ignored when an executor is set explicitly.
+    pub fn with_sandbox(mut self, choice: SandboxChoice) -> Self {
+        self.sandbox = Some(choice);
+        self
+    }
+
+    /// Score the critic verdict with `scorer` instead of the built-in heuristic.
+    pub fn with_critic_scorer(mut self, scorer: CriticScorer) -> Self {
+        self.critic_scorer = Some(scorer);
+        self
+    }
+
+    /// Route the session to manual review unless the analysis cites at least `count` sources.
+    pub fn with_critic_minimum_sources(mut self, count: usize) -> Self {
+        self.critic_minimum_sources = Some(count);
+        self
+    }
+
+    /// Format the analyst summary with `template` instead of the generic default.
+    pub fn with_analyst_template(mut self, template: SummaryTemplate) -> Self {
+        self.analyst_template = Some(template);
+        self
+    }
+
+    /// Truncate each retrieved finding to at most `limit` words; pass the value the
+    /// session was started with so a resumed researcher truncates the same way.
+    pub fn with_max_tokens_per_finding(mut self, limit: usize) -> Self {
+        self.max_tokens_per_finding = Some(limit);
+        self
+    }
+
+    /// Re-rank the researcher's retrieved documents with `scorer` before they are stored.
+    pub fn with_result_scorer(mut self, scorer: ResultScorer) -> Self {
+        self.result_scorer = Some(scorer);
+        self
+    }
+
+    /// Notify `url` (with the extra `headers`) whenever the session needs manual review.
+    pub fn with_manual_review_webhook(
+        mut self,
+        url: impl Into<String>,
+        headers: Vec<(String, String)>,
+    ) -> Self {
+        self.manual_review_webhook = Some(ManualReviewWebhook {
+            url: url.into(),
+            headers,
+        });
+        self
+    }
+
+    /// Abandon `task_id` after `timeout` and continue with the next task in the graph.
+    pub fn with_task_timeout(mut self, task_id: impl Into<String>, timeout: Duration) -> Self {
+        self.task_timeouts.insert(task_id.into(), timeout);
+        self
+    }
+
     pub fn with_qdrant_retriever(
         mut self,
         url: impl Into<String>,
@@ -591,6 +1574,11 @@ impl ResumeOptions {
         self
     }
 
+    pub fn with_sqlite_retriever(mut self, db_path: impl Into<PathBuf>) -> Self {
+        self.retriever = RetrieverChoice::sqlite(db_path);
+        self
+    }
+
     pub fn enable_trace(mut self) -> Self {
         self.trace_enabled = true;
         self
@@ -607,6 +1595,9 @@ pub struct LoadOptions {
     pub session_id: String,
     pub storage: StorageChoice,
     pub trace_output_dir: Option<PathBuf>,
+    /// Fail when `final.summary` or `critique.verdict` is missing instead of returning
+    /// an empty summary (e.g. a partially replicated Postgres row).
+    pub verify_integrity: bool,
 }
 
 impl LoadOptions {
@@ -615,6 +1606,7 @@ impl LoadOptions {
             session_id: session_id.into(),
             storage: StorageChoice::InMemory,
             trace_output_dir: None,
+            verify_integrity: false,
         }
     }
 
@@ -638,11 +1630,22 @@ impl LoadOptions {
         self.trace_output_dir = Some(dir.into());
         self
     }
+
+    pub fn with_verify_integrity(mut self, verify: bool) -> Self {
+        self.verify_integrity = verify;
+        self
+    }
 }
 
 pub struct DeleteOptions {
     pub session_id: String,
     pub storage: StorageChoice,
+    /// Retrieval backend whose indexed documents for the session are removed as well
+    /// (the default stub keeps nothing beyond the process).
+    pub retriever: RetrieverChoice,
+    /// Also remove the session's `session_records` rows (data-pipeline table) in the same
+    /// Postgres transaction. Ignored for non-Postgres storage.
+    pub cascade_delete_pipeline_records: bool,
 }
 
 impl DeleteOptions {
@@ -650,6 +1653,99 @@ impl DeleteOptions {
         Self {
             session_id: session_id.into(),
             storage: StorageChoice::InMemory,
+            retriever: RetrieverChoice::default(),
+            cascade_delete_pipeline_records: false,
+        }
+    }
+
+    pub fn with_retriever(mut self, retriever: RetrieverChoice) -> Self {
+        self.retriever = retriever;
+        self
+    }
+
+    pub fn with_qdrant_retriever(
+        mut self,
+        url: impl Into<String>,
+        collection: impl Into<String>,
+    ) -> Self {
+        self.retriever = RetrieverChoice::qdrant(url, collection, 1);
+        self
+    }
+
+    pub fn with_cascade_delete_pipeline_records(mut self, cascade: bool) -> Self {
+        self.cascade_delete_pipeline_records = cascade;
+        self
+    }
+
+    pub fn with_storage(mut self, storage: StorageChoice) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    pub fn with_shared_storage(mut self, storage: Arc<dyn SessionStorage>) -> Self {
+        self.storage = StorageChoice::Custom { storage };
+        self
+    }
+
+    #[cfg(feature = "postgres-session")]
+    pub fn with_postgres_storage(mut self, database_url: impl Into<String>) -> Self {
+        self.storage = StorageChoice::postgres(database_url);
+        self
+    }
+}
+
+/// Options for forking a stored session under a new ID.
+pub struct CloneOptions {
+    pub source_id: String,
+    /// ID for the copy; a fresh UUID is generated when unset.
+    pub new_id: Option<String>,
+    pub storage: StorageChoice,
+}
+
+impl CloneOptions {
+    pub fn new(source_id: impl Into<String>) -> Self {
+        Self {
+            source_id: source_id.into(),
+            new_id: None,
+            storage: StorageChoice::InMemory,
+        }
+    }
+
+    pub fn with_new_id(mut self, new_id: impl Into<String>) -> Self {
+        self.new_id = Some(new_id.into());
+        self
+    }
+
+    pub fn with_storage(mut self, storage: StorageChoice) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    pub fn with_shared_storage(mut self, storage: Arc<dyn SessionStorage>) -> Self {
+        self.storage = StorageChoice::Custom { storage };
+        self
+    }
+
+    #[cfg(feature = "postgres-session")]
+    pub fn with_postgres_storage(mut self, database_url: impl Into<String>) -> Self {
+        self.storage = StorageChoice::postgres(database_url);
+        self
+    }
+}
+
+/// Options for writing a session bundle (see [`export_session_bundle`]).
+pub struct ExportOptions {
+    pub session_id: String,
+    pub output: PathBuf,
+    pub storage: StorageChoice,
+}
+
+impl ExportOptions {
+    pub fn new(session_id: impl Into<String>, output: impl Into<PathBuf>) -> Self {
+        Self {
+            session_id: session_id.into(),
+            output: output.into(),
+            storage: StorageChoice::InMemory,
         }
     }
 
@@ -671,13 +1767,25 @@ impl DeleteOptions {
 }
 
 /// Resume a previously started session and return a detailed outcome.
-pub async fn resume_research_session_with_report(options: ResumeOptions) -> Result<SessionOutcome> {
+pub async fn resume_research_session_with_report(
+    options: ResumeOptions,
+) -> Result<SessionOutcome, DeepResearchError> {
     let retriever = build_retriever(&options.retriever).await?;
     let (graph, _tasks) = build_graph(
         options.customize_graph.as_deref(),
         retriever,
         options.fact_check_settings.clone(),
-        options.sandbox_executor.clone(),
+        resolve_sandbox(options.sandbox_executor.as_ref(), options.sandbox.as_ref())?,
+        TaskOverrides {
+            critic_scorer: options.critic_scorer.clone(),
+            analyst_template: options.analyst_template.clone(),
+            manual_review_webhook: options.manual_review_webhook.clone(),
+            result_scorer: options.result_scorer.clone(),
+            max_tokens_per_finding: options.max_tokens_per_finding,
+            critic_minimum_sources: options.critic_minimum_sources,
+        },
+        &options.task_timeouts,
+        None,
     );
     let storage = init_storage(&options.storage).await?;
     let runner = FlowRunner::new(graph, storage.clone());
@@ -685,6 +1793,7 @@ pub async fn resume_research_session_with_report(options: ResumeOptions) -> Resu
     let session = load_session(&storage, &options.session_id).await?;
     if options.trace_enabled {
         session.context.set("trace.enabled", true).await;
+        session.context.set("trace.persist", true).await;
         if session
             .context
             .get_sync::<TraceCollector>("trace.collector")
@@ -699,67 +1808,917 @@ pub async fn resume_research_session_with_report(options: ResumeOptions) -> Resu
             };
             session.context.set("trace.collector", collector).await;
         }
-        storage
-            .save(session)
-            .await
-            .map_err(|err| anyhow!("failed to persist session: {err}"))?;
+        storage.save(session).await.map_err(|err| {
+            DeepResearchError::StorageError(format!("failed to persist session: {err}"))
+        })?;
     }
 
-    execute_until_complete(&runner, &options.session_id).await?;
-
-    let session = load_session(&storage, &options.session_id).await?;
-    build_outcome(
-        &session,
+    if execute_until_complete(
+        &runner,
         &options.session_id,
-        options.trace_output_dir.as_ref(),
+        options.cancellation_token.as_ref(),
     )
+    .await?
+        == RunEnd::Cancelled
+    {
+        return Ok(cancelled_outcome(&options.session_id));
+    }
+
+    let session = load_session(&storage, &options.session_id).await?;
+    let trace_output = options.trace_output_dir.map(TraceOutput::LocalFile);
+    Ok(build_outcome(&session, &options.session_id, trace_output.as_ref()).await?)
+}
+
+/// Ensure the keys written by the final tasks are present and readable.
+fn verify_session_integrity(session: &Session, session_id: &str) -> Result<()> {
+    let missing: Vec<&str> = ["final.summary", "critique.verdict"]
+        .into_iter()
+        .filter(|key| session.context.get_sync::<String>(key).is_none())
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "session '{session_id}' failed integrity check: missing or unreadable {}",
+            missing.join(", ")
+        ))
+    }
 }
 
 /// Resume a previously started session and return the latest summary.
 pub async fn resume_research_session(options: ResumeOptions) -> Result<String> {
-    resume_research_session_with_report(options)
-        .await
-        .map(|outcome| outcome.summary)
+    let outcome = resume_research_session_with_report(options).await?;
+    Ok(outcome.summary)
 }
 
-pub async fn load_session_report(options: LoadOptions) -> Result<SessionOutcome> {
+pub async fn load_session_report(
+    options: LoadOptions,
+) -> Result<SessionOutcome, DeepResearchError> {
     let storage = init_storage(&options.storage).await?;
     let session = load_session(&storage, &options.session_id).await?;
-    build_outcome(
-        &session,
-        &options.session_id,
-        options.trace_output_dir.as_ref(),
-    )
+    if options.verify_integrity {
+        verify_session_integrity(&session, &options.session_id)?;
+    }
+    let trace_output = options.trace_output_dir.map(TraceOutput::LocalFile);
+    Ok(build_outcome(&session, &options.session_id, trace_output.as_ref()).await?)
 }
 
-pub async fn delete_session(options: DeleteOptions) -> Result<()> {
+/// Delete a session from storage and drop its documents from the configured retriever.
+pub async fn delete_session(options: DeleteOptions) -> Result<(), DeepResearchError> {
+    delete_stored_session(&options).await?;
+
+    if !matches!(options.retriever, RetrieverChoice::Stub) {
+        let retriever = build_retriever(&options.retriever).await?;
+        retriever
+            .delete_session(&options.session_id)
+            .await
+            .map_err(|err| {
+                DeepResearchError::RetrieverError(format!(
+                    "session '{}' deleted but retriever cleanup failed: {err}",
+                    options.session_id
+                ))
+            })?;
+    }
+    Ok(())
+}
+
+/// Copy session `source_id` to `new_id`, recording the origin under `session.cloned_from`.
+///
+/// The copy gets its own context (later writes to either session stay separate), resumes
+/// from the same task, and fails if `new_id` is already taken.
+pub async fn clone_session(
+    source_id: &str,
+    new_id: &str,
+    storage: &Arc<dyn SessionStorage>,
+) -> Result<(), DeepResearchError> {
+    if source_id == new_id {
+        return Err(DeepResearchError::SessionConflict(format!(
+            "clone target must differ from source '{source_id}'"
+        )));
+    }
+    let source = load_session(storage, source_id).await?;
+    let existing = storage.get(new_id).await.map_err(|err| {
+        DeepResearchError::StorageError(format!("failed to load session '{new_id}': {err}"))
+    })?;
+    if existing.is_some() {
+        return Err(DeepResearchError::SessionConflict(format!(
+            "session '{new_id}' already exists"
+        )));
+    }
+
+    // Round-trip through JSON so the copy does not share the source's context handle.
+    let snapshot = serde_json::to_value(&source)
+        .map_err(|err| anyhow!("failed to serialise session '{source_id}': {err}"))?;
+    let mut clone: Session = serde_json::from_value(snapshot)
+        .map_err(|err| anyhow!("failed to copy session '{source_id}': {err}"))?;
+    clone.id = new_id.to_string();
+    clone.context.set("session_id", new_id.to_string()).await;
+    clone
+        .context
+        .set("session.cloned_from", source_id.to_string())
+        .await;
+    clone
+        .context
+        .set(SESSION_CREATED_AT_KEY, chrono::Utc::now().to_rfc3339())
+        .await;
+
+    storage.save(clone).await.map_err(|err| {
+        DeepResearchError::StorageError(format!(
+            "failed to persist cloned session '{new_id}': {err}"
+        ))
+    })?;
+    info!(source = %source_id, clone = %new_id, "cloned session");
+    Ok(())
+}
+
+/// Clone a session using the configured storage backend and return the new session ID.
+pub async fn clone_session_with_options(
+    options: CloneOptions,
+) -> Result<String, DeepResearchError> {
     let storage = init_storage(&options.storage).await?;
-    let session = storage
-        .get(&options.session_id)
-        .await
-        .map_err(|err| anyhow!("failed to load session '{}': {err}", options.session_id))?;
+    let new_id = options.new_id.unwrap_or_else(new_session_id);
+    clone_session(&options.source_id, &new_id, &storage).await?;
+    Ok(new_id)
+}
+
+/// Write session `session_id` to a ZIP archive at `output`.
+///
+/// The bundle holds `session.json` (the stored session, context included, in the shape
+/// `SessionStorage` round-trips), `trace.json` when trace events were recorded,
+/// `summary.md`, and `sources.txt` (one deduplicated source per line). Requires the
+/// `bundle-export` feature; without it this returns an error.
+pub async fn export_session_bundle(
+    session_id: &str,
+    storage: &Arc<dyn SessionStorage>,
+    output: &Path,
+) -> Result<()> {
+    let session = load_session(storage, session_id).await?;
+
+    let session_json = serde_json::to_vec_pretty(&session)
+        .map_err(|err| anyhow!("failed to serialise session '{session_id}': {err}"))?;
+    let events = stored_trace_events(&session);
+    let summary = extract_final_summary(&session);
+    let analysis = session
+        .context
+        .get_sync::<AnalystOutput>("analysis.output")
+        .unwrap_or_default();
+
+    let mut summary_md = format!("# Session {session_id}\n\n");
+    if let Some(query) = session.context.get_sync::<String>("query") {
+        summary_md.push_str(&format!("**Query:** {query}\n\n"));
+    }
+    summary_md.push_str(&summary);
+    summary_md.push('\n');
+
+    let mut sources = dedup_sources(analysis.sources).join("\n");
+    if !sources.is_empty() {
+        sources.push('\n');
+    }
+
+    let mut entries = vec![("session.json", session_json)];
+    if !events.is_empty() {
+        let trace_json = serde_json::to_vec_pretty(&events)
+            .map_err(|err| anyhow!("failed to serialise trace for '{session_id}': {err}"))?;
+        entries.push(("trace.json", trace_json));
+    }
+    entries.push(("summary.md", summary_md.into_bytes()));
+    entries.push(("sources.txt", sources.into_bytes()));
+
+    write_bundle(output, entries)?;
+    info!(%session_id, path = %output.display(), "exported session bundle");
+    Ok(())
+}
+
+#[cfg(feature = "bundle-export")]
+fn write_bundle(output: &Path, entries: Vec<(&str, Vec<u8>)>) -> Result<()> {
+    use std::io::Write;
+
+    if let Some(parent) = output
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| anyhow!("failed to create {}: {err}", parent.display()))?;
+    }
+    let file = std::fs::File::create(output)
+        .map_err(|err| anyhow!("failed to create bundle {}: {err}", output.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let file_options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for (name, bytes) in entries {
+        zip.start_file(name, file_options)
+            .map_err(|err| anyhow!("failed to add {name} to bundle: {err}"))?;
+        zip.write_all(&bytes)
+            .map_err(|err| anyhow!("failed to write {name} to bundle: {err}"))?;
+    }
+    zip.finish()
+        .map_err(|err| anyhow!("failed to finalise bundle {}: {err}", output.display()))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "bundle-export"))]
+fn write_bundle(output: &Path, _entries: Vec<(&str, Vec<u8>)>) -> Result<()> {
+    Err(anyhow!(
+        "cannot write bundle {}: deepresearch-core was built without `bundle-export`",
+        output.display()
+    ))
+}
+
+/// Export a session bundle using the configured storage backend.
+pub async fn export_session_bundle_with_options(options: ExportOptions) -> Result<()> {
+    let storage = init_storage(&options.storage).await?;
+    export_session_bundle(&options.session_id, &storage, &options.output).await
+}
+
+async fn delete_stored_session(options: &DeleteOptions) -> Result<()> {
+    #[cfg(feature = "postgres-session")]
+    if let StorageChoice::Postgres { database_url } = &options.storage {
+        return delete_postgres_session(
+            database_url,
+            &options.session_id,
+            options.cascade_delete_pipeline_records,
+        )
+        .await;
+    }
+
+    if options.cascade_delete_pipeline_records {
+        warn!(
+            session = %options.session_id,
+            "pipeline record cascade only applies to Postgres storage; skipping"
+        );
+    }
+
+    let storage = init_storage(&options.storage).await?;
+    let session = storage.get(&options.session_id).await.map_err(|err| {
+        DeepResearchError::StorageError(format!(
+            "failed to load session '{}': {err}",
+            options.session_id
+        ))
+    })?;
 
     if session.is_none() {
-        return Err(anyhow!("session '{}' not found", options.session_id));
+        return Err(DeepResearchError::SessionNotFound(options.session_id.clone()).into());
     }
 
-    storage
-        .delete(&options.session_id)
+    storage.delete(&options.session_id).await.map_err(|err| {
+        DeepResearchError::StorageError(format!(
+            "failed to delete session '{}': {err}",
+            options.session_id
+        ))
+    })?;
+    Ok(())
+}
+
+/// Delete the session row directly so removal does not depend on the session having been
+/// finalised, optionally cascading to data-pipeline records within the same transaction.
+#[cfg(feature = "postgres-session")]
+async fn delete_postgres_session(
+    database_url: &str,
+    session_id: &str,
+    cascade_pipeline_records: bool,
+) -> Result<()> {
+    let pool = sqlx::PgPool::connect(database_url)
+        .await
+        .map_err(|err| anyhow!("failed to connect Postgres session storage: {err}"))?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| anyhow!("failed to open delete transaction: {err}"))?;
+
+    let deleted = sqlx::query("DELETE FROM sessions WHERE id = $1")
+        .bind(session_id)
+        .execute(&mut *tx)
         .await
-        .map_err(|err| anyhow!("failed to delete session '{}': {err}", options.session_id))?;
+        .map_err(|err| anyhow!("failed to delete session '{session_id}': {err}"))?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(DeepResearchError::SessionNotFound(session_id.to_string()).into());
+    }
+
+    if cascade_pipeline_records {
+        // The data-pipeline table only exists once the consolidation job has run.
+        let has_records_table: bool =
+            sqlx::query_scalar("SELECT to_regclass('session_records') IS NOT NULL")
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|err| anyhow!("failed to inspect pipeline tables: {err}"))?;
+        if has_records_table {
+            sqlx::query("DELETE FROM session_records WHERE session_id = $1")
+                .bind(session_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|err| {
+                    anyhow!("failed to delete pipeline records for '{session_id}': {err}")
+                })?;
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|err| anyhow!("failed to commit delete transaction: {err}"))?;
     Ok(())
 }
 
+/// Delete every stored session whose `session.created_at` is older than `older_than`.
+///
+/// Only backends that can enumerate sessions are supported: the in-memory backend is
+/// process-local and always starts empty, and custom `SessionStorage` implementations
+/// expose no listing API, so those return `Ok(0)` and an error respectively.
+pub async fn purge_old_sessions(storage: &StorageChoice, older_than: Duration) -> Result<usize> {
+    let cutoff = chrono::Utc::now()
+        - chrono::Duration::from_std(older_than)
+            .map_err(|err| anyhow!("invalid purge threshold: {err}"))?;
+
+    match storage {
+        StorageChoice::InMemory => Ok(0),
+        #[cfg(feature = "postgres-session")]
+        StorageChoice::Postgres { database_url } => {
+            purge_postgres_sessions(database_url, cutoff).await
+        }
+        StorageChoice::Custom { .. } => {
+            let _ = cutoff;
+            Err(anyhow!(
+                "custom session storage cannot enumerate sessions; purge them via the owning service"
+            ))
+        }
+    }
+}
+
+#[cfg(feature = "postgres-session")]
+async fn purge_postgres_sessions(
+    database_url: &str,
+    cutoff: chrono::DateTime<chrono::Utc>,
+) -> Result<usize> {
+    let pool = sqlx::PgPool::connect(database_url)
+        .await
+        .map_err(|err| anyhow!("failed to connect Postgres session storage: {err}"))?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| anyhow!("failed to open purge transaction: {err}"))?;
+
+    // Sessions created before `session.created_at` was recorded fall back to the row timestamp.
+    let result = sqlx::query(
+        "DELETE FROM sessions \
+         WHERE COALESCE((context -> 'data' ->> 'session.created_at')::timestamptz, created_at) < $1",
+    )
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| anyhow!("failed to purge sessions: {err}"))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| anyhow!("failed to commit purge transaction: {err}"))?;
+    Ok(result.rows_affected() as usize)
+}
+
+const DEFAULT_ARCHIVE_TABLE: &str = "archived_sessions";
+
+/// Options for moving old sessions out of the live `sessions` table.
+pub struct ArchiveOptions {
+    pub storage: StorageChoice,
+    pub older_than: Duration,
+    /// Destination table; defaults to `archived_sessions` and is created when absent.
+    pub archive_table: Option<String>,
+}
+
+impl ArchiveOptions {
+    pub fn new(older_than: Duration) -> Self {
+        Self {
+            storage: StorageChoice::InMemory,
+            older_than,
+            archive_table: None,
+        }
+    }
+
+    pub fn with_storage(mut self, storage: StorageChoice) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    #[cfg(feature = "postgres-session")]
+    pub fn with_postgres_storage(mut self, database_url: impl Into<String>) -> Self {
+        self.storage = StorageChoice::postgres(database_url);
+        self
+    }
+
+    pub fn with_archive_table(mut self, table: impl Into<String>) -> Self {
+        self.archive_table = Some(table.into());
+        self
+    }
+
+    fn archive_table(&self) -> &str {
+        self.archive_table
+            .as_deref()
+            .unwrap_or(DEFAULT_ARCHIVE_TABLE)
+    }
+}
+
+/// Result of [`archive_old_sessions`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ArchiveReport {
+    pub moved_count: usize,
+}
+
+/// Move every stored session whose `session.created_at` is older than `older_than` into
+/// the archive table, inside a single transaction.
+///
+/// Backend support matches [`purge_old_sessions`]. The archive table name is spliced into
+/// SQL, so it must be a plain identifier (`[A-Za-z_][A-Za-z0-9_]*`).
+pub async fn archive_old_sessions(options: ArchiveOptions) -> Result<ArchiveReport> {
+    let table = options.archive_table();
+    validate_table_identifier(table)?;
+    let cutoff = chrono::Utc::now()
+        - chrono::Duration::from_std(options.older_than)
+            .map_err(|err| anyhow!("invalid archive threshold: {err}"))?;
+
+    match &options.storage {
+        StorageChoice::InMemory => Ok(ArchiveReport::default()),
+        #[cfg(feature = "postgres-session")]
+        StorageChoice::Postgres { database_url } => {
+            archive_postgres_sessions(database_url, table, cutoff).await
+        }
+        StorageChoice::Custom { .. } => {
+            let _ = cutoff;
+            Err(anyhow!(
+                "custom session storage cannot enumerate sessions; archive them via the owning service"
+            ))
+        }
+    }
+}
+
+fn validate_table_identifier(table: &str) -> Result<()> {
+    let mut chars = table.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_');
+    if valid_start && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_') {
+        Ok(())
+    } else {
+        Err(anyhow!("invalid archive table name '{table}'"))
+    }
+}
+
+#[cfg(feature = "postgres-session")]
+async fn archive_postgres_sessions(
+    database_url: &str,
+    table: &str,
+    cutoff: chrono::DateTime<chrono::Utc>,
+) -> Result<ArchiveReport> {
+    let pool = sqlx::PgPool::connect(database_url)
+        .await
+        .map_err(|err| anyhow!("failed to connect Postgres session storage: {err}"))?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|err| anyhow!("failed to open archive transaction: {err}"))?;
+
+    // Mirror the live schema so archived rows can be restored with a plain INSERT ... SELECT.
+    sqlx::query(&format!(
+        "CREATE TABLE IF NOT EXISTS {table} \
+         (LIKE sessions INCLUDING ALL, archived_at TIMESTAMPTZ NOT NULL DEFAULT now())"
+    ))
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| anyhow!("failed to create archive table '{table}': {err}"))?;
+
+    let result = sqlx::query(&format!(
+        "WITH moved AS ( \
+             DELETE FROM sessions \
+             WHERE COALESCE((context -> 'data' ->> 'session.created_at')::timestamptz, created_at) < $1 \
+             RETURNING * \
+         ) \
+         INSERT INTO {table} SELECT * FROM moved"
+    ))
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| anyhow!("failed to archive sessions into '{table}': {err}"))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| anyhow!("failed to commit archive transaction: {err}"))?;
+    Ok(ArchiveReport {
+        moved_count: result.rows_affected() as usize,
+    })
+}
+
+/// Stored session metadata returned by [`list_sessions`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub query: Option<String>,
+}
+
+/// Options for enumerating stored sessions, newest first.
+pub struct ListOptions {
+    pub storage: StorageChoice,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+impl ListOptions {
+    pub fn new() -> Self {
+        Self {
+            storage: StorageChoice::InMemory,
+            limit: None,
+            offset: 0,
+        }
+    }
+
+    pub fn with_storage(mut self, storage: StorageChoice) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    #[cfg(feature = "postgres-session")]
+    pub fn with_postgres_storage(mut self, database_url: impl Into<String>) -> Self {
+        self.storage = StorageChoice::postgres(database_url);
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// List stored sessions ordered by `session.created_at`, newest first.
+///
+/// Only Postgres storage can be enumerated: `SessionStorage` has no listing method, and
+/// an empty result for the other backends would be indistinguishable from "no sessions".
+pub async fn list_sessions(options: ListOptions) -> Result<Vec<SessionSummary>> {
+    match &options.storage {
+        StorageChoice::InMemory => Err(anyhow!(
+            "in-memory session storage cannot enumerate sessions; use Postgres storage to list them"
+        )),
+        #[cfg(feature = "postgres-session")]
+        StorageChoice::Postgres { database_url } => {
+            list_postgres_sessions(database_url, options.limit, options.offset).await
+        }
+        StorageChoice::Custom { .. } => Err(anyhow!(
+            "custom session storage cannot enumerate sessions; list them via the owning service"
+        )),
+    }
+}
+
+#[cfg(feature = "postgres-session")]
+async fn list_postgres_sessions(
+    database_url: &str,
+    limit: Option<usize>,
+    offset: usize,
+) -> Result<Vec<SessionSummary>> {
+    let pool = sqlx::PgPool::connect(database_url)
+        .await
+        .map_err(|err| anyhow!("failed to connect Postgres session storage: {err}"))?;
+
+    // A NULL limit returns every remaining row.
+    let rows: Vec<(String, chrono::DateTime<chrono::Utc>, Option<String>)> = sqlx::query_as(
+        "SELECT id, \
+                COALESCE((context -> 'data' ->> 'session.created_at')::timestamptz, created_at) AS created, \
+                context -> 'data' ->> 'query' \
+         FROM sessions \
+         ORDER BY created DESC, id \
+         LIMIT $1 OFFSET $2",
+    )
+    .bind(limit.map(|limit| limit as i64))
+    .bind(offset as i64)
+    .fetch_all(&pool)
+    .await
+    .map_err(|err| anyhow!("failed to list sessions: {err}"))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(session_id, created_at, query)| SessionSummary {
+            session_id,
+            created_at,
+            query,
+        })
+        .collect())
+}
+
 pub struct IngestOptions {
     pub session_id: String,
     pub documents: Vec<IngestDocument>,
     pub retriever: RetrieverChoice,
+    /// Split documents into windows of this many words before embedding (`None` ingests whole documents).
+    pub chunk_size: Option<usize>,
+    /// Words shared between consecutive chunks when `chunk_size` is set.
+    pub chunk_overlap: usize,
 }
 
-pub async fn ingest_documents(options: IngestOptions) -> Result<()> {
+impl IngestOptions {
+    fn chunked_documents(self) -> (String, Vec<IngestDocument>) {
+        let documents = match self.chunk_size {
+            Some(size) => self
+                .documents
+                .into_iter()
+                .flat_map(|doc| doc.into_chunks(size, self.chunk_overlap))
+                .collect(),
+            None => self.documents,
+        };
+        (self.session_id, documents)
+    }
+}
+
+pub async fn ingest_documents(options: IngestOptions) -> Result<(), DeepResearchError> {
     let retriever = build_retriever(&options.retriever).await?;
+    let (session_id, documents) = options.chunked_documents();
     retriever
-        .ingest(&options.session_id, options.documents)
-        .await?;
+        .ingest(&session_id, documents)
+        .await
+        .map_err(|err| DeepResearchError::RetrieverError(format!("{err:#}")))?;
     Ok(())
 }
+
+/// Report how many documents the Qdrant retriever holds for `session_id`.
+///
+/// Only a Qdrant client is opened (no embedding model is loaded); other retrievers have no
+/// collection to inspect and return [`DeepResearchError::RetrieverError`].
+pub async fn session_index_info(
+    retriever: &RetrieverChoice,
+    session_id: &str,
+) -> Result<CollectionInfo, DeepResearchError> {
+    match retriever {
+        RetrieverChoice::Qdrant {
+            url, collection, ..
+        } => {
+            #[cfg(feature = "qdrant-retriever")]
+            {
+                let client = qdrant_client::Qdrant::from_url(url)
+                    .build()
+                    .map_err(|err| {
+                        DeepResearchError::RetrieverError(format!(
+                            "failed to create Qdrant client: {err}"
+                        ))
+                    })?;
+                crate::memory::qdrant::session_collection_info(&client, collection, session_id)
+                    .await
+                    .map_err(|err| DeepResearchError::RetrieverError(format!("{err:#}")))
+            }
+            #[cfg(not(feature = "qdrant-retriever"))]
+            {
+                let _ = (url, collection, session_id);
+                Err(DeepResearchError::RetrieverError(
+                    "qdrant retriever support not enabled; rebuild with `--features deepresearch-core/qdrant-retriever`".to_string(),
+                ))
+            }
+        }
+        RetrieverChoice::Stub | RetrieverChoice::Sqlite { .. } => {
+            Err(DeepResearchError::RetrieverError(
+                "index info is only available for the Qdrant retriever".to_string(),
+            ))
+        }
+    }
+}
+
+/// Outcome of [`ingest_documents_bulk`]; failed sessions are reported rather than aborting the batch.
+#[derive(Debug, Default)]
+pub struct BulkIngestReport {
+    pub sessions_processed: usize,
+    pub documents_indexed: usize,
+    pub errors: Vec<(String, anyhow::Error)>,
+}
+
+/// Ingest documents for many sessions, issuing one retriever batch per distinct retriever config.
+pub async fn ingest_documents_bulk(options: Vec<IngestOptions>) -> Result<BulkIngestReport> {
+    let mut groups: Vec<(RetrieverChoice, Vec<(String, Vec<IngestDocument>)>)> = Vec::new();
+    for option in options {
+        let choice = option.retriever.clone();
+        let batch = option.chunked_documents();
+        match groups.iter_mut().find(|(existing, _)| *existing == choice) {
+            Some((_, batches)) => batches.push(batch),
+            None => groups.push((choice, vec![batch])),
+        }
+    }
+
+    let mut report = BulkIngestReport::default();
+    for (choice, batches) in groups {
+        let sessions: Vec<String> = batches.iter().map(|(id, _)| id.clone()).collect();
+        let documents: usize = batches.iter().map(|(_, docs)| docs.len()).sum();
+
+        let outcome = match build_retriever(&choice).await {
+            Ok(retriever) => retriever.ingest_batches(batches).await,
+            Err(err) => Err(err),
+        };
+        match outcome {
+            Ok(()) => {
+                report.sessions_processed += sessions.len();
+                report.documents_indexed += documents;
+            }
+            Err(err) => {
+                warn!(error = %err, sessions = sessions.len(), "bulk ingestion failed for retriever group");
+                let message = format!("{err:#}");
+                report.errors.extend(
+                    sessions
+                        .into_iter()
+                        .map(|session_id| (session_id, anyhow!(message.clone()))),
+                );
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> ContextSchema {
+        HashMap::from([
+            ("request.id".to_string(), ContextValueType::String),
+            ("research.preseeded".to_string(), ContextValueType::Bool),
+            ("budget.max_tokens".to_string(), ContextValueType::Number),
+            ("extra".to_string(), ContextValueType::Json),
+        ])
+    }
+
+    #[test]
+    fn context_schema_accepts_matching_values() {
+        let entries = vec![
+            ("request.id".to_string(), json!("req-1")),
+            ("research.preseeded".to_string(), json!(true)),
+            ("budget.max_tokens".to_string(), json!(2048)),
+            ("extra".to_string(), json!({"nested": [1, 2]})),
+        ];
+        validate_initial_context(&schema(), &entries).expect("valid context");
+    }
+
+    #[test]
+    fn integrity_check_rejects_half_written_session() {
+        let session = Session::new_from_task("partial".to_string(), "researcher");
+        session.context.set_sync("critique.verdict", "Looks good");
+
+        let err = verify_session_integrity(&session, "partial").expect_err("summary missing");
+        assert!(err.to_string().contains("final.summary"));
+        assert!(!err.to_string().contains("critique.verdict"));
+
+        session
+            .context
+            .set_sync("final.summary", "Complete summary");
+        verify_session_integrity(&session, "partial").expect("complete session");
+    }
+
+    #[tokio::test]
+    async fn load_with_verify_integrity_rejects_half_written_session() {
+        let storage: Arc<dyn SessionStorage> = Arc::new(InMemorySessionStorage::new());
+        let session = Session::new_from_task("partial-load".to_string(), "researcher");
+        session.context.set_sync("critique.verdict", "Looks good");
+        storage.save(session).await.unwrap();
+
+        load_session_report(LoadOptions::new("partial-load").with_shared_storage(storage.clone()))
+            .await
+            .expect("unverified load tolerates missing keys");
+        let err = load_session_report(
+            LoadOptions::new("partial-load")
+                .with_shared_storage(storage)
+                .with_verify_integrity(true),
+        )
+        .await
+        .expect_err("summary missing");
+        assert!(err.to_string().contains("final.summary"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn outcome_reports_completion_once_final_summary_exists() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let output = TraceOutput::LocalFile(dir.path().to_path_buf());
+        let session = Session::new_from_task("watching".to_string(), "researcher");
+        session.context.set_sync("trace.enabled", true);
+        session.context.set_sync(
+            "trace.events",
+            vec![TraceEvent::new("researcher", "collected sources")],
+        );
+        let running = build_outcome(&session, "watching", Some(&output))
+            .await
+            .expect("outcome");
+        assert!(!running.completed);
+        assert!(running.trace_path.is_none());
+        assert!(std::fs::read_dir(dir.path()).unwrap().next().is_none());
+
+        session.context.set_sync("final.summary", "Done");
+        let finished = build_outcome(&session, "watching", Some(&output))
+            .await
+            .expect("outcome");
+        assert!(finished.completed);
+        assert_eq!(finished.summary, "Done");
+        assert!(finished.trace_path.is_some());
+    }
+
+    #[test]
+    fn context_schema_rejects_type_mismatch() {
+        let entries = vec![
+            ("request.id".to_string(), json!(42)),
+            ("research.preseeded".to_string(), json!("yes")),
+        ];
+        let err = validate_initial_context(&schema(), &entries)
+            .expect_err("mismatches must fail")
+            .to_string();
+        assert!(
+            err.contains("'request.id' expects String but got number"),
+            "{err}"
+        );
+        assert!(
+            err.contains("'research.preseeded' expects Bool but got string"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn context_schema_rejects_unknown_keys() {
+        let entries = vec![("requets.id".to_string(), json!("typo"))];
+        let err = validate_initial_context(&schema(), &entries)
+            .expect_err("typo must fail")
+            .to_string();
+        assert!(err.contains("unknown context key 'requets.id'"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn session_fails_fast_on_schema_violation() {
+        let err = run_research_session_with_report(
+            SessionOptions::new("schema check")
+                .with_initial_context("request.id", json!(false))
+                .with_context_schema(schema()),
+        )
+        .await
+        .expect_err("session must not start");
+        assert!(err.to_string().contains("schema validation"));
+    }
+
+    async fn stored_queries(options: SessionOptions<'_>) -> (String, String) {
+        let storage = Arc::new(InMemorySessionStorage::new());
+        let outcome =
+            run_research_session_with_report(options.with_storage(StorageChoice::Custom {
+                storage: storage.clone(),
+            }))
+            .await
+            .expect("session runs");
+        let session = storage
+            .get(&outcome.session_id)
+            .await
+            .unwrap()
+            .expect("session stored");
+        (
+            session.context.get_sync("query.original").unwrap(),
+            session.context.get_sync("query").unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn identity_preprocessor_keeps_query() {
+        let (original, query) = stored_queries(
+            SessionOptions::new("grid outlook")
+                .with_query_preprocessor(Arc::new(|query: &str| query.to_string())),
+        )
+        .await;
+        assert_eq!(original, "grid outlook");
+        assert_eq!(query, "grid outlook");
+    }
+
+    #[tokio::test]
+    async fn uppercasing_preprocessor_rewrites_query() {
+        let (original, query) = stored_queries(
+            SessionOptions::new("grid outlook")
+                .with_query_preprocessor(Arc::new(|query: &str| query.to_uppercase())),
+        )
+        .await;
+        assert_eq!(original, "grid outlook");
+        assert_eq!(query, "GRID OUTLOOK");
+    }
+
+    #[tokio::test]
+    async fn preprocessor_can_share_state_across_tasks() {
+        // Lookups prepared ahead of time (e.g. fetched asynchronously) are shared via Arc.
+        let acronyms = Arc::new(HashMap::from([("LNG", "liquefied natural gas")]));
+        let preprocessor: QueryPreprocessor = Arc::new(move |query: &str| {
+            query
+                .split_whitespace()
+                .map(|word| acronyms.get(word).copied().unwrap_or(word))
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+
+        let handle = tokio::spawn(stored_queries(
+            SessionOptions::new("LNG demand").with_query_preprocessor(preprocessor),
+        ));
+        let (original, query) = handle.await.unwrap();
+        assert_eq!(original, "LNG demand");
+        assert_eq!(query, "liquefied natural gas demand");
+    }
+}
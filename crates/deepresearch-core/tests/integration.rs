@@ -1,15 +1,20 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use deepresearch_core::{
-    FactCheckSettings, ResumeOptions, SandboxExecutor, SandboxRequest, SandboxResult,
-    SessionOptions, resume_research_session, run_research_session,
-    run_research_session_with_options,
+    ArchiveOptions, ArchiveReport, BaseGraphTasks, DeepResearchError, FactCheckSettings,
+    GraphCustomizer, IngestDocument, IngestOptions, ListOptions, ResumeOptions, RetrievedDocument,
+    Retriever, RetrieverChoice, SandboxExecutor, SandboxRequest, SandboxResult, SeedRetriever,
+    SessionOptions, StandardPipelineBuilder, StorageChoice, SummaryTemplate,
+    TRACE_TAG_CONFIDENCE_SCORE, TRACE_TAG_VERDICT, archive_old_sessions, clone_session,
+    ingest_documents_bulk, list_sessions, purge_old_sessions, resume_research_session,
+    run_research_session, run_research_session_with_options, run_research_session_with_report,
 };
-use graph_flow::{InMemorySessionStorage, SessionStorage};
+use graph_flow::{GraphBuilder, InMemorySessionStorage, Session, SessionStorage, Task};
 use insta::assert_snapshot;
 use serde_json::json;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 #[tokio::test]
@@ -35,6 +40,7 @@ async fn manual_review_branch_triggers() {
             min_confidence: 0.95,
             verification_count: 0,
             timeout_ms: 0,
+            ..FactCheckSettings::default()
         });
 
     let summary = run_research_session_with_options(options)
@@ -154,6 +160,832 @@ async fn math_context_keys_are_stable() {
     );
 }
 
+#[tokio::test]
+async fn context7_prefix_applied_only_when_enabled() {
+    let storage = Arc::new(InMemorySessionStorage::new());
+
+    let prefixed_id = Uuid::new_v4().to_string();
+    run_research_session_with_options(
+        SessionOptions::new("Compare grid storage vendors")
+            .with_session_id(prefixed_id.clone())
+            .with_shared_storage(storage.clone())
+            .with_context7_prefix(),
+    )
+    .await
+    .expect("workflow should succeed");
+
+    let verbatim_id = Uuid::new_v4().to_string();
+    run_research_session_with_options(
+        SessionOptions::new("Compare grid storage vendors")
+            .with_session_id(verbatim_id.clone())
+            .with_shared_storage(storage.clone()),
+    )
+    .await
+    .expect("workflow should succeed");
+
+    let stored_query = |session: Option<graph_flow::Session>| {
+        session
+            .expect("session should exist after run")
+            .context
+            .get_sync::<String>("query")
+            .expect("query key missing")
+    };
+
+    let prefixed = stored_query(storage.get(&prefixed_id).await.expect("lookup"));
+    assert_eq!(prefixed, "use context7 Compare grid storage vendors");
+
+    let verbatim = stored_query(storage.get(&verbatim_id).await.expect("lookup"));
+    assert_eq!(verbatim, "Compare grid storage vendors");
+}
+
+#[tokio::test]
+async fn sessions_record_creation_time_for_pruning() {
+    let storage = Arc::new(InMemorySessionStorage::new());
+    let session_id = Uuid::new_v4().to_string();
+    run_research_session_with_options(
+        SessionOptions::new("Survey sodium-ion supply chains")
+            .with_session_id(session_id.clone())
+            .with_shared_storage(storage.clone()),
+    )
+    .await
+    .expect("workflow should succeed");
+
+    let session = storage
+        .get(&session_id)
+        .await
+        .expect("lookup")
+        .expect("session should exist after run");
+    let created_at = session
+        .context
+        .get_sync::<String>("session.created_at")
+        .expect("session.created_at missing");
+    assert!(chrono::DateTime::parse_from_rfc3339(&created_at).is_ok());
+
+    let purged = purge_old_sessions(&StorageChoice::InMemory, Duration::from_secs(60))
+        .await
+        .expect("in-memory purge should succeed");
+    assert_eq!(purged, 0);
+
+    let custom = StorageChoice::Custom { storage };
+    assert!(
+        purge_old_sessions(&custom, Duration::from_secs(60))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn list_sessions_rejects_backends_that_cannot_enumerate() {
+    let err = list_sessions(ListOptions::new().with_limit(10))
+        .await
+        .expect_err("in-memory storage cannot be listed");
+    assert!(err.to_string().contains("cannot enumerate"), "{err}");
+
+    let custom = StorageChoice::Custom {
+        storage: Arc::new(InMemorySessionStorage::new()),
+    };
+    assert!(
+        list_sessions(ListOptions::new().with_storage(custom))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn archive_old_sessions_validates_options_per_backend() {
+    let report = archive_old_sessions(ArchiveOptions::new(Duration::from_secs(60)))
+        .await
+        .expect("in-memory archive should succeed");
+    assert_eq!(report, ArchiveReport { moved_count: 0 });
+
+    let injected = archive_old_sessions(
+        ArchiveOptions::new(Duration::from_secs(60)).with_archive_table("sessions; DROP TABLE x"),
+    )
+    .await
+    .expect_err("non-identifier table names must be rejected");
+    assert!(injected.to_string().contains("invalid archive table name"));
+
+    let custom = StorageChoice::Custom {
+        storage: Arc::new(InMemorySessionStorage::new()),
+    };
+    assert!(
+        archive_old_sessions(ArchiveOptions::new(Duration::from_secs(60)).with_storage(custom))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn dry_run_validates_graph_without_running_tasks() {
+    let storage = Arc::new(InMemorySessionStorage::new());
+    let outcome = run_research_session_with_report(
+        SessionOptions::new("Validate the deployment graph")
+            .with_session_id("dry-run")
+            .with_shared_storage(storage.clone())
+            .with_dry_run(true),
+    )
+    .await
+    .expect("dry run should validate");
+
+    assert_eq!(outcome.summary, "dry-run: graph validated");
+    let order: Vec<&str> = outcome
+        .trace_events
+        .iter()
+        .map(|event| event.task_id.as_str())
+        .collect();
+    assert_eq!(
+        order[..4],
+        ["researcher", "analyst", "fact_check", "critic"]
+    );
+    assert!(order.contains(&"finalize") && order.contains(&"manual_review"));
+    assert!(storage.get("dry-run").await.expect("lookup").is_none());
+}
+
+#[tokio::test]
+async fn dry_run_rejects_cyclic_graph() {
+    let customizer: Box<GraphCustomizer> =
+        Box::new(|builder: GraphBuilder, tasks: &BaseGraphTasks| {
+            builder.add_edge(tasks.analyst.id(), tasks.research.id())
+        });
+    let err = run_research_session_with_report(
+        SessionOptions::new("Validate a cyclic graph")
+            .with_customizer(customizer)
+            .with_dry_run(true),
+    )
+    .await
+    .expect_err("cycle must be rejected");
+
+    assert!(matches!(err, DeepResearchError::PlanningError(_)));
+    assert!(err.to_string().contains("cycle detected"), "{err}");
+}
+
+#[tokio::test]
+async fn trace_events_carry_standard_tags() {
+    let outcome = run_research_session_with_report(
+        SessionOptions::new("Tag the fact-check and critic trace events").enable_trace(),
+    )
+    .await
+    .expect("workflow should succeed");
+
+    let tagged = |task: &str, tag: &str| {
+        outcome
+            .trace_events
+            .iter()
+            .find(|event| event.task_id == task)
+            .and_then(|event| event.tags.get(tag).cloned())
+    };
+    assert!(
+        tagged("fact_check", TRACE_TAG_CONFIDENCE_SCORE).is_some_and(|value| value.is_number())
+    );
+    assert!(tagged("critic", TRACE_TAG_VERDICT).is_some_and(|value| value.is_string()));
+}
+
+#[tokio::test]
+async fn preseeded_documents_bypass_retriever() {
+    let storage = Arc::new(InMemorySessionStorage::new());
+    let session_id = Uuid::new_v4().to_string();
+    let outcome = run_research_session_with_report(
+        SessionOptions::new("Summarise our own retrieval results")
+            .with_session_id(session_id.clone())
+            .with_shared_storage(storage.clone())
+            .enable_trace()
+            .with_preseeded_documents(vec![
+                RetrievedDocument {
+                    text: "Lower-ranked finding".into(),
+                    score: 0.2,
+                    source: Some("https://example.com/low".into()),
+                },
+                RetrievedDocument {
+                    text: "Top-ranked finding".into(),
+                    score: 0.9,
+                    source: Some("https://example.com/high".into()),
+                },
+            ]),
+    )
+    .await
+    .expect("workflow should succeed");
+
+    let session = storage
+        .get(&session_id)
+        .await
+        .expect("lookup")
+        .expect("session should exist after run");
+    assert_eq!(
+        session.context.get_sync::<bool>("research.preseeded"),
+        Some(true)
+    );
+    let sources = session
+        .context
+        .get_sync::<Vec<String>>("research.sources")
+        .expect("research.sources missing");
+    assert_eq!(
+        sources,
+        vec!["https://example.com/high", "https://example.com/low"]
+    );
+    assert!(
+        outcome
+            .trace_events
+            .iter()
+            .any(|event| event.message.contains("preseeded"))
+    );
+}
+
+#[tokio::test]
+async fn outcome_lists_unique_sources() {
+    let document = |text: &str, score: f32| RetrievedDocument {
+        text: text.into(),
+        score,
+        source: Some("https://example.com/shared".into()),
+    };
+    let outcome = run_research_session_with_report(
+        SessionOptions::new("Summarise duplicated citations").with_preseeded_documents(vec![
+            document("First finding", 0.9),
+            document("Second finding", 0.5),
+        ]),
+    )
+    .await
+    .expect("workflow should succeed");
+
+    assert_eq!(outcome.sources, vec!["https://example.com/shared"]);
+}
+
+#[tokio::test]
+async fn timed_out_task_is_skipped() {
+    let storage = Arc::new(InMemorySessionStorage::new());
+    let session_id = Uuid::new_v4().to_string();
+    let outcome = run_research_session_with_report(
+        SessionOptions::new("Assess sodium-ion adoption")
+            .with_session_id(session_id.clone())
+            .with_shared_storage(storage.clone())
+            .enable_trace()
+            .with_task_timeout("researcher", Duration::from_millis(1)),
+    )
+    .await
+    .expect("workflow should continue past the timed-out task");
+
+    let session = storage
+        .get(&session_id)
+        .await
+        .expect("lookup")
+        .expect("session should exist after run");
+    assert_eq!(
+        session.context.get_sync::<bool>("researcher.timed_out"),
+        Some(true)
+    );
+    assert!(!outcome.summary.is_empty());
+    assert!(
+        outcome
+            .trace_events
+            .iter()
+            .any(|event| event.task_id == "researcher" && event.message.contains("timed out"))
+    );
+}
+
+#[tokio::test]
+async fn cancelled_session_short_circuits() {
+    let storage = Arc::new(InMemorySessionStorage::new());
+    let session_id = Uuid::new_v4().to_string();
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        canceller.cancel();
+    });
+
+    let outcome = run_research_session_with_report(
+        SessionOptions::new("Assess flow battery economics")
+            .with_session_id(session_id.clone())
+            .with_shared_storage(storage.clone())
+            .with_cancellation_token(token),
+    )
+    .await
+    .expect("cancellation is not an error");
+
+    assert!(outcome.cancelled);
+    assert!(outcome.summary.is_empty());
+    let session = storage
+        .get(&session_id)
+        .await
+        .expect("lookup")
+        .expect("session is persisted before execution starts");
+    assert!(
+        session
+            .context
+            .get_sync::<String>("final.summary")
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn analyst_template_shapes_final_summary() {
+    let template: SummaryTemplate = Arc::new(|findings: &[String], sources: &[String]| {
+        format!(
+            "Holding: {}. Authorities cited: {}.",
+            findings.first().cloned().unwrap_or_default(),
+            sources.len()
+        )
+    });
+    let summary = run_research_session_with_options(
+        SessionOptions::new("Summarise precedent on data retention")
+            .with_analyst_template(template),
+    )
+    .await
+    .expect("workflow should succeed");
+
+    assert!(
+        summary.contains("Holding: "),
+        "summary should use the analyst template: {summary}"
+    );
+    assert!(!summary.contains("Top insights"));
+}
+
+async fn run_standard_pipeline(builder: StandardPipelineBuilder) -> (Vec<String>, Session) {
+    let storage = Arc::new(InMemorySessionStorage::new());
+    let session_id = Uuid::new_v4().to_string();
+    let outcome = run_research_session_with_report(
+        SessionOptions::new("Map the sodium-ion supply chain")
+            .with_session_id(session_id.clone())
+            .with_shared_storage(storage.clone())
+            .enable_trace()
+            .with_customizer(builder.build())
+            .with_initial_context(
+                "math.request",
+                json!({
+                    "script_name": "pipeline_math.py",
+                    "script": "print('ok')",
+                    "args": [],
+                    "files": [],
+                    "expected_outputs": [],
+                    "timeout_ms": 1000
+                }),
+            ),
+    )
+    .await
+    .expect("workflow should succeed");
+
+    let mut order: Vec<String> = Vec::new();
+    for event in outcome.trace_events {
+        if !order.contains(&event.task_id) {
+            order.push(event.task_id);
+        }
+    }
+    let session = storage
+        .get(&session_id)
+        .await
+        .expect("lookup")
+        .expect("session should exist after run");
+    (order, session)
+}
+
+fn extra_retriever(text: &str) -> Arc<dyn Retriever> {
+    Arc::new(SeedRetriever::new(vec![RetrievedDocument {
+        text: text.into(),
+        score: 0.8,
+        source: Some("https://example.com/extra".into()),
+    }]))
+}
+
+#[tokio::test]
+async fn session_report_runs_math_tool_with_sandbox_executor() {
+    let session_id = Uuid::new_v4().to_string();
+    let storage = Arc::new(InMemorySessionStorage::new());
+    let options = SessionOptions::new("use context7 verify math wiring in reports")
+        .with_session_id(session_id.clone())
+        .with_shared_storage(storage.clone())
+        .with_sandbox_executor(Arc::new(StubSandbox))
+        .with_initial_context(
+            "math.request",
+            json!({
+                "script_name": "report_math.py",
+                "script": "print('ok')",
+                "timeout_ms": 1000
+            }),
+        )
+        .enable_trace();
+
+    let outcome = run_research_session_with_report(options)
+        .await
+        .expect("workflow should succeed");
+
+    let order: Vec<&str> = outcome
+        .trace_events
+        .iter()
+        .map(|event| event.task_id.as_str())
+        .filter(|task| matches!(*task, "researcher" | "math_tool" | "analyst"))
+        .collect();
+    let math = order.iter().position(|task| *task == "math_tool");
+    assert!(math.is_some(), "math_tool missing from trace: {order:?}");
+    assert!(order.iter().position(|task| *task == "researcher") < math);
+    assert!(order.iter().position(|task| *task == "analyst") > math);
+
+    let session = storage
+        .get(&session_id)
+        .await
+        .expect("storage lookup succeeds")
+        .expect("session should exist after run");
+    assert_eq!(
+        session.context.get_sync::<String>("math.status").as_deref(),
+        Some("success")
+    );
+}
+
+#[tokio::test]
+async fn cloned_session_copies_context_independently() {
+    let storage: Arc<dyn SessionStorage> = Arc::new(InMemorySessionStorage::new());
+    let source_id = Uuid::new_v4().to_string();
+    run_research_session_with_options(
+        SessionOptions::new("Compare fact-check thresholds for grid storage")
+            .with_session_id(source_id.clone())
+            .with_shared_storage(storage.clone()),
+    )
+    .await
+    .expect("workflow should succeed");
+
+    let clone_id = Uuid::new_v4().to_string();
+    clone_session(&source_id, &clone_id, &storage)
+        .await
+        .expect("clone succeeds");
+
+    let source = storage.get(&source_id).await.unwrap().unwrap();
+    let clone = storage.get(&clone_id).await.unwrap().unwrap();
+    assert_eq!(
+        clone.context.get_sync::<String>("session.cloned_from"),
+        Some(source_id.clone())
+    );
+    assert_eq!(
+        clone.context.get_sync::<String>("session_id"),
+        Some(clone_id.clone())
+    );
+    assert_eq!(
+        clone.context.get_sync::<String>("final.summary"),
+        source.context.get_sync::<String>("final.summary")
+    );
+
+    clone.context.set("query", "changed".to_string()).await;
+    assert_ne!(
+        source.context.get_sync::<String>("query").as_deref(),
+        Some("changed")
+    );
+    assert!(
+        source
+            .context
+            .get_sync::<String>("session.cloned_from")
+            .is_none()
+    );
+
+    let err = clone_session(&source_id, &clone_id, &storage)
+        .await
+        .expect_err("target already exists");
+    assert!(
+        matches!(err, DeepResearchError::SessionConflict(_)),
+        "{err}"
+    );
+    assert!(err.to_string().contains("already exists"));
+}
+
+#[tokio::test]
+async fn standard_pipeline_defaults_to_base_wiring() {
+    let (order, session) = run_standard_pipeline(StandardPipelineBuilder::new()).await;
+    assert_eq!(&order[..2], ["researcher", "analyst"]);
+    assert!(session.context.get_sync::<String>("math.status").is_none());
+}
+
+#[tokio::test]
+async fn standard_pipeline_runs_math_between_research_and_analysis() {
+    let (order, session) =
+        run_standard_pipeline(StandardPipelineBuilder::new().with_math_tool(Arc::new(StubSandbox)))
+            .await;
+    assert_eq!(&order[..3], ["researcher", "math_tool", "analyst"]);
+    assert_eq!(
+        session.context.get_sync::<String>("math.status").as_deref(),
+        Some("success")
+    );
+}
+
+#[tokio::test]
+async fn standard_pipeline_appends_extra_researcher_findings() {
+    let (order, session) = run_standard_pipeline(
+        StandardPipelineBuilder::new().with_extra_researcher(extra_retriever("Extra finding")),
+    )
+    .await;
+    assert_eq!(&order[..3], ["researcher", "researcher_1", "analyst"]);
+    let findings = session
+        .context
+        .get_sync::<Vec<String>>("research.findings")
+        .expect("research.findings missing");
+    assert!(findings.len() >= 2);
+    assert_eq!(findings.last().map(String::as_str), Some("Extra finding"));
+}
+
+#[tokio::test]
+async fn standard_pipeline_chains_extra_researchers_before_math() {
+    let (order, _session) = run_standard_pipeline(
+        StandardPipelineBuilder::new()
+            .with_extra_researcher(extra_retriever("First extra"))
+            .with_extra_researcher(extra_retriever("Second extra"))
+            .with_math_tool(Arc::new(StubSandbox)),
+    )
+    .await;
+    assert_eq!(
+        &order[..5],
+        [
+            "researcher",
+            "researcher_1",
+            "researcher_2",
+            "math_tool",
+            "analyst"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn bulk_ingest_reports_partial_failures() {
+    let doc = |id: &str| IngestDocument {
+        id: id.to_string(),
+        text: format!("document {id}"),
+        source: None,
+    };
+    let options = vec![
+        IngestOptions {
+            session_id: "bulk-a".into(),
+            documents: vec![doc("1"), doc("2")],
+            retriever: RetrieverChoice::Stub,
+            chunk_size: None,
+            chunk_overlap: 0,
+        },
+        IngestOptions {
+            session_id: "bulk-b".into(),
+            documents: vec![doc("3")],
+            retriever: RetrieverChoice::Stub,
+            chunk_size: None,
+            chunk_overlap: 0,
+        },
+        IngestOptions {
+            session_id: "bulk-c".into(),
+            documents: vec![doc("4")],
+            retriever: RetrieverChoice::sqlite("/dev/null/unwritable/memory.db"),
+            chunk_size: None,
+            chunk_overlap: 0,
+        },
+    ];
+
+    let report = ingest_documents_bulk(options).await.expect("bulk ingest");
+
+    assert_eq!(report.sessions_processed, 2);
+    assert_eq!(report.documents_indexed, 3);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].0, "bulk-c");
+}
+
+#[derive(Default)]
+struct RecordingRetriever {
+    ingested: std::sync::Mutex<Vec<(String, Vec<String>)>>,
+}
+
+#[async_trait]
+impl Retriever for RecordingRetriever {
+    async fn retrieve(
+        &self,
+        _session_id: &str,
+        _query: &str,
+        _limit: usize,
+    ) -> Result<Vec<RetrievedDocument>> {
+        Ok(Vec::new())
+    }
+
+    async fn ingest(&self, session_id: &str, docs: Vec<IngestDocument>) -> Result<()> {
+        self.ingested.lock().unwrap().push((
+            session_id.to_string(),
+            docs.into_iter().map(|doc| doc.id).collect(),
+        ));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn default_update_document_delegates_to_ingest() {
+    let retriever = RecordingRetriever::default();
+    retriever
+        .update_document(
+            "update-default",
+            IngestDocument {
+                id: "doc-1".into(),
+                text: "revised text".into(),
+                source: None,
+            },
+        )
+        .await
+        .expect("update");
+
+    let ingested = retriever.ingested.lock().unwrap();
+    assert_eq!(
+        *ingested,
+        vec![("update-default".to_string(), vec!["doc-1".to_string()])]
+    );
+}
+
+#[cfg(feature = "qdrant-retriever")]
+#[tokio::test]
+async fn qdrant_update_document_overwrites_existing_point() {
+    use deepresearch_core::{HybridRetriever, QdrantConfig};
+
+    // Point DEEPRESEARCH_QDRANT_URL at a running Qdrant (gRPC port) to exercise the backend.
+    let Ok(url) = std::env::var("DEEPRESEARCH_QDRANT_URL") else {
+        return;
+    };
+    let collection = format!("update-test-{}", Uuid::new_v4());
+    let retriever = HybridRetriever::new(QdrantConfig::new(url, collection, 1))
+        .await
+        .expect("qdrant retriever");
+    let session_id = "qdrant-update";
+    let doc_id = Uuid::new_v4().to_string();
+
+    retriever
+        .ingest(
+            session_id,
+            vec![IngestDocument {
+                id: doc_id.clone(),
+                text: "original battery chemistry notes".into(),
+                source: None,
+            }],
+        )
+        .await
+        .expect("ingest");
+    retriever
+        .update_document(
+            session_id,
+            IngestDocument {
+                id: doc_id,
+                text: "revised battery chemistry notes".into(),
+                source: None,
+            },
+        )
+        .await
+        .expect("update");
+
+    let docs = retriever
+        .retrieve(session_id, "battery chemistry", 10)
+        .await
+        .expect("retrieve");
+    assert_eq!(docs.len(), 1, "update must not duplicate the point");
+    assert_eq!(docs[0].text, "revised battery chemistry notes");
+
+    retriever
+        .delete_session_points(session_id)
+        .await
+        .expect("cleanup");
+}
+
+#[tokio::test]
+async fn context_file_seeds_initial_context() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/context.toml");
+    let storage: Arc<dyn SessionStorage> = Arc::new(InMemorySessionStorage::new());
+    let session_id = Uuid::new_v4().to_string();
+
+    let options = SessionOptions::new("Seed context from TOML")
+        .with_session_id(session_id.clone())
+        .with_shared_storage(storage.clone())
+        .with_context_file(&path)
+        .expect("context file parses");
+    run_research_session_with_report(options)
+        .await
+        .expect("workflow should succeed");
+
+    let session = storage
+        .get(&session_id)
+        .await
+        .expect("storage lookup succeeds")
+        .expect("session should exist after run");
+    assert_eq!(
+        session
+            .context
+            .get_sync::<String>("report.audience")
+            .as_deref(),
+        Some("grid operators")
+    );
+    assert_eq!(
+        session.context.get_sync::<i64>("report.max_sources"),
+        Some(5)
+    );
+    assert_eq!(
+        session.context.get_sync::<bool>("report.include_appendix"),
+        Some(true)
+    );
+    let math = session
+        .context
+        .get_sync::<serde_json::Value>("math.request")
+        .expect("math.request seeded");
+    assert_eq!(math["script_name"], "capacity_factor.py");
+    assert_eq!(math["args"], json!(["--region", "eu"]));
+
+    let missing = SessionOptions::new("missing")
+        .with_context_file(std::path::Path::new("tests/fixtures/does-not-exist.toml"));
+    assert!(missing.is_err());
+}
+
+#[cfg(feature = "bundle-export")]
+#[tokio::test]
+async fn export_session_bundle_writes_zip_archive() {
+    use deepresearch_core::export_session_bundle;
+    use std::io::Read;
+
+    let storage: Arc<dyn SessionStorage> = Arc::new(InMemorySessionStorage::new());
+    let session_id = Uuid::new_v4().to_string();
+    run_research_session_with_report(
+        SessionOptions::new("Bundle grid storage findings")
+            .with_session_id(session_id.clone())
+            .with_shared_storage(storage.clone())
+            .enable_trace(),
+    )
+    .await
+    .expect("workflow should succeed");
+
+    let dir = tempfile::tempdir().expect("temp dir");
+    let output = dir.path().join("bundles").join("session.zip");
+    export_session_bundle(&session_id, &storage, &output)
+        .await
+        .expect("export bundle");
+
+    let mut archive =
+        zip::ZipArchive::new(std::fs::File::open(&output).expect("bundle")).expect("zip");
+    let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        ["session.json", "sources.txt", "summary.md", "trace.json"]
+    );
+
+    let mut session_json = String::new();
+    archive
+        .by_name("session.json")
+        .expect("session entry")
+        .read_to_string(&mut session_json)
+        .expect("read session");
+    let restored: Session = serde_json::from_str(&session_json).expect("session round-trips");
+    assert_eq!(restored.id, session_id);
+
+    let mut summary = String::new();
+    archive
+        .by_name("summary.md")
+        .expect("summary entry")
+        .read_to_string(&mut summary)
+        .expect("read summary");
+    assert!(summary.starts_with(&format!("# Session {session_id}")));
+    assert!(summary.contains("Bundle grid storage findings"));
+
+    let missing = export_session_bundle("missing-session", &storage, &output)
+        .await
+        .expect_err("unknown session");
+    assert!(missing.to_string().contains("not found"), "{missing}");
+}
+
+#[cfg(feature = "test-utils")]
+#[tokio::test]
+async fn math_failure_is_recorded_from_mock_sandbox() {
+    use deepresearch_core::MockSandboxExecutor;
+
+    let session_id = Uuid::new_v4().to_string();
+    let storage = Arc::new(InMemorySessionStorage::new());
+    let sandbox = Arc::new(MockSandboxExecutor::new().with_expected_response(
+        |request| request.script_name == "failing_math.py",
+        MockSandboxExecutor::exit(3, "", "ZeroDivisionError"),
+    ));
+
+    let options = SessionOptions::new("use context7 verify failing math")
+        .with_session_id(session_id.clone())
+        .with_shared_storage(storage.clone())
+        .with_sandbox_executor(sandbox.clone())
+        .with_initial_context(
+            "math.request",
+            json!({
+                "script_name": "failing_math.py",
+                "script": "print(1 / 0)",
+                "timeout_ms": 1000
+            }),
+        );
+
+    run_research_session_with_options(options)
+        .await
+        .expect("workflow should succeed");
+
+    sandbox.expect_calls(1);
+    sandbox.assert_called_with(|request| request.script_contents == "print(1 / 0)");
+
+    let session = storage
+        .get(&session_id)
+        .await
+        .expect("storage lookup succeeds")
+        .expect("session should exist after run");
+    assert_eq!(
+        session.context.get_sync::<String>("math.status").as_deref(),
+        Some("failure")
+    );
+    assert_eq!(
+        session.context.get_sync::<Option<i32>>("math.exit_code"),
+        Some(Some(3))
+    );
+}
+
 struct StubSandbox;
 
 #[async_trait]
@@ -166,6 +998,9 @@ impl SandboxExecutor for StubSandbox {
             outputs: Vec::new(),
             timed_out: false,
             duration: Duration::from_millis(12),
+            stderr_overflow_warning: None,
+            resource_usage: None,
+            partial_output_errors: Vec::new(),
         })
     }
 }
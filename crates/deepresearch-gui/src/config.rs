@@ -2,6 +2,7 @@ use anyhow::{Context, Result};
 use std::env;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub struct AppConfig {
@@ -14,6 +15,9 @@ pub struct AppConfig {
     pub storage: StorageBackend,
     pub session_namespace: Option<String>,
     pub otel_endpoint: Option<String>,
+    pub session_ttl_secs: u64,
+    pub admin_token: Option<String>,
+    pub log_dir: PathBuf,
 }
 
 #[derive(Clone, Debug)]
@@ -28,6 +32,7 @@ pub enum StorageBackend {
 impl AppConfig {
     const DEFAULT_LISTEN_ADDR: &'static str = "0.0.0.0:8080";
     const DEFAULT_ASSETS_DIR: &'static str = "crates/deepresearch-gui/web/dist";
+    const DEFAULT_SESSION_TTL_SECS: u64 = 3600;
 
     pub fn from_env() -> Result<Self> {
         let listen_addr =
@@ -84,6 +89,24 @@ impl AppConfig {
             .map(|value| value.trim().to_string())
             .filter(|value| !value.is_empty());
 
+        let session_ttl_secs = env::var("GUI_SESSION_TTL_SECS")
+            .ok()
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<u64>()
+                    .with_context(|| "GUI_SESSION_TTL_SECS must be a non-negative integer")
+            })
+            .transpose()?
+            .unwrap_or(Self::DEFAULT_SESSION_TTL_SECS);
+
+        let admin_token = env::var("GUI_ADMIN_TOKEN")
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        let log_dir = deepresearch_core::log_base_dir();
+
         let gui_enabled = gui_enabled || auth_token.is_some();
 
         Ok(Self {
@@ -96,8 +119,16 @@ impl AppConfig {
             storage,
             session_namespace,
             otel_endpoint,
+            session_ttl_secs,
+            admin_token,
+            log_dir,
         })
     }
+
+    /// Retention window for finished sessions; `None` when eviction is disabled.
+    pub fn session_ttl(&self) -> Option<Duration> {
+        (self.session_ttl_secs > 0).then(|| Duration::from_secs(self.session_ttl_secs))
+    }
 }
 
 fn parse_bool(input: &str) -> Option<bool> {
@@ -49,6 +49,16 @@ pub fn session_failed(session_id: &str, running: usize, available_permits: usize
     );
 }
 
+pub fn session_cancelled(session_id: &str, running: usize, available_permits: usize) {
+    info!(
+        target = "telemetry.gui",
+        session_id,
+        running_sessions = running,
+        available_permits,
+        event = "session_cancelled"
+    );
+}
+
 pub fn stream_opened(session_id: &str, active_streams: usize) {
     info!(
         target = "telemetry.gui",
@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use axum::{
+    Json, Router,
+    extract::{FromRequestParts, Query},
+    http::{StatusCode, header, request::Parts},
+    routing::get,
+};
+use chrono::{DateTime, NaiveDate};
+use deepresearch_core::{AuditEntry, AuditQuery, read_audit_entries};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditParams {
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub page: Option<usize>,
+    #[serde(default)]
+    pub page_size: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditResponse {
+    pub entries: Vec<AuditEntry>,
+    /// Zero-based, matching `/api/sessions`.
+    pub page: usize,
+    pub page_size: usize,
+    pub total_count: usize,
+}
+
+pub fn audit_router() -> Router<AppState> {
+    Router::new().route("/audit", get(list_audit_entries))
+}
+
+async fn list_audit_entries(
+    AdminState(state): AdminState,
+    Query(params): Query<AuditParams>,
+) -> Result<Json<AuditResponse>, AppError> {
+    let query = AuditQuery {
+        session_id: params.session_id.filter(|id| !id.trim().is_empty()),
+        from: parse_date("from", params.from.as_deref())?,
+        to: parse_date("to", params.to.as_deref())?,
+    };
+    let page = params.page.unwrap_or(0);
+    let page_size = params
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let log_dir = state.log_dir();
+    let entries = tokio::task::spawn_blocking(move || read_audit_entries(&log_dir, &query))
+        .await
+        .map_err(AppError::internal)??;
+
+    let total_count = entries.len();
+    let entries = entries
+        .into_iter()
+        .skip(page.saturating_mul(page_size))
+        .take(page_size)
+        .collect();
+
+    Ok(Json(AuditResponse {
+        entries,
+        page,
+        page_size,
+        total_count,
+    }))
+}
+
+/// Accepts `YYYY-MM-DD` or a full RFC3339 timestamp (only the date part is used).
+fn parse_date(name: &str, raw: Option<&str>) -> Result<Option<NaiveDate>, AppError> {
+    let Some(raw) = raw.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .or_else(|_| DateTime::parse_from_rfc3339(raw).map(|ts| ts.date_naive()))
+        .map(Some)
+        .map_err(|_| {
+            AppError::new(
+                StatusCode::BAD_REQUEST,
+                format!("`{name}` must be an ISO date (YYYY-MM-DD)"),
+            )
+        })
+}
+
+/// Extractor gating compliance endpoints behind `GUI_ADMIN_TOKEN`, independent of the GUI token.
+pub struct AdminState(pub AppState);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AdminState {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Some(expected) = state.admin_token() else {
+            return Err(AppError::new(
+                StatusCode::FORBIDDEN,
+                "audit endpoint disabled",
+            ));
+        };
+
+        let provided = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+
+        match provided {
+            Some(token) if token == expected.as_str() => Ok(AdminState(state.clone())),
+            _ => Err(AppError::new(
+                StatusCode::UNAUTHORIZED,
+                "invalid admin token",
+            )),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use deepresearch_core::HealthStatus;
 use serde::Serialize;
 
 use crate::state::{AppState, SessionMetrics};
@@ -8,6 +9,8 @@ struct HealthResponse {
     status: &'static str,
     gui_enabled: bool,
     metrics: SessionMetrics,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checks: Option<HealthStatus>,
 }
 
 pub fn health_router() -> Router<AppState> {
@@ -28,19 +31,23 @@ async fn ready(State(state): State<AppState>) -> (StatusCode, Json<HealthRespons
         );
     }
 
+    let checks = state.session_service().health_check().await;
     let metrics = state.metrics();
-    if metrics.available_permits == 0 {
+    if metrics.available_permits == 0 || !checks.is_healthy() {
         return (
             StatusCode::SERVICE_UNAVAILABLE,
             Json(HealthResponse {
                 status: "degraded",
                 gui_enabled: true,
                 metrics,
+                checks: Some(checks),
             }),
         );
     }
 
-    (StatusCode::OK, Json(build_response("ok", state)))
+    let mut response = build_response("ok", state);
+    response.checks = Some(checks);
+    (StatusCode::OK, Json(response))
 }
 
 fn build_response(status: &'static str, state: AppState) -> HealthResponse {
@@ -48,5 +55,6 @@ fn build_response(status: &'static str, state: AppState) -> HealthResponse {
         status,
         gui_enabled: state.gui_enabled(),
         metrics: state.metrics(),
+        checks: None,
     }
 }
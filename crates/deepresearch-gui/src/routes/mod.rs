@@ -1,6 +1,8 @@
+mod audit;
 mod health;
 mod session;
 
+use audit::audit_router;
 use axum::{
     Router,
     body::Body,
@@ -18,7 +20,7 @@ use crate::state::AppState;
 pub fn build_router(state: AppState) -> Router {
     Router::new()
         .nest("/health", health_router())
-        .nest("/api", session_router())
+        .nest("/api", session_router().merge(audit_router()))
         .fallback(spa_fallback)
         .with_state(state)
 }
@@ -1,21 +1,33 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use axum::{
     Json, Router,
-    extract::{FromRequestParts, Path},
+    extract::{
+        FromRequestParts, Path, Query,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
     http::{StatusCode, header, request::Parts},
-    response::sse::{KeepAlive, Sse},
+    response::{
+        Response,
+        sse::{KeepAlive, Sse},
+    },
     routing::{get, post},
 };
 use serde::{Deserialize, Serialize};
-use tracing::instrument;
+use tokio_stream::StreamExt;
+use tracing::{debug, instrument};
 
 use crate::error::AppError;
 use crate::state::{
-    AppState, SessionMetrics, SessionRequest, SessionState, SessionStatus, SseStream,
+    AppState, SessionEventStream, SessionMetrics, SessionRequest, SessionState, SessionStatus,
+    SseStream,
 };
 
+/// Interval between WebSocket pings so idle proxies keep the connection open.
+const WS_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
 #[derive(Debug, Deserialize)]
 pub struct StartSessionRequest {
     pub query: String,
@@ -44,6 +56,8 @@ pub struct TraceResponse {
     pub task_metrics: Vec<TaskMetric>,
     pub artifacts: TraceArtifacts,
     pub requires_manual: bool,
+    pub sources: Vec<String>,
+    pub source_references: Vec<deepresearch_core::SourceReference>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fact_check: Option<FactCheckSnapshot>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -52,6 +66,12 @@ pub struct TraceResponse {
     pub trace_path: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct CancelSessionResponse {
+    pub session_id: String,
+    pub cancelled: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct CapacitySnapshot {
     pub max_concurrency: usize,
@@ -71,10 +91,24 @@ impl From<SessionMetrics> for CapacitySnapshot {
     }
 }
 
+const DEFAULT_SESSIONS_PAGE_SIZE: usize = 50;
+const MAX_SESSIONS_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct ListSessionsParams {
+    #[serde(default)]
+    pub page: Option<usize>,
+    #[serde(default)]
+    pub page_size: Option<usize>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ListSessionsResponse {
     pub sessions: Vec<SessionStatus>,
     pub capacity: CapacitySnapshot,
+    pub total_count: usize,
+    pub page: usize,
+    pub page_size: usize,
 }
 
 #[derive(Debug, Serialize)]
@@ -124,9 +158,10 @@ pub struct TaskMetric {
 pub fn session_router() -> Router<AppState> {
     Router::new()
         .route("/sessions", post(start_session).get(list_sessions))
-        .route("/sessions/:id", get(get_session))
+        .route("/sessions/:id", get(get_session).delete(cancel_session))
         .route("/sessions/:id/trace", get(get_session_trace))
         .route("/sessions/:id/stream", get(stream_session))
+        .route("/sessions/:id/ws", get(session_socket))
 }
 
 #[instrument(skip_all, fields(session_id = %payload.session_id.as_deref().unwrap_or("new")))]
@@ -158,6 +193,7 @@ async fn start_session(
         error: None,
         trace_available: false,
         requires_manual: false,
+        expires_at: None,
     });
 
     let metrics_snapshot = service.metrics();
@@ -187,6 +223,30 @@ async fn get_session(
     }
 }
 
+async fn cancel_session(
+    GuardedState(state): GuardedState,
+    Path(session_id): Path<String>,
+) -> Result<(StatusCode, Json<CancelSessionResponse>), AppError> {
+    let service = state.session_service();
+    if service.cancel_session(&session_id) {
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(CancelSessionResponse {
+                session_id,
+                cancelled: true,
+            }),
+        ));
+    }
+
+    match service.status(&session_id) {
+        Some(_) => Err(AppError::new(
+            StatusCode::CONFLICT,
+            "session is not running",
+        )),
+        None => Err(AppError::new(StatusCode::NOT_FOUND, "session not found")),
+    }
+}
+
 async fn get_session_trace(
     GuardedState(state): GuardedState,
     Path(session_id): Path<String>,
@@ -207,6 +267,8 @@ async fn get_session_trace(
                 graphviz: outcome.explain_graphviz(),
             },
             requires_manual: outcome.requires_manual,
+            sources: outcome.sources.clone(),
+            source_references: outcome.source_references.clone(),
             fact_check: outcome
                 .factcheck_confidence
                 .map(|confidence| FactCheckSnapshot {
@@ -244,13 +306,79 @@ async fn stream_session(
     }
 }
 
+/// WebSocket alternative to `stream_session` for proxies that strip SSE: each
+/// `SessionEvent` arrives as a JSON text frame and the socket closes after the terminal event.
+async fn session_socket(
+    GuardedState(state): GuardedState,
+    Path(session_id): Path<String>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    match state.session_service().session_events(&session_id) {
+        Some(events) => {
+            Ok(upgrade.on_upgrade(move |socket| forward_session_events(socket, events)))
+        }
+        None => Err(AppError::new(StatusCode::NOT_FOUND, "session not found")),
+    }
+}
+
+async fn forward_session_events(mut socket: WebSocket, mut events: SessionEventStream) {
+    let mut keepalive = tokio::time::interval(WS_KEEPALIVE_INTERVAL);
+    keepalive.tick().await;
+
+    loop {
+        tokio::select! {
+            event = events.next() => {
+                let Some(event) = event else { break };
+                let terminal = event.is_terminal();
+                let payload = match serde_json::to_string(&event) {
+                    Ok(payload) => payload,
+                    Err(err) => {
+                        debug!(error = %err, "failed to serialize session event");
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(payload)).await.is_err() {
+                    return;
+                }
+                if terminal {
+                    break;
+                }
+            }
+            _ = keepalive.tick() => {
+                if socket.send(Message::Ping(Vec::new())).await.is_err() {
+                    return;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    let _ = socket.send(Message::Close(None)).await;
+}
+
 async fn list_sessions(
     GuardedState(state): GuardedState,
+    Query(params): Query<ListSessionsParams>,
 ) -> Result<Json<ListSessionsResponse>, AppError> {
+    let page = params.page.unwrap_or(0);
+    let page_size = params
+        .page_size
+        .unwrap_or(DEFAULT_SESSIONS_PAGE_SIZE)
+        .clamp(1, MAX_SESSIONS_PAGE_SIZE);
+
     let service = state.session_service();
-    let sessions = service.list_sessions();
+    let (sessions, total_count) = service.list_sessions_paged(page, page_size);
     let capacity = service.metrics().into();
-    Ok(Json(ListSessionsResponse { sessions, capacity }))
+    Ok(Json(ListSessionsResponse {
+        sessions,
+        capacity,
+        total_count,
+        page,
+        page_size,
+    }))
 }
 
 fn build_timeline(events: &[deepresearch_core::TraceEvent]) -> Vec<TimelinePoint> {
@@ -272,9 +400,11 @@ fn build_timeline(events: &[deepresearch_core::TraceEvent]) -> Vec<TimelinePoint
                 .map(|next| next.timestamp_ms)
                 .filter(|next| *next >= event.timestamp_ms);
 
-            let duration_ms = next_timestamp.map(|next| {
-                next.saturating_sub(event.timestamp_ms)
-                    .min(u64::MAX as u128) as u64
+            let duration_ms = event.duration_ms.or_else(|| {
+                next_timestamp.map(|next| {
+                    next.saturating_sub(event.timestamp_ms)
+                        .min(u64::MAX as u128) as u64
+                })
             });
 
             TimelinePoint {
@@ -7,7 +7,10 @@ use anyhow::Context;
 use anyhow::Result;
 use axum::response::sse::Event;
 use dashmap::DashMap;
-use deepresearch_core::{SessionOptions, SessionOutcome, run_research_session_with_report};
+use deepresearch_core::{
+    HealthStatus, RetrieverChoice, SessionOptions, SessionOutcome, check_health,
+    run_research_session_with_report,
+};
 #[cfg(feature = "postgres-session")]
 use graph_flow::storage_postgres::PostgresSessionStorage;
 use graph_flow::{InMemorySessionStorage, SessionStorage};
@@ -21,18 +24,24 @@ use std::sync::{
     atomic::{AtomicUsize, Ordering},
 };
 use std::task::{Context as TaskContext, Poll};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::{Semaphore, broadcast};
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::{self as stream, Stream, StreamExt};
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+const MAX_EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
 #[derive(Clone)]
 pub struct AppState {
     session_service: Arc<SessionService>,
     assets_dir: Arc<PathBuf>,
     gui_enabled: bool,
     auth_token: Option<Arc<String>>,
+    admin_token: Option<Arc<String>>,
+    log_dir: Arc<PathBuf>,
 }
 
 impl AppState {
@@ -55,7 +64,9 @@ impl AppState {
             config.max_concurrency,
             config.default_enable_trace,
             config.session_namespace.clone(),
-        );
+        )
+        .with_session_ttl(config.session_ttl());
+        service.spawn_eviction_task();
 
         Ok(Self {
             session_service: Arc::new(service),
@@ -65,6 +76,11 @@ impl AppState {
                 .auth_token
                 .as_ref()
                 .map(|token| Arc::new(token.to_string())),
+            admin_token: config
+                .admin_token
+                .as_ref()
+                .map(|token| Arc::new(token.to_string())),
+            log_dir: Arc::new(config.log_dir.clone()),
         })
     }
 
@@ -84,6 +100,14 @@ impl AppState {
         self.auth_token.clone()
     }
 
+    pub fn admin_token(&self) -> Option<Arc<String>> {
+        self.admin_token.clone()
+    }
+
+    pub fn log_dir(&self) -> Arc<PathBuf> {
+        self.log_dir.clone()
+    }
+
     pub fn metrics(&self) -> SessionMetrics {
         self.session_service.metrics()
     }
@@ -99,6 +123,8 @@ pub struct SessionService {
     max_concurrency: usize,
     namespace: Option<String>,
     stream_subscribers: Arc<AtomicUsize>,
+    session_ttl: Option<Duration>,
+    retriever: RetrieverChoice,
 }
 
 impl SessionService {
@@ -117,12 +143,61 @@ impl SessionService {
             max_concurrency: max_concurrency.max(1),
             namespace,
             stream_subscribers: Arc::new(AtomicUsize::new(0)),
+            session_ttl: None,
+            retriever: RetrieverChoice::default(),
+        }
+    }
+
+    /// Evict finished sessions once they are older than `ttl` (`None` keeps them forever).
+    pub fn with_session_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.session_ttl = ttl;
+        self
+    }
+
+    /// Retriever used by sessions started through this service and probed by
+    /// [`Self::health_check`].
+    pub fn with_retriever(mut self, retriever: RetrieverChoice) -> Self {
+        self.retriever = retriever;
+        self
+    }
+
+    /// Spawn the background task that periodically evicts expired sessions.
+    ///
+    /// The task holds a weak reference and stops once the service is dropped.
+    pub fn spawn_eviction_task(&self) {
+        let Some(ttl) = self.session_ttl else {
+            return;
+        };
+        let sessions = Arc::downgrade(&self.sessions);
+        let period = ttl.min(MAX_EVICTION_INTERVAL);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(sessions) = sessions.upgrade() else {
+                    break;
+                };
+                let evicted = evict_expired_records(&sessions, ttl);
+                if evicted > 0 {
+                    info!(evicted, "evicted expired sessions");
+                }
+            }
+        });
+    }
+
+    /// Remove finished sessions older than the configured TTL, returning the number evicted.
+    pub fn evict_expired(&self) -> usize {
+        match self.session_ttl {
+            Some(ttl) => evict_expired_records(&self.sessions, ttl),
+            None => 0,
         }
     }
 
     pub async fn start_session(&self, mut request: SessionRequest) -> Result<String> {
         let session_id = self.normalize_session_id(request.session_id.take());
-        let prompt = ensure_context7_prefix(&request.query);
+        let prompt = request.query.clone();
         let enable_trace = request.enable_trace.unwrap_or(self.default_enable_trace);
 
         let sender = self
@@ -134,22 +209,43 @@ impl SessionService {
             })
             .clone();
         let _ = sender.send(SessionEvent::started());
-        self.sessions
-            .insert(session_id.clone(), SessionRecord::Running);
+        let cancel = CancellationToken::new();
+        self.sessions.insert(
+            session_id.clone(),
+            SessionRecord::Running {
+                cancel: cancel.clone(),
+            },
+        );
 
         let semaphore = self.semaphore.clone();
         let sessions = self.sessions.clone();
         let streams = self.streams.clone();
         let storage = self.storage.clone();
+        let retriever = self.retriever.clone();
         let session_id_for_task = session_id.clone();
         let sender_for_task = sender.clone();
         let namespace = self.namespace.clone();
 
         tokio::spawn(async move {
             let semaphore_clone = semaphore.clone();
-            let permit = match semaphore_clone.acquire_owned().await {
-                Ok(permit) => permit,
-                Err(err) => {
+            let acquired = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => None,
+                permit = semaphore_clone.acquire_owned() => Some(permit),
+            };
+            let permit = match acquired {
+                None => {
+                    mark_cancelled(
+                        &sessions,
+                        &semaphore,
+                        &sender_for_task,
+                        &session_id_for_task,
+                    );
+                    streams.remove(&session_id_for_task);
+                    return;
+                }
+                Some(Ok(permit)) => permit,
+                Some(Err(err)) => {
                     let event = SessionEvent::error(&err);
                     let _ = sender_for_task.send(event.clone());
                     sessions.insert(
@@ -157,11 +253,12 @@ impl SessionService {
                         SessionRecord::Failed {
                             error: err.to_string(),
                             event,
+                            failed_at: Instant::now(),
                         },
                     );
                     let running = sessions
                         .iter()
-                        .filter(|entry| matches!(entry.value(), SessionRecord::Running))
+                        .filter(|entry| matches!(entry.value(), SessionRecord::Running { .. }))
                         .count();
                     let available_permits = semaphore.available_permits();
                     metrics::session_failed(
@@ -177,7 +274,10 @@ impl SessionService {
 
             let mut options = SessionOptions::new(&prompt)
                 .with_session_id(session_id_for_task.clone())
-                .with_shared_storage(storage);
+                .with_shared_storage(storage)
+                .with_retriever(retriever)
+                .with_context7_prefix()
+                .with_cancellation_token(cancel);
 
             if enable_trace {
                 options = options.enable_trace();
@@ -192,6 +292,14 @@ impl SessionService {
             drop(permit);
 
             match result {
+                Ok(outcome) if outcome.cancelled => {
+                    mark_cancelled(
+                        &sessions,
+                        &semaphore,
+                        &sender_for_task,
+                        &session_id_for_task,
+                    );
+                }
                 Ok(outcome) => {
                     info!(session_id = %session_id_for_task, "session completed");
                     let event = SessionEvent::completed(&outcome);
@@ -201,11 +309,12 @@ impl SessionService {
                         SessionRecord::Completed {
                             outcome: outcome.clone(),
                             event: event.clone(),
+                            completed_at: Instant::now(),
                         },
                     );
                     let running = sessions
                         .iter()
-                        .filter(|entry| matches!(entry.value(), SessionRecord::Running))
+                        .filter(|entry| matches!(entry.value(), SessionRecord::Running { .. }))
                         .count();
                     let available_permits = semaphore.available_permits();
                     metrics::session_completed(
@@ -225,11 +334,12 @@ impl SessionService {
                         SessionRecord::Failed {
                             error: err.to_string(),
                             event: event.clone(),
+                            failed_at: Instant::now(),
                         },
                     );
                     let running = sessions
                         .iter()
-                        .filter(|entry| matches!(entry.value(), SessionRecord::Running))
+                        .filter(|entry| matches!(entry.value(), SessionRecord::Running { .. }))
                         .count();
                     let available_permits = semaphore.available_permits();
                     metrics::session_failed(
@@ -248,35 +358,22 @@ impl SessionService {
         Ok(session_id)
     }
 
+    /// Abort a running (or queued) session. Returns `false` if the session is unknown or
+    /// has already finished.
+    pub fn cancel_session(&self, session_id: &str) -> bool {
+        match self.sessions.get(session_id).as_deref() {
+            Some(SessionRecord::Running { cancel }) => {
+                cancel.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub fn status(&self, session_id: &str) -> Option<SessionStatus> {
         self.sessions
             .get(session_id)
-            .map(|record| match record.value() {
-                SessionRecord::Running => SessionStatus {
-                    session_id: session_id.to_string(),
-                    state: SessionState::Running,
-                    summary: None,
-                    error: None,
-                    trace_available: false,
-                    requires_manual: false,
-                },
-                SessionRecord::Completed { outcome, .. } => SessionStatus {
-                    session_id: session_id.to_string(),
-                    state: SessionState::Completed,
-                    summary: Some(outcome.summary.clone()),
-                    error: None,
-                    trace_available: !outcome.trace_events.is_empty(),
-                    requires_manual: outcome.requires_manual,
-                },
-                SessionRecord::Failed { error, .. } => SessionStatus {
-                    session_id: session_id.to_string(),
-                    state: SessionState::Failed,
-                    summary: None,
-                    error: Some(error.clone()),
-                    trace_available: false,
-                    requires_manual: false,
-                },
-            })
+            .map(|record| self.build_status(session_id.to_string(), record.value()))
     }
 
     pub fn outcome(&self, session_id: &str) -> Option<Arc<SessionOutcome>> {
@@ -288,76 +385,94 @@ impl SessionService {
             })
     }
 
-    pub fn event_stream(&self, session_id: &str) -> Option<SseStream> {
+    /// Lifecycle events for a session: the terminal event for finished sessions, or a live
+    /// subscription while it runs. `None` when the session is unknown.
+    pub fn session_events(&self, session_id: &str) -> Option<SessionEventStream> {
         if let Some(record) = self.sessions.get(session_id) {
             match record.value() {
-                SessionRecord::Completed { event, .. } => {
-                    let event = event.clone().into_sse_event();
-                    let stream = stream::iter(vec![Result::<Event, Infallible>::Ok(event)]);
-                    return Some(self.instrument_stream(session_id, Box::pin(stream)));
-                }
-                SessionRecord::Failed { event, .. } => {
-                    let event = event.clone().into_sse_event();
-                    let stream = stream::iter(vec![Result::<Event, Infallible>::Ok(event)]);
-                    return Some(self.instrument_stream(session_id, Box::pin(stream)));
+                SessionRecord::Completed { event, .. }
+                | SessionRecord::Failed { event, .. }
+                | SessionRecord::Cancelled { event, .. } => {
+                    return Some(Box::pin(stream::iter(vec![event.clone()])));
                 }
-                SessionRecord::Running => {}
+                SessionRecord::Running { .. } => {}
             }
         }
 
         self.streams.get(session_id).map(|sender| {
             let rx = sender.subscribe();
             let stream = BroadcastStream::new(rx).filter_map(|event| match event {
-                Ok(event) => Some(Result::<Event, Infallible>::Ok(event.into_sse_event())),
+                Ok(event) => Some(event),
                 Err(err) => {
                     warn!(error = %err, "session event stream closed");
                     None
                 }
             });
+            Box::pin(stream) as SessionEventStream
+        })
+    }
+
+    pub fn event_stream(&self, session_id: &str) -> Option<SseStream> {
+        self.session_events(session_id).map(|events| {
+            let stream =
+                events.map(|event| Result::<Event, Infallible>::Ok(event.into_sse_event()));
             self.instrument_stream(session_id, Box::pin(stream))
         })
     }
 
+    /// Sessions visible to this service; with a namespace configured, only ids under
+    /// `<namespace>::` are listed.
     pub fn list_sessions(&self) -> Vec<SessionStatus> {
+        let prefix = self.namespace_prefix();
         self.sessions
             .iter()
-            .map(|entry| {
-                let session_id = entry.key().clone();
-                match entry.value() {
-                    SessionRecord::Running => SessionStatus {
-                        session_id,
-                        state: SessionState::Running,
-                        summary: None,
-                        error: None,
-                        trace_available: false,
-                        requires_manual: false,
-                    },
-                    SessionRecord::Completed { outcome, .. } => SessionStatus {
-                        session_id,
-                        state: SessionState::Completed,
-                        summary: Some(outcome.summary.clone()),
-                        error: None,
-                        trace_available: !outcome.trace_events.is_empty(),
-                        requires_manual: outcome.requires_manual,
-                    },
-                    SessionRecord::Failed { error, .. } => SessionStatus {
-                        session_id,
-                        state: SessionState::Failed,
-                        summary: None,
-                        error: Some(error.clone()),
-                        trace_available: false,
-                        requires_manual: false,
-                    },
-                }
+            .filter(|entry| {
+                prefix
+                    .as_deref()
+                    .is_none_or(|prefix| entry.key().starts_with(prefix))
             })
+            .map(|entry| self.build_status(entry.key().clone(), entry.value()))
             .collect()
     }
 
+    /// One page of [`Self::list_sessions`], ordered by session id so pages stay stable
+    /// between requests, together with the total number of visible sessions.
+    pub fn list_sessions_paged(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> (Vec<SessionStatus>, usize) {
+        let prefix = self.namespace_prefix();
+        let mut ids: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|entry| {
+                prefix
+                    .as_deref()
+                    .is_none_or(|prefix| entry.key().starts_with(prefix))
+            })
+            .map(|entry| entry.key().clone())
+            .collect();
+        ids.sort();
+        let total = ids.len();
+
+        let statuses = ids
+            .into_iter()
+            .skip(page.saturating_mul(page_size))
+            .take(page_size)
+            .filter_map(|id| {
+                let record = self.sessions.get(&id)?;
+                Some(self.build_status(id, record.value()))
+            })
+            .collect();
+        (statuses, total)
+    }
+
     pub fn metrics(&self) -> SessionMetrics {
         let running_sessions = self
             .sessions
             .iter()
-            .filter(|entry| matches!(entry.value(), SessionRecord::Running))
+            .filter(|entry| matches!(entry.value(), SessionRecord::Running { .. }))
             .count();
         SessionMetrics {
             max_concurrency: self.max_concurrency,
@@ -367,19 +482,79 @@ impl SessionService {
         }
     }
 
+    /// Probe the session storage and the retriever used by GUI sessions.
+    pub async fn health_check(&self) -> HealthStatus {
+        check_health(self.storage.clone(), &self.retriever, None).await
+    }
+
+    fn build_status(&self, session_id: String, record: &SessionRecord) -> SessionStatus {
+        let expires_at = self.expires_at(record);
+        match record {
+            SessionRecord::Running { .. } => SessionStatus {
+                session_id,
+                state: SessionState::Running,
+                summary: None,
+                error: None,
+                trace_available: false,
+                requires_manual: false,
+                expires_at,
+            },
+            SessionRecord::Completed { outcome, .. } => SessionStatus {
+                session_id,
+                state: SessionState::Completed,
+                summary: Some(outcome.summary.clone()),
+                error: None,
+                trace_available: !outcome.trace_events.is_empty(),
+                requires_manual: outcome.requires_manual,
+                expires_at,
+            },
+            SessionRecord::Failed { error, .. } => SessionStatus {
+                session_id,
+                state: SessionState::Failed,
+                summary: None,
+                error: Some(error.clone()),
+                trace_available: false,
+                requires_manual: false,
+                expires_at,
+            },
+            SessionRecord::Cancelled { .. } => SessionStatus {
+                session_id,
+                state: SessionState::Cancelled,
+                summary: None,
+                error: None,
+                trace_available: false,
+                requires_manual: false,
+                expires_at,
+            },
+        }
+    }
+
+    /// Unix timestamp (seconds) at which a finished session becomes eligible for eviction.
+    fn expires_at(&self, record: &SessionRecord) -> Option<u64> {
+        let ttl = self.session_ttl?;
+        let finished_at = record.finished_at()?;
+        let remaining = ttl.saturating_sub(finished_at.elapsed());
+        let expiry = SystemTime::now() + remaining;
+        expiry
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|elapsed| elapsed.as_secs())
+    }
+
     fn normalize_session_id(&self, session_id: Option<String>) -> String {
         let raw = session_id.unwrap_or_else(|| Uuid::new_v4().to_string());
-        if let Some(namespace) = &self.namespace {
-            if raw.starts_with(namespace) {
-                raw
-            } else {
-                format!("{namespace}::{raw}")
-            }
-        } else {
-            raw
+        match self.namespace_prefix() {
+            Some(prefix) if !raw.starts_with(&prefix) => format!("{prefix}{raw}"),
+            _ => raw,
         }
     }
 
+    fn namespace_prefix(&self) -> Option<String> {
+        self.namespace
+            .as_ref()
+            .map(|namespace| format!("{namespace}::"))
+    }
+
     fn instrument_stream(&self, session_id: &str, stream: SseStream) -> SseStream {
         self.stream_subscribers.fetch_add(1, Ordering::SeqCst);
         let active = self.stream_subscribers.load(Ordering::SeqCst);
@@ -426,26 +601,86 @@ impl Drop for InstrumentedStream {
 }
 
 pub type SseStream = Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>;
+pub type SessionEventStream = Pin<Box<dyn Stream<Item = SessionEvent> + Send>>;
+
+fn mark_cancelled(
+    sessions: &DashMap<String, SessionRecord>,
+    semaphore: &Semaphore,
+    sender: &broadcast::Sender<SessionEvent>,
+    session_id: &str,
+) {
+    info!(%session_id, "session cancelled");
+    let event = SessionEvent::cancelled();
+    sessions.insert(
+        session_id.to_string(),
+        SessionRecord::Cancelled {
+            event: event.clone(),
+            cancelled_at: Instant::now(),
+        },
+    );
+    let running = sessions
+        .iter()
+        .filter(|entry| matches!(entry.value(), SessionRecord::Running { .. }))
+        .count();
+    metrics::session_cancelled(session_id, running, semaphore.available_permits());
+    let _ = sender.send(event);
+}
+
+fn evict_expired_records(sessions: &DashMap<String, SessionRecord>, ttl: Duration) -> usize {
+    let mut evicted = 0;
+    sessions.retain(|_, record| {
+        let expired = record
+            .finished_at()
+            .map(|finished_at| finished_at.elapsed() >= ttl)
+            .unwrap_or(false);
+        if expired {
+            evicted += 1;
+        }
+        !expired
+    });
+    evicted
+}
 
 #[derive(Debug)]
 pub enum SessionRecord {
-    Running,
+    Running {
+        cancel: CancellationToken,
+    },
     Completed {
         outcome: Arc<SessionOutcome>,
         event: SessionEvent,
+        completed_at: Instant,
     },
     Failed {
         error: String,
         event: SessionEvent,
+        failed_at: Instant,
+    },
+    Cancelled {
+        event: SessionEvent,
+        cancelled_at: Instant,
     },
 }
 
+impl SessionRecord {
+    /// Moment the session reached a terminal state; `None` while running.
+    pub fn finished_at(&self) -> Option<Instant> {
+        match self {
+            SessionRecord::Running { .. } => None,
+            SessionRecord::Completed { completed_at, .. } => Some(*completed_at),
+            SessionRecord::Failed { failed_at, .. } => Some(*failed_at),
+            SessionRecord::Cancelled { cancelled_at, .. } => Some(*cancelled_at),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SessionState {
     Running,
     Completed,
     Failed,
+    Cancelled,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -458,6 +693,8 @@ pub struct SessionStatus {
     pub error: Option<String>,
     pub trace_available: bool,
     pub requires_manual: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -482,6 +719,11 @@ pub struct SessionEvent {
 }
 
 impl SessionEvent {
+    /// Whether no further events follow this one.
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self.kind, SessionEventKind::Started)
+    }
+
     pub fn started() -> Self {
         Self {
             kind: SessionEventKind::Started,
@@ -512,6 +754,16 @@ impl SessionEvent {
         }
     }
 
+    pub fn cancelled() -> Self {
+        Self {
+            kind: SessionEventKind::Cancelled,
+            message: Some("session cancelled".into()),
+            summary: None,
+            trace_available: Some(false),
+            requires_manual: Some(false),
+        }
+    }
+
     pub fn into_sse_event(self) -> Event {
         let data = serde_json::to_string(&self).unwrap_or_else(|_| {
             serde_json::json!({
@@ -531,6 +783,7 @@ pub enum SessionEventKind {
     Started,
     Completed,
     Error,
+    Cancelled,
 }
 
 impl SessionEventKind {
@@ -539,6 +792,7 @@ impl SessionEventKind {
             SessionEventKind::Started => "started",
             SessionEventKind::Completed => "completed",
             SessionEventKind::Error => "error",
+            SessionEventKind::Cancelled => "cancelled",
         }
     }
 }
@@ -569,16 +823,3 @@ impl SessionRequest {
         self
     }
 }
-
-fn ensure_context7_prefix(query: &str) -> String {
-    const PREFIX: &str = "use context7";
-    let trimmed = query.trim_start();
-
-    if trimmed.to_ascii_lowercase().starts_with(PREFIX) {
-        query.to_string()
-    } else if trimmed.is_empty() {
-        PREFIX.to_string()
-    } else {
-        format!("{PREFIX} {query}")
-    }
-}
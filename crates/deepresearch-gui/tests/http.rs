@@ -3,9 +3,12 @@ use std::path::PathBuf;
 use axum_test::TestServer;
 use deepresearch_gui::config::{AppConfig, StorageBackend};
 use deepresearch_gui::routes::build_router;
-use deepresearch_gui::state::AppState;
+use deepresearch_gui::state::{AppState, SessionRequest};
+use futures::StreamExt;
 use serde_json::json;
+use tokio::net::TcpListener;
 use tokio::time::{Duration, sleep, timeout};
+use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
 
 fn base_config() -> AppConfig {
     AppConfig {
@@ -18,6 +21,9 @@ fn base_config() -> AppConfig {
         storage: StorageBackend::InMemory,
         session_namespace: None,
         otel_endpoint: None,
+        session_ttl_secs: 3600,
+        admin_token: None,
+        log_dir: PathBuf::from("data/logs"),
     }
 }
 
@@ -43,6 +49,10 @@ async fn readiness_requires_gui_flag() {
 
     let response = enabled_server.get("/health/ready").await;
     assert_eq!(response.status_code(), 200);
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(body["checks"]["storage_ok"], json!(true));
+    assert_eq!(body["checks"]["retriever_ok"], json!(true));
+    assert!(body["checks"]["sandbox_ok"].is_null());
 }
 
 #[tokio::test]
@@ -175,3 +185,285 @@ async fn session_stream_reports_completion() {
     assert!(trace_payload["critic"].is_object());
     assert!(trace_payload["requires_manual"].is_boolean());
 }
+
+#[tokio::test]
+async fn session_websocket_delivers_completed_event() {
+    let mut config = base_config();
+    config.gui_enabled = true;
+
+    let state = AppState::try_new(&config)
+        .await
+        .expect("state initialization failed");
+    let session_id = state
+        .session_service()
+        .start_session(SessionRequest::new("How ready is the roadmap?"))
+        .await
+        .expect("session start");
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    let router = build_router(state);
+    tokio::spawn(async move {
+        axum::serve(listener, router.into_make_service())
+            .await
+            .expect("serve");
+    });
+
+    let url = format!("ws://{addr}/api/sessions/{session_id}/ws");
+    let (mut socket, _) = connect_async(url).await.expect("websocket handshake");
+
+    let completed = timeout(Duration::from_secs(5), async {
+        while let Some(message) = socket.next().await {
+            if let WsMessage::Text(text) = message.expect("websocket frame") {
+                let event: serde_json::Value = serde_json::from_str(&text).expect("event json");
+                if event["kind"] == "completed" {
+                    return event;
+                }
+            }
+        }
+        panic!("websocket closed before the completed event");
+    })
+    .await
+    .expect("session did not complete in time");
+
+    assert!(
+        completed["summary"]
+            .as_str()
+            .is_some_and(|summary| !summary.is_empty()),
+        "completed event missing summary: {completed}"
+    );
+    assert!(completed["requires_manual"].is_boolean());
+}
+
+#[tokio::test]
+async fn running_session_can_be_cancelled() {
+    let mut config = base_config();
+    config.gui_enabled = true;
+
+    let state = AppState::try_new(&config)
+        .await
+        .expect("state initialization failed");
+    let server = TestServer::new(build_router(state)).unwrap();
+
+    let response = server
+        .post("/api/sessions")
+        .json(&json!({ "query": "Can this session be stopped?" }))
+        .await;
+    assert_eq!(response.status_code(), 202);
+    let session_id = response.json::<serde_json::Value>()["session_id"]
+        .as_str()
+        .expect("session id missing")
+        .to_string();
+
+    let status_path = format!("/api/sessions/{session_id}");
+    let response = server.delete(&status_path).await;
+    assert_eq!(response.status_code(), 202);
+    assert_eq!(response.json::<serde_json::Value>()["cancelled"], true);
+
+    let status = timeout(Duration::from_secs(5), async {
+        loop {
+            let payload = server.get(&status_path).await.json::<serde_json::Value>();
+            if payload["state"] != "running" {
+                return payload;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("session did not settle in time");
+    assert_eq!(status["state"], "cancelled");
+
+    let stream = server
+        .get(&format!("/api/sessions/{session_id}/stream"))
+        .await
+        .text();
+    assert!(
+        stream.contains("event: cancelled"),
+        "stream missing cancelled event: {stream}"
+    );
+
+    assert_eq!(server.delete(&status_path).await.status_code(), 409);
+    assert_eq!(
+        server.delete("/api/sessions/missing").await.status_code(),
+        404
+    );
+}
+
+#[tokio::test]
+async fn completed_sessions_expire_after_ttl() {
+    let mut config = base_config();
+    config.gui_enabled = true;
+    config.session_ttl_secs = 1;
+
+    let state = AppState::try_new(&config)
+        .await
+        .expect("state initialization failed");
+    let service = state.session_service();
+    let router = build_router(state);
+    let server = TestServer::new(router).unwrap();
+
+    let response = server
+        .post("/api/sessions")
+        .json(&json!({ "query": "How long are sessions retained?" }))
+        .await;
+    assert_eq!(response.status_code(), 202);
+    let session_id = response.json::<serde_json::Value>()["session_id"]
+        .as_str()
+        .expect("session id missing")
+        .to_string();
+
+    timeout(Duration::from_secs(5), async {
+        while service.outcome(&session_id).is_none() {
+            sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("session did not complete in time");
+
+    let listing = server
+        .get("/api/sessions")
+        .await
+        .json::<serde_json::Value>();
+    let entry = listing["sessions"]
+        .as_array()
+        .and_then(|sessions| sessions.iter().find(|s| s["session_id"] == session_id))
+        .cloned()
+        .expect("session missing from listing");
+    assert!(
+        entry["expires_at"].is_u64(),
+        "expires_at missing from listing: {entry}"
+    );
+
+    sleep(Duration::from_millis(1100)).await;
+    // The background task may already have evicted the entry; either way it must be gone.
+    service.evict_expired();
+    assert!(service.status(&session_id).is_none());
+}
+
+#[tokio::test]
+async fn session_listing_is_paginated() {
+    let mut config = base_config();
+    config.gui_enabled = true;
+    config.max_concurrency = 4;
+
+    let state = AppState::try_new(&config)
+        .await
+        .expect("state initialization failed");
+    let router = build_router(state);
+    let server = TestServer::new(router).unwrap();
+
+    for session_id in ["page-a", "page-b", "page-c"] {
+        let response = server
+            .post("/api/sessions")
+            .json(&json!({ "query": "Paginated listing", "session_id": session_id }))
+            .await;
+        assert_eq!(response.status_code(), 202);
+    }
+
+    let body = server
+        .get("/api/sessions")
+        .add_query_param("page", 1)
+        .add_query_param("page_size", 2)
+        .await
+        .json::<serde_json::Value>();
+    assert_eq!(body["total_count"], json!(3));
+    assert_eq!(body["page"], json!(1));
+    assert_eq!(body["page_size"], json!(2));
+    let sessions = body["sessions"].as_array().expect("sessions array");
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0]["session_id"], "page-c");
+
+    let body = server
+        .get("/api/sessions")
+        .await
+        .json::<serde_json::Value>();
+    assert_eq!(body["page"], json!(0));
+    assert_eq!(body["page_size"], json!(50));
+    assert_eq!(body["sessions"].as_array().map(Vec::len), Some(3));
+}
+
+#[tokio::test]
+async fn audit_endpoint_requires_admin_token_and_filters() {
+    let logs = tempfile::TempDir::new().expect("temp dir");
+    let month_dir = logs.path().join("2025").join("03");
+    std::fs::create_dir_all(&month_dir).unwrap();
+    std::fs::write(
+        month_dir.join("audit.jsonl"),
+        concat!(
+            r#"{"timestamp":"2025-03-02T08:00:00+00:00","session_id":"s-1","redactions":["api_key"]}"#,
+            "\n",
+            r#"{"timestamp":"2025-03-05T08:00:00+00:00","session_id":"s-2","redactions":["secret"]}"#,
+            "\n",
+            r#"{"timestamp":"2025-03-09T08:00:00+00:00","session_id":"s-1","redactions":["bearer"]}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    let mut config = base_config();
+    config.gui_enabled = true;
+    config.auth_token = Some("user".into());
+    config.admin_token = Some("admin".into());
+    config.log_dir = logs.path().to_path_buf();
+
+    let state = AppState::try_new(&config)
+        .await
+        .expect("state initialization failed");
+    let server = TestServer::new(build_router(state)).unwrap();
+
+    let response = server
+        .get("/api/audit")
+        .add_header("authorization", "Bearer user")
+        .await;
+    assert_eq!(response.status_code(), 401);
+
+    let response = server
+        .get("/api/audit?session_id=s-1&from=2025-03-01&to=2025-03-31&page_size=1")
+        .add_header("authorization", "Bearer admin")
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(body["total_count"], 2);
+    assert_eq!(body["page"], 0);
+    assert_eq!(body["entries"].as_array().map(Vec::len), Some(1));
+    assert_eq!(body["entries"][0]["redacted_fields"], json!(["api_key"]));
+
+    let response = server
+        .get("/api/audit?from=not-a-date")
+        .add_header("authorization", "Bearer admin")
+        .await;
+    assert_eq!(response.status_code(), 400);
+}
+
+#[tokio::test]
+async fn namespaced_sessions_are_isolated() {
+    let mut config = base_config();
+    config.gui_enabled = true;
+    config.session_namespace = Some("tenant-a".into());
+
+    let state = AppState::try_new(&config)
+        .await
+        .expect("state initialization failed");
+    let router = build_router(state);
+    let server = TestServer::new(router).unwrap();
+
+    // An id that merely shares the namespace's leading characters is still namespaced.
+    let response = server
+        .post("/api/sessions")
+        .json(&json!({ "query": "Tenant scoped query", "session_id": "tenant-abc" }))
+        .await;
+    assert_eq!(response.status_code(), 202);
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(body["session_id"], "tenant-a::tenant-abc");
+
+    let response = server.get("/api/sessions").await;
+    assert_eq!(response.status_code(), 200);
+    let body = response.json::<serde_json::Value>();
+    let sessions = body["sessions"].as_array().expect("sessions array");
+    assert!(!sessions.is_empty());
+    assert!(sessions.iter().all(|session| {
+        session["session_id"]
+            .as_str()
+            .is_some_and(|id| id.starts_with("tenant-a::"))
+    }));
+}
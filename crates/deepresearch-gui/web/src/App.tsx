@@ -1,8 +1,8 @@
 import { ChangeEvent, FormEvent, useCallback, useEffect, useMemo, useRef, useState } from "react";
 
-type SessionState = "running" | "completed" | "failed";
+type SessionState = "running" | "completed" | "failed" | "cancelled";
 
-type SessionEventKind = "started" | "completed" | "error";
+type SessionEventKind = "started" | "completed" | "error" | "cancelled";
 
 type SessionEvent = {
   kind: SessionEventKind;
@@ -510,6 +510,14 @@ const App = () => {
       }
     });
 
+    source.addEventListener("cancelled", (evt) => {
+      const payload: SessionEvent = JSON.parse((evt as MessageEvent).data);
+      appendEvent(payload);
+      setSessionState("cancelled");
+      source.close();
+      refreshSessions();
+    });
+
     source.addEventListener("error", (evt) => {
       const payload = safeParseEvent(evt);
       appendEvent(payload);